@@ -1,5 +1,10 @@
-use criterion::{criterion_group, criterion_main, Criterion};
-use poly2tri_rs::{Point, SweeperBuilder};
+// generated inputs come from `datasets`, run with `cargo bench --features datasets`
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use poly2tri_rs::{
+    datasets, DelaunayFlag, FrontBackend, LegalizeOrder, LegalizeStrategy, Point, SweepConfig,
+    SweeperBuilder,
+};
+use rand::{Rng, SeedableRng};
 
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("bench_100", |b| {
@@ -39,11 +44,329 @@ fn criterion_benchmark(c: &mut Criterion) {
             let _result = sweeper.triangulate();
         })
     });
+
+    // wide and flat input keeps a long advancing front, most time is spent on
+    // walking the front, so it shows the cost of node lookups.
+    c.bench_function("bench_wide_front_10k", |b| {
+        let points = wide_points(10_000);
+        b.iter(|| {
+            let sweeper = SweeperBuilder::new(vec![
+                Point::new(-10., -10.),
+                Point::new(10010., -10.),
+                Point::new(10010., 110.),
+                Point::new(-10., 110.),
+            ])
+            .add_steiner_points(points.clone())
+            .build();
+            let _result = sweeper.triangulate();
+        })
+    });
+}
+
+fn datasets() -> [(&'static str, Vec<Point>, Vec<Point>); 3] {
+    [
+        (
+            "bird",
+            parse_points(include_str!("../test_data/bird.dat")),
+            vec![],
+        ),
+        (
+            "nazca_heron",
+            parse_points(include_str!("../test_data/nazca_heron.dat")),
+            vec![],
+        ),
+        (
+            "wide_front_10k",
+            vec![
+                Point::new(-10., -10.),
+                Point::new(10010., -10.),
+                Point::new(10010., 110.),
+                Point::new(-10., 110.),
+            ],
+            wide_points(10_000),
+        ),
+    ]
+}
+
+fn front_backend_benchmark(c: &mut Criterion) {
+    let datasets = datasets();
+    let mut group = c.benchmark_group("front_backend");
+    for (name, polyline, steiner_points) in datasets.iter() {
+        for backend in [
+            FrontBackend::Vec,
+            FrontBackend::BTree,
+            FrontBackend::Chunked,
+        ] {
+            let id = BenchmarkId::new(format!("{backend:?}"), name);
+            group.bench_with_input(id, &backend, |b, backend| {
+                b.iter(|| {
+                    let sweeper = SweeperBuilder::new(polyline.clone())
+                        .add_steiner_points(steiner_points.clone())
+                        .with_config(SweepConfig::default().front_backend(*backend))
+                        .build();
+                    let _result = sweeper.triangulate();
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn legalize_strategy_benchmark(c: &mut Criterion) {
+    let datasets = datasets();
+    let mut group = c.benchmark_group("legalize_strategy");
+    for (name, polyline, steiner_points) in datasets.iter() {
+        for order in [LegalizeOrder::Lifo, LegalizeOrder::Fifo] {
+            for flag in [
+                DelaunayFlag::Flipped,
+                DelaunayFlag::Checked,
+                DelaunayFlag::Off,
+                DelaunayFlag::Tick,
+            ] {
+                let strategy = LegalizeStrategy::default().order(order).delaunay_flag(flag);
+                let id = BenchmarkId::new(format!("{order:?}_{flag:?}"), name);
+                group.bench_with_input(id, &strategy, |b, strategy| {
+                    b.iter(|| {
+                        let sweeper = SweeperBuilder::new(polyline.clone())
+                            .add_steiner_points(steiner_points.clone())
+                            .with_config(SweepConfig::default().legalize(*strategy))
+                            .build();
+                        let _result = sweeper.triangulate();
+                    })
+                });
+            }
+        }
+    }
+    group.finish();
+}
+
+// panel with a grid of octagon perforations, time per hole should stay flat as
+// the count grows
+fn holes_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("holes");
+    for side in [16, 32, 64] {
+        let (outer, holes) = perforated_panel(side);
+        group.throughput(Throughput::Elements(holes.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("add_holes", holes.len()),
+            &holes,
+            |b, holes| b.iter(|| SweeperBuilder::new(outer.clone()).add_holes(holes.clone())),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("triangulate", holes.len()),
+            &holes,
+            |b, holes| {
+                b.iter(|| {
+                    let sweeper = SweeperBuilder::new(outer.clone())
+                        .add_holes(holes.clone())
+                        .build();
+                    let _result = sweeper.triangulate();
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+// delaunay of random points, time per point should grow like log n. Predicate
+// mode is a build feature, compare runs with and without `--features exact_orient`.
+fn uniform_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("uniform");
+    group.sample_size(10);
+    for count in [1_000, 10_000, 100_000, 1_000_000] {
+        let builder = datasets::uniform(count, 0);
+        group.throughput(Throughput::Elements(count as u64));
+        for backend in [
+            FrontBackend::Vec,
+            FrontBackend::BTree,
+            FrontBackend::Chunked,
+        ] {
+            let id = BenchmarkId::new(format!("{backend:?}"), count);
+            group.bench_with_input(id, &backend, |b, backend| {
+                b.iter(|| {
+                    let sweeper = builder
+                        .clone()
+                        .with_config(SweepConfig::default().front_backend(*backend))
+                        .build();
+                    let _result = sweeper.triangulate();
+                })
+            });
+        }
+    }
+    group.finish();
 }
 
-criterion_group!(benches, criterion_benchmark);
+// constrained workloads, each one stresses a different part of the sweep
+fn workload_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("workload");
+    let workloads = [
+        ("contour_10k", datasets::contour(10_000, 0)),
+        ("perforated_32", datasets::perforated(32)),
+        ("spiral_64", datasets::spiral(64)),
+    ];
+    for (name, builder) in workloads.iter() {
+        for backend in [
+            FrontBackend::Vec,
+            FrontBackend::BTree,
+            FrontBackend::Chunked,
+        ] {
+            let id = BenchmarkId::new(format!("{backend:?}"), name);
+            group.bench_with_input(id, &backend, |b, backend| {
+                b.iter(|| {
+                    let sweeper = builder
+                        .clone()
+                        .with_config(SweepConfig::default().front_backend(*backend))
+                        .build();
+                    let _result = sweeper.triangulate();
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+// particles jittering in place, each frame moves 1% of them. Compares moving
+// each one by move_point, moving all by update_positions, and a new sweep.
+fn kinetic_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("kinetic");
+    let outer = vec![
+        Point::new(-10., -10.),
+        Point::new(1010., -10.),
+        Point::new(1010., 1010.),
+        Point::new(-10., 1010.),
+    ];
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    let particles = (0..10_000)
+        .map(|_| Point::new(rng.gen_range(0.0..1000.), rng.gen_range(0.0..1000.)))
+        .collect::<Vec<_>>();
+    let build = |particles: &[Point]| {
+        SweeperBuilder::new(outer.clone())
+            .add_steiner_points(particles.iter().copied())
+            .build()
+            .triangulate()
+    };
+    // back and forth each frame, the mesh stays about the same
+    let frame = |frame: usize| {
+        let offset = (frame % 2) as f64 * 0.3;
+        (0..particles.len())
+            .step_by(100)
+            .map(|index| {
+                let p = particles[index];
+                (index, Point::new(p.x + offset, p.y + offset))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    group.bench_function("move_point_10k", |b| {
+        let mut triangles = build(&particles);
+        let mut count = 0;
+        b.iter(|| {
+            for (index, p) in frame(count) {
+                let _ = triangles.move_point(outer.len() + index, p);
+            }
+            count += 1;
+        })
+    });
+    group.bench_function("update_positions_10k", |b| {
+        let mut triangles = build(&particles);
+        let mut positions = outer
+            .iter()
+            .chain(particles.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        let mut count = 0;
+        b.iter(|| {
+            for (index, p) in frame(count) {
+                positions[outer.len() + index] = p;
+            }
+            let _ = triangles.update_positions(&positions);
+            count += 1;
+        })
+    });
+    group.bench_function("rebuild_10k", |b| {
+        let mut moved = particles.clone();
+        let mut count = 0;
+        b.iter(|| {
+            for (index, p) in frame(count) {
+                moved[index] = p;
+            }
+            let _result = build(&moved);
+            count += 1;
+        })
+    });
+    group.finish();
+}
+
+// p2t and spade on the same inputs, build with `--features compare`
+#[cfg(feature = "compare")]
+fn compare_benchmark(c: &mut Criterion) {
+    use poly2tri_rs::compare;
+
+    let mut group = c.benchmark_group("compare");
+    group.sample_size(10);
+    let workloads = [
+        ("uniform_100k", datasets::uniform(100_000, 0)),
+        ("contour_10k", datasets::contour(10_000, 0)),
+        ("spiral_64", datasets::spiral(64)),
+    ];
+    for (name, builder) in workloads.iter() {
+        group.bench_with_input(BenchmarkId::new("p2t", name), builder, |b, builder| {
+            b.iter(|| builder.clone().build().triangulate())
+        });
+        group.bench_with_input(BenchmarkId::new("spade", name), builder, |b, builder| {
+            b.iter(|| compare::spade_cdt(builder).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    criterion_benchmark,
+    front_backend_benchmark,
+    legalize_strategy_benchmark,
+    holes_benchmark,
+    uniform_benchmark,
+    workload_benchmark,
+    kinetic_benchmark
+);
+#[cfg(feature = "compare")]
+criterion_group!(compare_benches, compare_benchmark);
+
+#[cfg(feature = "compare")]
+criterion_main!(benches, compare_benches);
+#[cfg(not(feature = "compare"))]
 criterion_main!(benches);
 
+fn wide_points(count: usize) -> Vec<Point> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    (0..count)
+        .map(|_| Point::new(rng.gen_range(0.0..10000.), rng.gen_range(0.0..100.)))
+        .collect()
+}
+
+fn perforated_panel(side: usize) -> (Vec<Point>, Vec<Vec<Point>>) {
+    let size = side as f64 * 10.;
+    let outer = vec![
+        Point::new(0., 0.),
+        Point::new(size, 0.),
+        Point::new(size, size),
+        Point::new(0., size),
+    ];
+    let holes = (0..side * side)
+        .map(|i| {
+            let (x, y) = ((i % side) as f64 * 10. + 5., (i / side) as f64 * 10. + 5.);
+            (0..8)
+                .map(|k| {
+                    let angle = k as f64 * std::f64::consts::TAU / 8.;
+                    Point::new(x + 2. * angle.cos(), y + 2. * angle.sin())
+                })
+                .collect()
+        })
+        .collect();
+    (outer, holes)
+}
+
 fn parse_points(serialized: &str) -> Vec<Point> {
     let mut points = vec![];
     for line in serialized.lines() {
@@ -0,0 +1,4 @@
+fn main() {
+    // link flags for the node addon
+    napi_build::setup();
+}
@@ -0,0 +1,138 @@
+//! Node.js bindings of poly2tri-rs, e.g. for map tile pipelines. Same API as the
+//! python module. Build the addon with the napi cli.
+//!
+//! Points are flat `Float64Array`s of `x, y` pairs, read in place from JS memory,
+//! results are typed arrays handed over to JS without copying.
+//!
+//! ```js
+//! const { Builder, triangulate } = require('poly2tri-rs')
+//!
+//! const outer = new Float64Array([0, 0, 10, 0, 10, 10, 0, 10])
+//! const hole = new Float64Array([4, 4, 4, 6, 6, 6, 6, 4])
+//! // 3 vertex indices per triangle, vertices are outer, holes, then steiner points
+//! const indices = triangulate(outer, [hole])
+//!
+//! const builder = new Builder(outer)
+//! builder.addSteinerPoints(new Float64Array([2, 2]))
+//! const mesh = builder.triangulate()
+//! mesh.positions(), mesh.indices()
+//! ```
+use napi::bindgen_prelude::{Float64Array, Uint32Array};
+use napi::{Error, Result};
+use napi_derive::napi;
+
+use poly2tri_rs::{Point, SweeperBuilder, Triangles};
+
+/// Points of flat `x, y` pairs
+fn to_points(array: &[f64]) -> Result<Vec<Point>> {
+    if !array.len().is_multiple_of(2) {
+        return Err(Error::from_reason(format!(
+            "expected x, y pairs, got {} values",
+            array.len()
+        )));
+    }
+    Ok(array
+        .chunks_exact(2)
+        .map(|xy| Point::new(xy[0], xy[1]))
+        .collect())
+}
+
+/// Builder for a triangulation, wraps [`SweeperBuilder`]
+#[napi(js_name = "Builder")]
+pub struct NodeBuilder {
+    /// taken while a builder method runs, as those consume the builder
+    builder: Option<SweeperBuilder>,
+}
+
+impl NodeBuilder {
+    fn update(&mut self, f: impl FnOnce(SweeperBuilder) -> SweeperBuilder) {
+        self.builder = self.builder.take().map(f);
+    }
+}
+
+#[napi]
+impl NodeBuilder {
+    #[napi(constructor)]
+    pub fn new(outer: Float64Array) -> Result<Self> {
+        Ok(Self {
+            builder: Some(SweeperBuilder::new(to_points(&outer)?)),
+        })
+    }
+
+    #[napi]
+    pub fn add_hole(&mut self, hole: Float64Array) -> Result<()> {
+        let hole = to_points(&hole)?;
+        self.update(|builder| builder.add_hole(hole));
+        Ok(())
+    }
+
+    #[napi]
+    pub fn add_steiner_points(&mut self, points: Float64Array) -> Result<()> {
+        let points = to_points(&points)?;
+        self.update(|builder| builder.add_steiner_points(points));
+        Ok(())
+    }
+
+    /// Triangulate, throws listing input problems, the builder is kept
+    #[napi]
+    pub fn triangulate(&self) -> Result<NodeTriangles> {
+        let builder = self.builder.clone().expect("builder is set");
+        let sweeper = builder
+            .try_build()
+            .map_err(|report| Error::from_reason(report.to_string()))?;
+        Ok(NodeTriangles::new(&sweeper.triangulate()))
+    }
+}
+
+/// Result of a triangulation
+#[napi(js_name = "Triangles")]
+pub struct NodeTriangles {
+    positions: Vec<f64>,
+    indices: Vec<u32>,
+}
+
+impl NodeTriangles {
+    fn new(triangles: &Triangles) -> Self {
+        let mut indices = vec![0; triangles.index_count()];
+        triangles.write_indices_into(&mut indices);
+        Self {
+            positions: triangles.vertex_positions().concat(),
+            indices,
+        }
+    }
+}
+
+#[napi]
+impl NodeTriangles {
+    /// vertex positions as `x, y` pairs
+    #[napi]
+    pub fn positions(&self) -> Float64Array {
+        Float64Array::new(self.positions.clone())
+    }
+
+    /// 3 vertex indices per triangle
+    #[napi]
+    pub fn indices(&self) -> Uint32Array {
+        Uint32Array::new(self.indices.clone())
+    }
+}
+
+/// Triangulate `outer` with `holes` and `steiner_points`, returns 3 vertex indices
+/// per triangle
+#[napi]
+pub fn triangulate(
+    outer: Float64Array,
+    holes: Option<Vec<Float64Array>>,
+    steiner_points: Option<Float64Array>,
+) -> Result<Uint32Array> {
+    let mut builder = NodeBuilder::new(outer)?;
+    for hole in holes.unwrap_or_default() {
+        builder.add_hole(hole)?;
+    }
+    if let Some(points) = steiner_points {
+        builder.add_steiner_points(points)?;
+    }
+    let triangles = builder.triangulate()?;
+    // the result is not used afterwards, hand its buffer over
+    Ok(Uint32Array::new(triangles.indices))
+}
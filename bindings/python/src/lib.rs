@@ -0,0 +1,152 @@
+//! Python bindings of poly2tri-rs. Build the module with maturin, contour,
+//! holes and steiner points are numpy `(N, 2)` float arrays.
+//!
+//! ```python
+//! import numpy as np
+//! import poly2tri_rs
+//!
+//! outer = np.array([[0, 0], [10, 0], [10, 10], [0, 10]], dtype=np.float64)
+//! hole = np.array([[4, 4], [4, 6], [6, 6], [6, 4]], dtype=np.float64)
+//! # (M, 3) vertex indices, vertices are outer, holes, then steiner points
+//! indices = poly2tri_rs.triangulate(outer, holes=[hole])
+//!
+//! builder = poly2tri_rs.Builder(outer)
+//! builder.add_steiner_points(np.array([[2, 2]], dtype=np.float64))
+//! mesh = builder.triangulate()
+//! mesh.positions(), mesh.indices()
+//! ```
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2, PyReadonlyArray2, PyUntypedArrayMethods};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use poly2tri_rs::{Point, SweeperBuilder, Triangles};
+
+/// Points of an `(N, 2)` array
+fn to_points(array: &PyReadonlyArray2<'_, f64>) -> PyResult<Vec<Point>> {
+    if array.shape()[1] != 2 {
+        return Err(PyValueError::new_err(format!(
+            "expected an (N, 2) array, got {:?}",
+            array.shape()
+        )));
+    }
+    Ok(array
+        .as_array()
+        .rows()
+        .into_iter()
+        .map(|row| Point::new(row[0], row[1]))
+        .collect())
+}
+
+/// Builder for a triangulation, wraps [`SweeperBuilder`]
+#[pyclass(name = "Builder", module = "poly2tri_rs")]
+struct PyBuilder {
+    /// taken while a builder method runs, as those consume the builder
+    builder: Option<SweeperBuilder>,
+}
+
+impl PyBuilder {
+    fn update(&mut self, f: impl FnOnce(SweeperBuilder) -> SweeperBuilder) {
+        self.builder = self.builder.take().map(f);
+    }
+}
+
+#[pymethods]
+impl PyBuilder {
+    #[new]
+    fn new(outer: PyReadonlyArray2<'_, f64>) -> PyResult<Self> {
+        Ok(Self {
+            builder: Some(SweeperBuilder::new(to_points(&outer)?)),
+        })
+    }
+
+    fn add_hole(&mut self, hole: PyReadonlyArray2<'_, f64>) -> PyResult<()> {
+        let hole = to_points(&hole)?;
+        self.update(|builder| builder.add_hole(hole));
+        Ok(())
+    }
+
+    fn add_steiner_points(&mut self, points: PyReadonlyArray2<'_, f64>) -> PyResult<()> {
+        let points = to_points(&points)?;
+        self.update(|builder| builder.add_steiner_points(points));
+        Ok(())
+    }
+
+    /// Triangulate, raises ValueError listing input problems, the builder is kept
+    fn triangulate(&self) -> PyResult<PyTriangles> {
+        let builder = self.builder.clone().expect("builder is set");
+        let sweeper = builder
+            .try_build()
+            .map_err(|report| PyValueError::new_err(report.to_string()))?;
+        Ok(PyTriangles::new(&sweeper.triangulate()))
+    }
+}
+
+/// Result of a triangulation
+#[pyclass(name = "Triangles", module = "poly2tri_rs")]
+struct PyTriangles {
+    positions: Vec<[f64; 2]>,
+    indices: Vec<u32>,
+}
+
+impl PyTriangles {
+    fn new(triangles: &Triangles) -> Self {
+        let mut indices = vec![0; triangles.index_count()];
+        triangles.write_indices_into(&mut indices);
+        Self {
+            positions: triangles.vertex_positions(),
+            indices,
+        }
+    }
+}
+
+#[pymethods]
+impl PyTriangles {
+    /// `(N, 2)` float array of vertex positions
+    fn positions<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        let flat = self.positions.iter().flatten().copied().collect::<Vec<_>>();
+        Array2::from_shape_vec((self.positions.len(), 2), flat)
+            .expect("shape matches")
+            .into_pyarray(py)
+    }
+
+    /// `(M, 3)` vertex indices of triangles
+    fn indices<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<u32>> {
+        Array2::from_shape_vec((self.indices.len() / 3, 3), self.indices.clone())
+            .expect("shape matches")
+            .into_pyarray(py)
+    }
+
+    fn __len__(&self) -> usize {
+        self.indices.len() / 3
+    }
+}
+
+/// Triangulate `outer` with `holes` and `steiner_points`, returns `(M, 3)` vertex
+/// indices
+#[pyfunction]
+#[pyo3(signature = (outer, holes = vec![], steiner_points = None))]
+fn triangulate<'py>(
+    py: Python<'py>,
+    outer: PyReadonlyArray2<'py, f64>,
+    holes: Vec<PyReadonlyArray2<'py, f64>>,
+    steiner_points: Option<PyReadonlyArray2<'py, f64>>,
+) -> PyResult<Bound<'py, PyArray2<u32>>> {
+    let mut builder = PyBuilder::new(outer)?;
+    for hole in holes {
+        builder.add_hole(hole)?;
+    }
+    if let Some(points) = steiner_points {
+        builder.add_steiner_points(points)?;
+    }
+    Ok(builder.triangulate()?.indices(py))
+}
+
+#[pymodule]
+#[pyo3(name = "poly2tri_rs")]
+fn init(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyBuilder>()?;
+    module.add_class::<PyTriangles>()?;
+    module.add_function(wrap_pyfunction!(triangulate, module)?)?;
+    Ok(())
+}
@@ -160,7 +160,6 @@ impl DrawObserver {
         #[derive(Template)] // this will generate the code...
         #[template(path = "draw_template.html")] // using the template in this path, relative
                                                  // to the `templates` dir in the crate root
-
         struct DrawTemplate<'a> {
             // the name of the struct can be anything
             frames: &'a [String],
@@ -332,7 +331,7 @@ impl DrawObserver {
                     doc.append(circle((x, y), point_r, "red", "clear"));
                 }
 
-                for p_id in edges {
+                for p_id in context.points.edges(edges) {
                     let p_point = context.points.get_point(p_id).unwrap();
                     let p = map.map_point(p_point.x, p_point.y);
                     let q = map.map_point(point.x, point.y);
@@ -382,8 +381,11 @@ impl DrawObserver {
                         } else {
                             color
                         };
-                        let color = if t.is_delaunay(idx) { "black" } else { color };
-                        color
+                        if t.is_delaunay(idx) {
+                            "black"
+                        } else {
+                            color
+                        }
                     };
 
                     doc.append(line(p0_drifted, p1_drifted, color_for_idx(2)));
@@ -451,7 +453,7 @@ impl DrawObserver {
         }
 
         let mut draw_illegal_triangle = |tid: TriangleId, fill_color: &str, border_color: &str| {
-            let t = tid.get(&context.triangles);
+            let t = tid.get(context.triangles);
             let p0 = context.points.get_point(t.points[0]).unwrap();
             let p1 = context.points.get_point(t.points[1]).unwrap();
             let p2 = context.points.get_point(t.points[2]).unwrap();
@@ -467,8 +469,8 @@ impl DrawObserver {
 
         let illegal_pairs = Sweeper::illegal_triangles(context);
         for (from_tid, to_tid) in illegal_pairs {
-            let from_t = from_tid.get(&context.triangles);
-            let to_t = to_tid.get(&context.triangles);
+            let from_t = from_tid.get(context.triangles);
+            let to_t = to_tid.get(context.triangles);
 
             let (from_i, to_i) = from_t.common_edge_index(to_t).unwrap();
 
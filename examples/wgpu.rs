@@ -0,0 +1,173 @@
+//! Triangulate a polygon, upload it with `gpu_buffers` and draw it into an
+//! offscreen texture, then count covered pixels.
+//!
+//! cargo run --example wgpu --features wgpu -- test_data/bird.dat
+use clap::Parser;
+use poly2tri_rs::{
+    loader::{Loader, PlainFileLoader},
+    VertexWriter,
+};
+
+const SIZE: u32 = 256;
+
+const SHADER: &str = r#"
+@vertex
+fn vs_main(@location(1) uv: vec2<f32>) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(uv * 1.8 - 0.9, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0, 1.0, 1.0, 1.0);
+}
+"#;
+
+#[derive(Parser)]
+struct Args {
+    /// polygon file, same format as test_data
+    #[arg(default_value = "test_data/bird.dat")]
+    path: String,
+}
+
+fn main() {
+    let args = Args::parse();
+    let builder = PlainFileLoader::default()
+        .load(&args.path)
+        .expect("load polygon file");
+    let triangles = builder.build().triangulate();
+
+    let instance = wgpu::Instance::default();
+    let Some(adapter) =
+        pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+    else {
+        println!("no gpu adapter available");
+        return;
+    };
+    let (device, queue) = pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: None,
+            required_features: wgpu::Features::empty(),
+            required_limits: wgpu::Limits::downlevel_defaults(),
+        },
+        None,
+    ))
+    .expect("request device");
+
+    // uv maps the bounding box to [0, 1], the shader takes it as position
+    let buffers = VertexWriter::new()
+        .uv(true)
+        .write(&triangles)
+        .gpu_buffers(&device);
+    let attributes = buffers.vertex_attributes();
+
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+    });
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: None,
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: buffers.array_stride(),
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &attributes,
+            }],
+        },
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        multiview: None,
+    });
+
+    let output = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: (SIZE * SIZE * 4) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&pipeline);
+        pass.set_vertex_buffer(0, buffers.vertex.slice(..));
+        pass.set_index_buffer(buffers.index.slice(..), wgpu::IndexFormat::Uint32);
+        pass.draw_indexed(0..buffers.index_count, 0, 0..1);
+    }
+    encoder.copy_texture_to_buffer(
+        wgpu::ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::ImageCopyBuffer {
+            buffer: &output,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(SIZE * 4),
+                rows_per_image: Some(SIZE),
+            },
+        },
+        texture.size(),
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let slice = output.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |result| result.expect("map output"));
+    device.poll(wgpu::Maintain::Wait);
+    let covered = slice
+        .get_mapped_range()
+        .chunks_exact(4)
+        .filter(|pixel| pixel[0] > 0)
+        .count();
+
+    println!(
+        "{} triangles, {} of {} pixels covered",
+        buffers.index_count / 3,
+        covered,
+        SIZE * SIZE
+    );
+}
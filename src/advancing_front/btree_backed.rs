@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+use std::ops::Bound::{Excluded, Unbounded};
+
+use super::*;
+
+/// Front stored in a btree, insert and delete are O(log n) regardless of front
+/// width, while each lookup walks the tree. Index is unused, nodes are always
+/// resolved by key.
+pub struct BTreeFront {
+    nodes: BTreeMap<PointKey, NodeInner>,
+    #[cfg(test)]
+    stats: CacheStats,
+}
+
+fn raw((key, node): (&PointKey, &NodeInner)) -> RawNode {
+    RawNode {
+        index: 0,
+        key: *key,
+        node: *node,
+    }
+}
+
+impl BTreeFront {
+    fn lookup(&self, key: PointKey) -> Option<RawNode> {
+        #[cfg(test)]
+        self.stats.miss();
+        self.nodes.get_key_value(&key).map(raw)
+    }
+}
+
+impl FrontStore for BTreeFront {
+    /// btree grows by nodes, capacity is not used
+    fn new(nodes: [(PointKey, NodeInner); 3], _capacity: Option<usize>) -> Self {
+        Self {
+            nodes: nodes.into_iter().collect(),
+            #[cfg(test)]
+            stats: Default::default(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn insert(&mut self, key: PointKey, node: NodeInner) -> usize {
+        self.nodes.insert(key, node);
+        0
+    }
+
+    unsafe fn update_and_delete(
+        &mut self,
+        update: NodeId,
+        triangle_id: TriangleId,
+        delete: NodeId,
+    ) {
+        let node = self.nodes.get_mut(&PointKey(update.point)).unwrap();
+        debug_assert!(node.point_id == update.point_id, "point_id mismatch");
+        node.triangle = triangle_id;
+
+        self.nodes.remove(&PointKey(delete.point));
+    }
+
+    fn nth(&self, n: usize) -> Option<RawNode> {
+        self.nodes.iter().nth(n).map(raw)
+    }
+
+    fn next(&self, _index: usize, key: PointKey) -> Option<RawNode> {
+        self.nodes.range((Excluded(key), Unbounded)).next().map(raw)
+    }
+
+    fn prev(&self, _index: usize, key: PointKey) -> Option<RawNode> {
+        self.nodes.range(..key).next_back().map(raw)
+    }
+
+    fn locate(&self, key: PointKey) -> Option<RawNode> {
+        self.nodes.range(..=key).next_back().map(raw)
+    }
+
+    fn get(&self, key: PointKey) -> Option<RawNode> {
+        self.lookup(key)
+    }
+
+    fn get_and_cache(&mut self, key: PointKey) -> Option<RawNode> {
+        self.lookup(key)
+    }
+
+    fn get_by_id(&self, node_id: NodeId) -> Option<RawNode> {
+        self.lookup(PointKey(node_id.point))
+    }
+
+    fn get_by_id_and_cache(&mut self, node_id: NodeId) -> Option<RawNode> {
+        self.lookup(PointKey(node_id.point))
+    }
+
+    fn next_of_id(&self, node_id: NodeId) -> Option<RawNode> {
+        self.next(0, PointKey(node_id.point))
+    }
+
+    fn prev_of_id(&self, node_id: NodeId) -> Option<RawNode> {
+        self.prev(0, PointKey(node_id.point))
+    }
+
+    fn update_triangle(&mut self, key: PointKey, triangle_id: TriangleId) {
+        self.nodes.get_mut(&key).unwrap().triangle = triangle_id;
+    }
+
+    #[cfg(test)]
+    fn cache_stats(&self) -> (u64, u64) {
+        self.stats.get()
+    }
+}
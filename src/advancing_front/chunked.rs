@@ -0,0 +1,457 @@
+use super::*;
+
+/// Chunk never shrinks below this target length when splitting
+const MIN_CHUNK_LEN: usize = 32;
+
+/// Index of a node is packed as `chunk << SLOT_BITS | slot`, slots are stable
+/// until the chunk is compacted or split, so index hints survive deletes.
+const SLOT_BITS: u32 = usize::BITS / 2;
+const SLOT_MASK: usize = (1 << SLOT_BITS) - 1;
+
+fn pack(chunk: usize, slot: usize) -> usize {
+    (chunk << SLOT_BITS) | slot
+}
+
+fn unpack(index: usize) -> (usize, usize) {
+    (index >> SLOT_BITS, index & SLOT_MASK)
+}
+
+/// Front stored in sorted chunks.
+///
+/// Each chunk is a small sorted vec, so insert and delete only shift inside one
+/// chunk, and the chunk count stays around √n. This wins over the flat vec when
+/// the front is huge, e.g. wide and flat datasets.
+/// - deletions only mark the entry as tombstone, the chunk is compacted when
+///   more than half of it is dead.
+/// - insertions reuse an adjacent tombstone slot when possible, otherwise
+///   shift inside the chunk, and split the chunk when it grows too long.
+pub struct ChunkedFront {
+    chunks: Vec<Chunk>,
+    /// live node count
+    len: usize,
+    access_cache: Option<(PointKey, usize)>,
+    #[cfg(test)]
+    stats: CacheStats,
+}
+
+struct Entry {
+    key: PointKey,
+    node: NodeInner,
+    deleted: bool,
+}
+
+impl Entry {
+    fn new(key: PointKey, node: NodeInner) -> Self {
+        Self {
+            key,
+            node,
+            deleted: false,
+        }
+    }
+
+    fn to_raw(&self, index: usize) -> RawNode {
+        RawNode {
+            index,
+            key: self.key,
+            node: self.node,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Chunk {
+    entries: Vec<Entry>,
+    /// tombstone count in entries
+    dead: usize,
+}
+
+impl Chunk {
+    fn first_key(&self) -> PointKey {
+        self.entries[0].key
+    }
+
+    fn live(&self) -> usize {
+        self.entries.len() - self.dead
+    }
+
+    /// drop all tombstones
+    fn compact(&mut self) {
+        self.entries.retain(|e| !e.deleted);
+        self.dead = 0;
+    }
+}
+
+impl ChunkedFront {
+    /// max entries one chunk holds before split, grows with √len
+    fn max_chunk_len(&self) -> usize {
+        let target = ((self.len as f64).sqrt() as usize).max(MIN_CHUNK_LEN);
+        target * 2
+    }
+
+    fn raw(&self, index: usize) -> RawNode {
+        self.entry(index).to_raw(index)
+    }
+
+    fn entry(&self, index: usize) -> &Entry {
+        let (chunk_idx, slot) = unpack(index);
+        &self.chunks[chunk_idx].entries[slot]
+    }
+
+    /// the chunk which should hold `key`
+    fn chunk_for_key(&self, key: &PointKey) -> usize {
+        self.chunks
+            .partition_point(|c| c.first_key() <= *key)
+            .saturating_sub(1)
+    }
+
+    /// Ok(index) if a live node for key exists, otherwise Err(index) of the position
+    /// key would be inserted at. The Err index may point to one past chunk's end.
+    fn search_by_key(&self, key: &PointKey) -> Result<usize, usize> {
+        let chunk_idx = self.chunk_for_key(key);
+        let chunk = &self.chunks[chunk_idx];
+        match chunk.entries.binary_search_by_key(key, |e| e.key) {
+            Ok(slot) if !chunk.entries[slot].deleted => Ok(pack(chunk_idx, slot)),
+            Ok(slot) | Err(slot) => Err(pack(chunk_idx, slot)),
+        }
+    }
+
+    fn search_by_key_with_cache(&self, key: &PointKey) -> Result<usize, usize> {
+        if let Some((cached_key, index)) = self.access_cache {
+            // check the cached node and its live neighbor
+            let candidate = match cached_key.cmp(key) {
+                Ordering::Equal => Some(index),
+                Ordering::Greater => self.prev_live(index),
+                Ordering::Less => self.next_live(index),
+            };
+            if let Some(candidate) = candidate.filter(|i| self.entry(*i).key == *key) {
+                #[cfg(test)]
+                self.stats.hit();
+                return Ok(candidate);
+            }
+        }
+
+        #[cfg(test)]
+        self.stats.miss();
+        self.search_by_key(key)
+    }
+
+    /// first live index strictly after `index`
+    fn next_live(&self, index: usize) -> Option<usize> {
+        let (chunk_idx, slot) = unpack(index);
+        self.live_at_or_after(pack(chunk_idx, slot + 1))
+    }
+
+    /// first live index at or after `index`, index's slot may be one past chunk's end
+    fn live_at_or_after(&self, index: usize) -> Option<usize> {
+        let (mut chunk_idx, mut slot) = unpack(index);
+        while let Some(chunk) = self.chunks.get(chunk_idx) {
+            if let Some(offset) = chunk.entries[slot.min(chunk.entries.len())..]
+                .iter()
+                .position(|e| !e.deleted)
+            {
+                return Some(pack(chunk_idx, slot + offset));
+            }
+            chunk_idx += 1;
+            slot = 0;
+        }
+        None
+    }
+
+    /// last live index strictly before `index`
+    fn prev_live(&self, index: usize) -> Option<usize> {
+        let (mut chunk_idx, mut end) = unpack(index);
+        loop {
+            let chunk = self.chunks.get(chunk_idx)?;
+            if let Some(slot) = chunk.entries[..end.min(chunk.entries.len())]
+                .iter()
+                .rposition(|e| !e.deleted)
+            {
+                return Some(pack(chunk_idx, slot));
+            }
+            if chunk_idx == 0 {
+                return None;
+            }
+            chunk_idx -= 1;
+            end = usize::MAX;
+        }
+    }
+
+    /// split the chunk into two halves, tombstones are dropped
+    fn split_chunk(&mut self, chunk_idx: usize) {
+        let chunk = &mut self.chunks[chunk_idx];
+        chunk.compact();
+        let tail = chunk.entries.split_off(chunk.entries.len() / 2);
+        self.chunks.insert(
+            chunk_idx + 1,
+            Chunk {
+                entries: tail,
+                dead: 0,
+            },
+        );
+    }
+
+    /// resolve node_id's latest index.
+    /// Return Err(index to insert) when the node is deleted
+    fn resolve_index_for_id(&self, node_id: NodeId) -> Result<usize, usize> {
+        let (chunk_idx, slot) = unpack(node_id.index_hint);
+        match self
+            .chunks
+            .get(chunk_idx)
+            .and_then(|chunk| chunk.entries.get(slot))
+        {
+            Some(entry) if !entry.deleted && entry.node.point_id == node_id.point_id => {
+                // index_hint match
+                Ok(node_id.index_hint)
+            }
+            _ => self.search_by_key(&PointKey(node_id.point)),
+        }
+    }
+}
+
+impl FrontStore for ChunkedFront {
+    /// chunks are sized by √len, so capacity is not used
+    fn new(nodes: [(PointKey, NodeInner); 3], _capacity: Option<usize>) -> Self {
+        let mut entries = Vec::<Entry>::with_capacity(MIN_CHUNK_LEN * 2);
+        entries.extend(nodes.into_iter().map(|(key, node)| Entry::new(key, node)));
+
+        Self {
+            chunks: vec![Chunk { entries, dead: 0 }],
+            len: 3,
+            access_cache: None,
+            #[cfg(test)]
+            stats: Default::default(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn insert(&mut self, key: PointKey, node: NodeInner) -> usize {
+        let chunk_idx = self.chunk_for_key(&key);
+        let chunk = &mut self.chunks[chunk_idx];
+        let slot = match chunk.entries.binary_search_by_key(&key, |e| e.key) {
+            Ok(slot) => {
+                let entry = &mut chunk.entries[slot];
+                if entry.deleted {
+                    entry.deleted = false;
+                    chunk.dead -= 1;
+                    self.len += 1;
+                }
+                entry.node = node;
+                slot
+            }
+            Err(slot) => {
+                self.len += 1;
+                // all entries before slot are less than key, all entries from slot are
+                // larger, so a tombstone on either side can be reused in place
+                if slot > 0 && chunk.entries[slot - 1].deleted {
+                    chunk.entries[slot - 1] = Entry::new(key, node);
+                    chunk.dead -= 1;
+                    slot - 1
+                } else if slot < chunk.entries.len() && chunk.entries[slot].deleted {
+                    chunk.entries[slot] = Entry::new(key, node);
+                    chunk.dead -= 1;
+                    slot
+                } else {
+                    chunk.entries.insert(slot, Entry::new(key, node));
+                    slot
+                }
+            }
+        };
+
+        let index = if self.chunks[chunk_idx].entries.len() > self.max_chunk_len() {
+            self.split_chunk(chunk_idx);
+            // split changed slots, search again
+            self.search_by_key(&key).unwrap()
+        } else {
+            pack(chunk_idx, slot)
+        };
+
+        self.access_cache = Some((key, index));
+        index
+    }
+
+    unsafe fn update_and_delete(
+        &mut self,
+        update: NodeId,
+        triangle_id: TriangleId,
+        delete: NodeId,
+    ) {
+        let (chunk_idx, slot) = unpack(update.index_hint);
+        let entry = &mut self.chunks[chunk_idx].entries[slot];
+        debug_assert!(entry.node.point_id == update.point_id, "point_id mismatch");
+        entry.node.triangle = triangle_id;
+
+        let (chunk_idx, slot) = unpack(delete.index_hint);
+        let chunk = &mut self.chunks[chunk_idx];
+        debug_assert!(!chunk.entries[slot].deleted);
+        debug_assert!(chunk.entries[slot].node.point_id == delete.point_id);
+        chunk.entries[slot].deleted = true;
+        chunk.dead += 1;
+        self.len -= 1;
+
+        if chunk.live() == 0 {
+            self.chunks.remove(chunk_idx);
+        } else if chunk.dead * 2 > chunk.entries.len() {
+            chunk.compact();
+        }
+
+        self.access_cache = None;
+    }
+
+    fn nth(&self, n: usize) -> Option<RawNode> {
+        let mut remain = n;
+        for (chunk_idx, chunk) in self.chunks.iter().enumerate() {
+            if remain >= chunk.live() {
+                remain -= chunk.live();
+                continue;
+            }
+            let (slot, entry) = chunk
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| !e.deleted)
+                .nth(remain)?;
+            return Some(entry.to_raw(pack(chunk_idx, slot)));
+        }
+        None
+    }
+
+    fn next(&self, index: usize, _key: PointKey) -> Option<RawNode> {
+        Some(self.raw(self.next_live(index)?))
+    }
+
+    fn prev(&self, index: usize, _key: PointKey) -> Option<RawNode> {
+        Some(self.raw(self.prev_live(index)?))
+    }
+
+    fn locate(&self, key: PointKey) -> Option<RawNode> {
+        let index = match self.search_by_key(&key) {
+            Ok(index) => index,
+            Err(index) => self.prev_live(index)?,
+        };
+        Some(self.raw(index))
+    }
+
+    fn get(&self, key: PointKey) -> Option<RawNode> {
+        let index = self.search_by_key_with_cache(&key).ok()?;
+        Some(self.raw(index))
+    }
+
+    fn get_and_cache(&mut self, key: PointKey) -> Option<RawNode> {
+        let index = self.search_by_key_with_cache(&key).ok()?;
+        // update cache
+        self.access_cache = Some((key, index));
+        Some(self.raw(index))
+    }
+
+    fn get_by_id(&self, node_id: NodeId) -> Option<RawNode> {
+        let index = self.resolve_index_for_id(node_id).ok()?;
+        Some(self.raw(index))
+    }
+
+    fn get_by_id_and_cache(&mut self, node_id: NodeId) -> Option<RawNode> {
+        let index = self.resolve_index_for_id(node_id).ok()?;
+        // update cache
+        self.access_cache = Some((PointKey(node_id.point), index));
+        Some(self.raw(index))
+    }
+
+    fn next_of_id(&self, node_id: NodeId) -> Option<RawNode> {
+        let index = match self.resolve_index_for_id(node_id) {
+            Ok(index) => self.next_live(index)?,
+            Err(index) => self.live_at_or_after(index)?,
+        };
+        Some(self.raw(index))
+    }
+
+    fn prev_of_id(&self, node_id: NodeId) -> Option<RawNode> {
+        let index = match self.resolve_index_for_id(node_id) {
+            Ok(index) | Err(index) => self.prev_live(index)?,
+        };
+        Some(self.raw(index))
+    }
+
+    fn update_triangle(&mut self, key: PointKey, triangle_id: TriangleId) {
+        let index = self.search_by_key_with_cache(&key).unwrap();
+        let (chunk_idx, slot) = unpack(index);
+        self.chunks[chunk_idx].entries[slot].node.triangle = triangle_id;
+    }
+
+    #[cfg(test)]
+    fn cache_stats(&self) -> (u64, u64) {
+        self.stats.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::points::PointsBuilder;
+    use crate::shape::InnerTriangle;
+
+    #[test]
+    fn test_chunked_matches_sorted_model() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut points = PointsBuilder::default();
+        let p_0 = points.add_steiner_point(Point::new(-1., 0.));
+        let p_1 = points.add_steiner_point(Point::new(0., 3.));
+        let p_2 = points.add_steiner_point(Point::new(1., 1.));
+        let new_points = (0..5000)
+            .map(|_| {
+                let point = Point::new(rng.gen_range(-0.99..0.99), rng.gen_range(0.0..1.));
+                (points.add_steiner_point(point), point)
+            })
+            .collect::<Vec<_>>();
+        let points = points.build();
+
+        let triangle = InnerTriangle::new(p_0, p_1, p_2);
+        let tid = TriangleId::from_index(0);
+        let mut af =
+            AdvancingFront::with_backend(&triangle, tid, &points, FrontBackend::Chunked, None);
+
+        // the model keeps live points sorted by key
+        let mut model = vec![Point::new(-1., 0.), Point::new(0., 3.), Point::new(1., 1.)];
+        model.sort_by_key(|p| PointKey(*p));
+
+        for (point_id, point) in new_points {
+            af.insert(point_id, point, tid);
+            let pos = model.partition_point(|p| PointKey(*p) < PointKey(point));
+            model.insert(pos, point);
+
+            // randomly delete an inner node, the way fill_one does
+            if rng.gen_bool(0.6) {
+                let node = af.locate_node(point).unwrap();
+                let prev = node.prev().unwrap();
+                if prev.prev().is_some() {
+                    let (prev_id, node_id) = (prev.get_node_id(), node.get_node_id());
+                    unsafe { af.update_and_delete(prev_id, tid, node_id) };
+                    let pos = model.partition_point(|p| PointKey(*p) < PointKey(point));
+                    model.remove(pos);
+                }
+            }
+        }
+
+        let front = af.iter().map(|n| n.point()).collect::<Vec<_>>();
+        assert_eq!(front.len(), model.len());
+        for (a, b) in front.iter().zip(model.iter()) {
+            assert!(a.eq(b));
+        }
+
+        // prev/next walk agrees with iteration
+        let mut node = af.nth(0).unwrap();
+        for expected in model.iter().skip(1) {
+            node = node.next().unwrap();
+            assert!(node.point().eq(expected));
+        }
+        assert!(node.next().is_none());
+        assert!(af
+            .nth(model.len() - 1)
+            .unwrap()
+            .point()
+            .eq(model.last().unwrap()));
+    }
+}
@@ -1,9 +1,64 @@
 use std::cmp::Ordering;
+use std::ops::{Bound, Range, RangeBounds};
 
-use crate::{triangles::TriangleId, Point, PointId};
+use crate::shape::InnerTriangle;
+use crate::{points::Points, triangles::TriangleId, FrontBackend, Point, PointId};
 
+mod btree_backed;
+mod chunked;
 mod vec_backed;
-pub use vec_backed::AdvancingFront;
+
+use btree_backed::BTreeFront;
+use chunked::ChunkedFront;
+use vec_backed::VecFront;
+
+/// New type to wrap `Point` as Node's key
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PointKey(Point);
+
+impl PartialEq for PointKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.x.eq(&other.0.x) && self.0.y.eq(&other.0.y)
+    }
+}
+
+impl Eq for PointKey {}
+
+impl PartialOrd for PointKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PointKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let order = match self.0.x.partial_cmp(&other.0.x) {
+            None | Some(Ordering::Equal) => self.0.y.partial_cmp(&other.0.y),
+            x_order => x_order,
+        };
+        order.unwrap_or(Ordering::Equal)
+    }
+}
+
+impl From<Point> for PointKey {
+    fn from(value: Point) -> Self {
+        Self(value)
+    }
+}
+
+impl PointKey {
+    /// clone the point
+    pub(crate) fn point(&self) -> Point {
+        self.0
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct NodeInner {
+    pub point_id: PointId,
+    /// last node's triangle is None
+    pub triangle: TriangleId,
+}
 
 /// A owned version of NodeId, this should be used when you need to pass NodeRef along with
 /// AdvancingFront's mut reference.
@@ -51,14 +106,10 @@ impl<'a> NodeRef<'a> {
         self.advancing_front.next_node(self)
     }
 
-    pub fn prev(&self) -> Option<NodeRef> {
+    pub fn prev(&self) -> Option<NodeRef<'a>> {
         self.advancing_front.prev_node(self)
     }
 
-    pub(crate) fn index(&self) -> usize {
-        self.index
-    }
-
     /// convert to a owned version of NodeId
     pub fn get_node_id(&self) -> NodeId {
         NodeId {
@@ -69,6 +120,367 @@ impl<'a> NodeRef<'a> {
     }
 }
 
+/// Node info returned by a [`FrontStore`], [`AdvancingFront`] wraps it into [`NodeRef`]
+#[derive(Clone, Copy)]
+pub(crate) struct RawNode {
+    /// backend specific index
+    pub index: usize,
+    pub key: PointKey,
+    pub node: NodeInner,
+}
+
+/// Storage behind [`AdvancingFront`], nodes are sorted by [`PointKey`].
+/// Index is backend specific, it is only meaningful to the store which returned it.
+pub(crate) trait FrontStore {
+    /// create the store with initial nodes, nodes are sorted.
+    /// `capacity` is a hint of the max node count
+    fn new(nodes: [(PointKey, NodeInner); 3], capacity: Option<usize>) -> Self
+    where
+        Self: Sized;
+
+    /// number of nodes
+    fn len(&self) -> usize;
+
+    /// insert a new node or update the existing one, returns its index
+    fn insert(&mut self, key: PointKey, node: NodeInner) -> usize;
+
+    /// update `update` node's triangle, then delete `delete` node
+    /// # Safety
+    /// both ids' index hint should point to the node
+    unsafe fn update_and_delete(&mut self, update: NodeId, triangle_id: TriangleId, delete: NodeId);
+
+    /// `n`th node
+    fn nth(&self, n: usize) -> Option<RawNode>;
+
+    /// node next to the live node at `index` with `key`
+    fn next(&self, index: usize, key: PointKey) -> Option<RawNode>;
+
+    /// node prev to the live node at `index` with `key`
+    fn prev(&self, index: usize, key: PointKey) -> Option<RawNode>;
+
+    /// the node with largest key less or equal than `key`
+    fn locate(&self, key: PointKey) -> Option<RawNode>;
+
+    /// the node for `key`, consult the access cache
+    fn get(&self, key: PointKey) -> Option<RawNode>;
+
+    /// the node for `key`, and record it as access cache
+    fn get_and_cache(&mut self, key: PointKey) -> Option<RawNode>;
+
+    /// the node for `node_id`, `node_id`'s index_hint is tried first
+    fn get_by_id(&self, node_id: NodeId) -> Option<RawNode>;
+
+    /// the node for `node_id`, and record it as access cache
+    fn get_by_id_and_cache(&mut self, node_id: NodeId) -> Option<RawNode>;
+
+    /// next node of `node_id`, even if the node is deleted
+    fn next_of_id(&self, node_id: NodeId) -> Option<RawNode>;
+
+    /// prev node of `node_id`, even if the node is deleted
+    fn prev_of_id(&self, node_id: NodeId) -> Option<RawNode>;
+
+    /// update the triangle for node of `key`
+    fn update_triangle(&mut self, key: PointKey, triangle_id: TriangleId);
+
+    /// access cache (hit, miss) count
+    #[cfg(test)]
+    fn cache_stats(&self) -> (u64, u64);
+}
+
+enum Store {
+    Vec(VecFront),
+    BTree(BTreeFront),
+    Chunked(ChunkedFront),
+}
+
+/// forward the call to the selected backend
+macro_rules! dispatch {
+    ($store:expr, $s:ident => $body:expr) => {
+        match $store {
+            Store::Vec($s) => $body,
+            Store::BTree($s) => $body,
+            Store::Chunked($s) => $body,
+        }
+    };
+}
+
+impl Store {
+    fn new(
+        nodes: [(PointKey, NodeInner); 3],
+        backend: FrontBackend,
+        capacity: Option<usize>,
+    ) -> Self {
+        match backend {
+            FrontBackend::Vec => Store::Vec(VecFront::new(nodes, capacity)),
+            FrontBackend::BTree => Store::BTree(BTreeFront::new(nodes, capacity)),
+            FrontBackend::Chunked => Store::Chunked(ChunkedFront::new(nodes, capacity)),
+        }
+    }
+}
+
+/// Advancing front, stores all advancing edges sorted by point, the storage is
+/// selected by [`FrontBackend`].
+pub struct AdvancingFront {
+    store: Store,
+}
+
+impl AdvancingFront {
+    /// Create a new advancing front with the initial triangle
+    /// Triangle's point order: P0, P-1, P-2
+    pub fn new(triangle: &InnerTriangle, triangle_id: TriangleId, points: &Points) -> Self {
+        Self::with_backend(triangle, triangle_id, points, FrontBackend::default(), None)
+    }
+
+    /// Create a new advancing front with the initial triangle, stored in `backend`
+    /// `capacity` is a hint of the max node count
+    pub fn with_backend(
+        triangle: &InnerTriangle,
+        triangle_id: TriangleId,
+        points: &Points,
+        backend: FrontBackend,
+        capacity: Option<usize>,
+    ) -> Self {
+        let node = |point_index: usize, triangle_id: TriangleId| {
+            let point_id = triangle.points[point_index];
+            let point = points.get_point(point_id).expect("should not fail");
+            (
+                PointKey(point),
+                NodeInner {
+                    point_id,
+                    triangle: triangle_id,
+                },
+            )
+        };
+        let mut nodes = [
+            node(1, triangle_id),
+            node(0, triangle_id),
+            node(2, TriangleId::INVALID),
+        ];
+        nodes.sort_unstable_by_key(|(key, _)| *key);
+
+        Self {
+            store: Store::new(nodes, backend, capacity),
+        }
+    }
+
+    /// Rebuild the front with point and triangle ids renumbered, used after point
+    /// or triangle store dropped entries.
+    pub(crate) fn remap(
+        &mut self,
+        map_point: impl Fn(PointId) -> PointId,
+        map_triangle: impl Fn(TriangleId) -> TriangleId,
+    ) {
+        let mut nodes = self
+            .iter()
+            .map(|node| {
+                (
+                    PointKey(node.point()),
+                    NodeInner {
+                        point_id: map_point(node.point_id()),
+                        triangle: node
+                            .triangle
+                            .map(&map_triangle)
+                            .unwrap_or(TriangleId::INVALID),
+                    },
+                )
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        // front always has head, tail and at least one point in between
+        let first = [
+            nodes.next().unwrap(),
+            nodes.next().unwrap(),
+            nodes.next().unwrap(),
+        ];
+        let backend = match self.store {
+            Store::Vec(_) => FrontBackend::Vec,
+            Store::BTree(_) => FrontBackend::BTree,
+            Store::Chunked(_) => FrontBackend::Chunked,
+        };
+        let mut store = Store::new(first, backend, Some(self.len() * 2));
+        for (key, node) in nodes {
+            dispatch!(&mut store, s => s.insert(key, node));
+        }
+        self.store = store;
+    }
+
+    fn to_node(&self, raw: RawNode) -> NodeRef<'_> {
+        NodeRef {
+            point_id: raw.node.point_id,
+            point: raw.key.point(),
+            triangle: raw.node.triangle.into_option(),
+            index: raw.index,
+            advancing_front: self,
+        }
+    }
+
+    /// number of nodes in the front
+    pub fn len(&self) -> usize {
+        dispatch!(&self.store, s => s.len())
+    }
+
+    /// insert a new node for point and triangle
+    /// or update the node pointing to new triangle
+    /// Returns the [`NodeId`] of the node, with its index as hint
+    pub fn insert(&mut self, point_id: PointId, point: Point, triangle_id: TriangleId) -> NodeId {
+        debug_assert!(!triangle_id.invalid());
+        let node = NodeInner {
+            point_id,
+            triangle: triangle_id,
+        };
+        let index = dispatch!(&mut self.store, s => s.insert(PointKey(point), node));
+        NodeId {
+            point_id,
+            point,
+            index_hint: index,
+        }
+    }
+
+    /// update `update` node's triangle, then delete `delete` node
+    /// when call this method, need to ensure that both ids' index still points to the node
+    pub(crate) unsafe fn update_and_delete(
+        &mut self,
+        update: NodeId,
+        triangle_id: TriangleId,
+        delete: NodeId,
+    ) {
+        debug_assert!(!triangle_id.invalid());
+        dispatch!(&mut self.store, s => unsafe { s.update_and_delete(update, triangle_id, delete) })
+    }
+
+    /// Get `n`th node
+    pub fn nth(&self, n: usize) -> Option<NodeRef<'_>> {
+        let raw = dispatch!(&self.store, s => s.nth(n))?;
+        Some(self.to_node(raw))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = NodeRef<'_>> + '_ {
+        std::iter::successors(self.nth(0), |node| node.next())
+    }
+
+    /// Nodes with x in `range`, left to right, e.g. for drawing the visible part
+    /// of the front. The first one is found by search, not by walking from the left.
+    pub fn iter_range(&self, range: Range<f64>) -> impl Iterator<Item = NodeRef<'_>> + '_ {
+        let first = match self.locate_node(Point::new(range.start, f64::NEG_INFINITY)) {
+            Some(node) if node.point().x < range.start => node.next(),
+            Some(node) => Some(node),
+            None => self.nth(0),
+        };
+        std::iter::successors(first, |node| node.next())
+            .take_while(move |node| node.point().x < range.end)
+    }
+
+    /// Nodes with x in `range`, left to right, e.g. `x_min..x_max`, `x_min..=x_max`
+    /// or `x_min..`. Bounds are mapped onto the half open window of
+    /// [`AdvancingFront::iter_range`], inclusive ends by the next larger f64.
+    pub fn range(&self, range: impl RangeBounds<f64>) -> impl Iterator<Item = NodeRef<'_>> + '_ {
+        let start = match range.start_bound() {
+            Bound::Included(&x) => x,
+            Bound::Excluded(&x) => x.next_up(),
+            Bound::Unbounded => f64::NEG_INFINITY,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&x) => x.next_up(),
+            Bound::Excluded(&x) => x,
+            Bound::Unbounded => f64::INFINITY,
+        };
+        self.iter_range(start..end)
+    }
+
+    /// locate the node containing point
+    /// locate the node for `x`
+    pub fn locate_node(&self, point: Point) -> Option<NodeRef<'_>> {
+        let raw = dispatch!(&self.store, s => s.locate(PointKey(point)))?;
+        Some(self.to_node(raw))
+    }
+
+    /// Get the node identified by `point`
+    pub fn get_node(&self, point: Point) -> Option<NodeRef<'_>> {
+        let raw = dispatch!(&self.store, s => s.get(PointKey(point)))?;
+        Some(self.to_node(raw))
+    }
+
+    /// Get the node identified by `point`
+    pub fn get_node_with_cache(&mut self, point: Point) -> Option<NodeRef<'_>> {
+        let raw = dispatch!(&mut self.store, s => s.get_and_cache(PointKey(point)))?;
+        Some(self.to_node(raw))
+    }
+
+    /// Get the node identified by `point`
+    pub fn get_node_with_id(&self, node_id: NodeId) -> Option<NodeRef<'_>> {
+        let raw = dispatch!(&self.store, s => s.get_by_id(node_id))?;
+        Some(self.to_node(raw))
+    }
+
+    /// Get the node identified by `node_id`, and record it in access cache
+    pub fn get_node_with_id_and_cache(&mut self, node_id: NodeId) -> Option<NodeRef<'_>> {
+        let raw = dispatch!(&mut self.store, s => s.get_by_id_and_cache(node_id))?;
+        Some(self.to_node(raw))
+    }
+
+    /// update node's triangle
+    pub fn update_triangle(&mut self, point: Point, triangle_id: TriangleId) {
+        dispatch!(&mut self.store, s => s.update_triangle(PointKey(point), triangle_id))
+    }
+
+    /// Get next node of the node identified by `point`
+    /// Note: even if the node is deleted, this also returns next node as if it is not deleted
+    pub fn locate_next_node(&self, node_id: NodeId) -> Option<NodeRef<'_>> {
+        let raw = dispatch!(&self.store, s => s.next_of_id(node_id))?;
+        Some(self.to_node(raw))
+    }
+
+    fn next_node(&self, node: &NodeRef) -> Option<NodeRef<'_>> {
+        let raw = dispatch!(&self.store, s => s.next(node.index, PointKey(node.point)))?;
+        Some(self.to_node(raw))
+    }
+
+    /// Get prev node of the node identified by `point`
+    /// Note: even if the node is deleted, then this returns prev node as if it is not deleted
+    pub fn locate_prev_node(&self, node_id: NodeId) -> Option<NodeRef<'_>> {
+        let raw = dispatch!(&self.store, s => s.prev_of_id(node_id))?;
+        Some(self.to_node(raw))
+    }
+
+    fn prev_node(&self, node: &NodeRef) -> Option<NodeRef<'_>> {
+        let raw = dispatch!(&self.store, s => s.prev(node.index, PointKey(node.point)))?;
+        Some(self.to_node(raw))
+    }
+
+    /// access cache (hit, miss) count
+    #[cfg(test)]
+    pub fn cache_stats(&self) -> (u64, u64) {
+        dispatch!(&self.store, s => s.cache_stats())
+    }
+}
+
+/// access cache hit/miss counter, only collected in test
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct CacheStats {
+    hit: std::sync::atomic::AtomicU64,
+    miss: std::sync::atomic::AtomicU64,
+}
+
+#[cfg(test)]
+impl CacheStats {
+    pub fn hit(&self) {
+        self.hit.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn miss(&self) {
+        self.miss.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> (u64, u64) {
+        (
+            self.hit.load(std::sync::atomic::Ordering::Relaxed),
+            self.miss.load(std::sync::atomic::Ordering::Relaxed),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,6 +492,16 @@ mod tests {
 
     #[test]
     fn test_advancing_front() {
+        for backend in [
+            FrontBackend::Vec,
+            FrontBackend::BTree,
+            FrontBackend::Chunked,
+        ] {
+            check_advancing_front(backend);
+        }
+    }
+
+    fn check_advancing_front(backend: FrontBackend) {
         let mut triangles = TriangleStore::new();
 
         let mut points = PointsBuilder::default();
@@ -91,7 +513,8 @@ mod tests {
         let triangle_id = triangles.insert(InnerTriangle::new(p_0, p_1, p_2));
         let triangle = triangles.get(triangle_id).unwrap();
 
-        let advancing_front = AdvancingFront::new(triangle, triangle_id, &points);
+        let advancing_front =
+            AdvancingFront::with_backend(triangle, triangle_id, &points, backend, None);
         {
             let p = advancing_front.locate_node(Point::new(0., 10.)).unwrap();
             let point = p.point();
@@ -112,5 +535,34 @@ mod tests {
             let next_node = advancing_front.locate_next_node(p).unwrap();
             assert_eq!(next_node.point().x, 1.);
         }
+
+        let xs = |range: Range<f64>| {
+            advancing_front
+                .iter_range(range)
+                .map(|node| node.point().x)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(xs(-1.0..1.), [-1., 0.]);
+        assert_eq!(xs(-0.5..2.), [0., 1.]);
+        assert_eq!(xs(-10.0..10.), [-1., 0., 1.]);
+        assert!(xs(-10.0..-5.).is_empty());
+        assert!(xs(5.0..6.).is_empty());
+
+        let xs = |range: (Bound<f64>, Bound<f64>)| {
+            advancing_front
+                .range(range)
+                .map(|node| node.point().x)
+                .collect::<Vec<_>>()
+        };
+        let none = Vec::<f64>::new();
+        assert_eq!(
+            xs((Bound::Included(-1.), Bound::Included(1.))),
+            [-1., 0., 1.]
+        );
+        assert_eq!(xs((Bound::Excluded(-1.), Bound::Unbounded)), [0., 1.]);
+        assert_eq!(xs((Bound::Unbounded, Bound::Excluded(0.))), [-1.]);
+        assert_eq!(xs((Bound::Excluded(0.), Bound::Excluded(1.))), none);
+        assert_eq!(advancing_front.range(..).count(), 3);
+        assert_eq!(advancing_front.range(-1.0..1.).count(), 2);
     }
 }
@@ -0,0 +1,125 @@
+//! Zero copy [rkyv](https://docs.rs/rkyv) archives of the result, for engines that
+//! memory map precomputed meshes, e.g. navmeshes, at load time.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{archive::{ArchivedMesh, Mesh}, Point, SweeperBuilder};
+//!
+//!    let triangles = SweeperBuilder::new(vec![
+//!        Point::new(0., 0.),
+//!        Point::new(10., 0.),
+//!        Point::new(10., 10.),
+//!        Point::new(0., 10.),
+//!    ])
+//!    .build()
+//!    .triangulate();
+//!    let bytes = Mesh::new(&triangles).to_bytes();
+//!
+//!    // e.g. bytes of a memory mapped file, nothing is copied
+//!    let mesh: &ArchivedMesh = Mesh::access(&bytes).unwrap();
+//!    assert_eq!(mesh.indices.len(), 2);
+//! ```
+use rkyv::rancor::Error;
+use rkyv::util::AlignedVec;
+
+use crate::Triangles;
+
+/// Result mesh with positions, indices and adjacency
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize, Debug, Clone, PartialEq)]
+pub struct Mesh {
+    /// `x, y` of each vertex, same as [`Triangles::write_positions_into`] but f64
+    pub positions: Vec<[f64; 2]>,
+    /// vertex indices of each triangle, same as [`Triangles::write_indices_into`]
+    pub indices: Vec<[u32; 3]>,
+    /// triangle across each edge, same as [`Triangles::write_adjacency_into`]
+    pub adjacency: Vec<[u32; 3]>,
+}
+
+impl Mesh {
+    /// Collect result of `triangles`, regardless of iteration progress
+    pub fn new(triangles: &Triangles) -> Self {
+        let count = triangles.index_count();
+        let mut indices = vec![0; count];
+        triangles.write_indices_into(&mut indices);
+        let mut adjacency = vec![0; count];
+        triangles.write_adjacency_into(&mut adjacency);
+        let triples = |flat: Vec<u32>| {
+            flat.chunks_exact(3)
+                .map(|c| [c[0], c[1], c[2]])
+                .collect::<Vec<_>>()
+        };
+
+        Self {
+            positions: triangles.vertex_positions(),
+            indices: triples(indices),
+            adjacency: triples(adjacency),
+        }
+    }
+
+    /// Archive into aligned bytes
+    pub fn to_bytes(&self) -> AlignedVec {
+        // serializing into a vec does not fail
+        rkyv::to_bytes::<Error>(self).expect("serialize mesh")
+    }
+
+    /// Validate `bytes` and view them as an archived mesh, without copying
+    pub fn access(bytes: &[u8]) -> Result<&ArchivedMesh, Error> {
+        rkyv::access::<ArchivedMesh, Error>(bytes)
+    }
+
+    /// Deserialize an archived mesh back to an owned one
+    pub fn from_archived(archived: &ArchivedMesh) -> Result<Self, Error> {
+        rkyv::deserialize::<Self, Error>(archived)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Point, SweeperBuilder};
+
+    #[test]
+    fn test_archive() {
+        let triangles = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_hole(vec![
+            Point::new(4., 4.),
+            Point::new(4., 6.),
+            Point::new(6., 6.),
+            Point::new(6., 4.),
+        ])
+        .build()
+        .triangulate();
+        let mesh = Mesh::new(&triangles);
+        assert_eq!(mesh.positions.len(), 8);
+        assert_eq!(mesh.indices.len(), 8);
+
+        // neighbor across edge i shares the points other than i
+        for (t, neighbors) in mesh.adjacency.iter().enumerate() {
+            for (i, neighbor) in neighbors.iter().enumerate() {
+                if *neighbor == u32::MAX {
+                    continue;
+                }
+                let other = mesh.indices[*neighbor as usize];
+                let edge = [mesh.indices[t][(i + 1) % 3], mesh.indices[t][(i + 2) % 3]];
+                assert!(edge.iter().all(|p| other.contains(p)));
+                assert!(mesh.adjacency[*neighbor as usize].contains(&(t as u32)));
+            }
+        }
+        // 4 outer and 4 hole edges are on the border
+        let border = mesh.adjacency.iter().flatten().filter(|n| **n == u32::MAX);
+        assert_eq!(border.count(), 8);
+
+        let bytes = mesh.to_bytes();
+        let archived = Mesh::access(&bytes).unwrap();
+        assert_eq!(archived.indices.len(), mesh.indices.len());
+        assert_eq!(archived.positions[1][0], 10.);
+        assert_eq!(Mesh::from_archived(archived).unwrap(), mesh);
+
+        assert!(Mesh::access(&bytes[..bytes.len() / 2]).is_err());
+    }
+}
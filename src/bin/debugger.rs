@@ -0,0 +1,339 @@
+//! Interactive visual debugger, step through observer events of a triangulation.
+//!
+//! cargo run --bin debugger --features debugger -- test_data/bird.dat
+//!
+//! Left/Right steps through events, hover a triangle to see its id, neighbors
+//! and edge flags.
+use eframe::egui;
+use poly2tri_rs::{loader, Context, Edge, Observer, Point, PointId, TriangleId};
+
+/// frames beyond this are dropped, each frame copies all triangles
+const MAX_FRAMES: usize = 5000;
+
+struct FrameTriangle {
+    id: usize,
+    points: [Point; 3],
+    point_ids: [usize; 3],
+    neighbors: [Option<usize>; 3],
+    constrained: [bool; 3],
+    delaunay: [bool; 3],
+    interior: bool,
+    in_result: bool,
+}
+
+/// Snapshot of sweep state after one observer event
+struct Frame {
+    label: String,
+    triangles: Vec<FrameTriangle>,
+    front: Vec<Point>,
+    /// point or edge processed by this event
+    focus: Vec<Point>,
+}
+
+#[derive(Default)]
+struct Recorder {
+    frames: Vec<Frame>,
+    /// also record every legalize step
+    legalize: bool,
+}
+
+impl Recorder {
+    fn record(&mut self, label: String, focus: Vec<Point>, context: &Context) {
+        if self.frames.len() >= MAX_FRAMES {
+            return;
+        }
+        let triangles = context
+            .triangles
+            .iter()
+            .map(|(id, t)| FrameTriangle {
+                id: id.as_usize(),
+                points: t.points.map(|p| context.points.get_point(p).unwrap()),
+                point_ids: t.points.map(|p| p.as_usize()),
+                neighbors: t.neighbors.map(|n| n.into_option().map(|n| n.as_usize())),
+                constrained: [0, 1, 2].map(|i| t.is_constrained(i)),
+                delaunay: [0, 1, 2].map(|i| t.is_delaunay(i)),
+                interior: t.interior,
+                in_result: context.result.contains(&id),
+            })
+            .collect();
+        let front = context.advancing_front.iter().map(|n| n.point()).collect();
+        self.frames.push(Frame {
+            label,
+            triangles,
+            front,
+            focus,
+        });
+    }
+}
+
+impl Observer for Recorder {
+    fn point_event(&mut self, point_id: PointId, context: &Context) {
+        let point = context.points.get_point(point_id).unwrap();
+        self.record(
+            format!(
+                "point {} ({:.3}, {:.3})",
+                point_id.as_usize(),
+                point.x,
+                point.y
+            ),
+            vec![point],
+            context,
+        );
+    }
+
+    fn edge_event(&mut self, edge: Edge, context: &Context) {
+        let p = context.points.get_point(edge.p).unwrap();
+        let q = context.points.get_point(edge.q).unwrap();
+        self.record(
+            format!("edge {} -> {}", edge.p.as_usize(), edge.q.as_usize()),
+            vec![p, q],
+            context,
+        );
+    }
+
+    fn legalize_step(&mut self, triangle_id: TriangleId, context: &Context) {
+        if self.legalize {
+            self.record(
+                format!("legalize step {}", triangle_id.as_usize()),
+                vec![],
+                context,
+            );
+        }
+    }
+
+    fn sweep_done(&mut self, context: &Context) {
+        self.record("sweep done".into(), vec![], context);
+    }
+
+    fn finalized(&mut self, context: &Context) {
+        self.record("finalized".into(), vec![], context);
+    }
+}
+
+struct Debugger {
+    path: String,
+    legalize: bool,
+    frames: Vec<Frame>,
+    current: usize,
+    show_front: bool,
+    show_ids: bool,
+    error: Option<String>,
+}
+
+impl Debugger {
+    fn new(path: String) -> Self {
+        let mut debugger = Self {
+            path,
+            legalize: false,
+            frames: vec![],
+            current: 0,
+            show_front: true,
+            show_ids: false,
+            error: None,
+        };
+        debugger.reload();
+        debugger
+    }
+
+    fn reload(&mut self) {
+        if self.path.is_empty() {
+            return;
+        }
+        match loader::load_file(&self.path) {
+            Ok(builder) => {
+                let mut recorder = Recorder {
+                    legalize: self.legalize,
+                    ..Default::default()
+                };
+                let _ = builder.build().triangulate_with_observer(&mut recorder);
+                self.frames = recorder.frames;
+                self.current = 0;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("{}: {e}", self.path)),
+        }
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("file");
+            ui.text_edit_singleline(&mut self.path);
+            if ui.button("load").clicked() {
+                self.reload();
+            }
+            if ui.checkbox(&mut self.legalize, "legalize steps").changed() {
+                self.reload();
+            }
+            ui.checkbox(&mut self.show_front, "front");
+            ui.checkbox(&mut self.show_ids, "ids");
+        });
+
+        if self.frames.is_empty() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            if ui.button("<").clicked() {
+                self.current = self.current.saturating_sub(1);
+            }
+            if ui.button(">").clicked() {
+                self.current = (self.current + 1).min(self.frames.len() - 1);
+            }
+            ui.add(egui::Slider::new(&mut self.current, 0..=self.frames.len() - 1).text("event"));
+            ui.label(&self.frames[self.current].label);
+        });
+    }
+
+    fn canvas(&self, ui: &mut egui::Ui) -> Option<usize> {
+        let (response, painter) = ui.allocate_painter(ui.available_size(), egui::Sense::hover());
+        let frame = self.frames.get(self.current)?;
+
+        let (mut min, mut max) = (
+            Point::new(f64::MAX, f64::MAX),
+            Point::new(f64::MIN, f64::MIN),
+        );
+        for p in frame.triangles.iter().flat_map(|t| t.points.iter()) {
+            min = Point::new(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        let rect = response.rect.shrink(10.);
+        let scale =
+            (rect.width() as f64 / (max.x - min.x)).min(rect.height() as f64 / (max.y - min.y));
+        // y flipped, screen y goes down
+        let to_screen = |p: Point| {
+            egui::pos2(
+                rect.left() + ((p.x - min.x) * scale) as f32,
+                rect.bottom() - ((p.y - min.y) * scale) as f32,
+            )
+        };
+        let to_world = |p: egui::Pos2| {
+            Point::new(
+                (p.x - rect.left()) as f64 / scale + min.x,
+                (rect.bottom() - p.y) as f64 / scale + min.y,
+            )
+        };
+
+        let hovered = response.hover_pos().and_then(|pos| {
+            let p = to_world(pos);
+            frame.triangles.iter().position(|t| contains(&t.points, p))
+        });
+
+        for (index, t) in frame.triangles.iter().enumerate() {
+            let points = t.points.map(to_screen).to_vec();
+            let fill = if hovered == Some(index) {
+                egui::Color32::from_rgb(255, 213, 79)
+            } else if t.in_result {
+                egui::Color32::from_rgb(129, 212, 250)
+            } else if t.interior {
+                egui::Color32::from_rgb(179, 229, 252)
+            } else {
+                egui::Color32::from_gray(235)
+            };
+            painter.add(egui::Shape::convex_polygon(
+                points,
+                fill,
+                egui::Stroke::NONE,
+            ));
+
+            // edge i is opposite to point i
+            for i in 0..3 {
+                let (a, b) = (t.points[(i + 1) % 3], t.points[(i + 2) % 3]);
+                let stroke = if t.constrained[i] {
+                    egui::Stroke::new(2., egui::Color32::from_rgb(255, 167, 38))
+                } else {
+                    egui::Stroke::new(1., egui::Color32::from_gray(120))
+                };
+                painter.line_segment([to_screen(a), to_screen(b)], stroke);
+            }
+
+            if self.show_ids {
+                let center = Point::new(
+                    (t.points[0].x + t.points[1].x + t.points[2].x) / 3.,
+                    (t.points[0].y + t.points[1].y + t.points[2].y) / 3.,
+                );
+                painter.text(
+                    to_screen(center),
+                    egui::Align2::CENTER_CENTER,
+                    t.id.to_string(),
+                    egui::FontId::monospace(10.),
+                    egui::Color32::DARK_GRAY,
+                );
+            }
+        }
+
+        if self.show_front {
+            let front = frame.front.iter().map(|p| to_screen(*p)).collect();
+            painter.add(egui::Shape::line(
+                front,
+                egui::Stroke::new(2., egui::Color32::from_rgb(239, 83, 80)),
+            ));
+        }
+
+        match frame.focus.as_slice() {
+            [p] => {
+                painter.circle_filled(to_screen(*p), 4., egui::Color32::from_rgb(62, 39, 35));
+            }
+            [p, q] => {
+                painter.line_segment(
+                    [to_screen(*p), to_screen(*q)],
+                    egui::Stroke::new(3., egui::Color32::from_rgb(62, 39, 35)),
+                );
+            }
+            _ => {}
+        }
+
+        hovered
+    }
+}
+
+impl eframe::App for Debugger {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if !self.frames.is_empty() {
+            ctx.input(|input| {
+                if input.key_pressed(egui::Key::ArrowLeft) {
+                    self.current = self.current.saturating_sub(1);
+                }
+                if input.key_pressed(egui::Key::ArrowRight) {
+                    self.current = (self.current + 1).min(self.frames.len() - 1);
+                }
+            });
+        }
+
+        egui::TopBottomPanel::top("controls").show(ctx, |ui| {
+            self.controls(ui);
+            if let Some(error) = &self.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+
+        let mut hovered = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            hovered = self.canvas(ui);
+        });
+
+        if let Some(t) = hovered.and_then(|index| self.frames[self.current].triangles.get(index)) {
+            egui::show_tooltip_at_pointer(ctx, egui::Id::new("triangle"), |ui| {
+                ui.label(format!("triangle {}", t.id));
+                ui.label(format!("points {:?}", t.point_ids));
+                ui.label(format!("neighbors {:?}", t.neighbors));
+                ui.label(format!("constrained {:?}", t.constrained));
+                ui.label(format!("delaunay {:?}", t.delaunay));
+                ui.label(format!("interior {}", t.interior));
+            });
+        }
+    }
+}
+
+fn contains(t: &[Point; 3], p: Point) -> bool {
+    let side = |a: Point, b: Point| (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x);
+    let (d0, d1, d2) = (side(t[0], t[1]), side(t[1], t[2]), side(t[2], t[0]));
+    (d0 >= 0. && d1 >= 0. && d2 >= 0.) || (d0 <= 0. && d1 <= 0. && d2 <= 0.)
+}
+
+fn main() -> Result<(), eframe::Error> {
+    let path = std::env::args().nth(1).unwrap_or_default();
+    eframe::run_native(
+        "p2t debugger",
+        eframe::NativeOptions::default(),
+        Box::new(|_cc| Box::new(Debugger::new(path))),
+    )
+}
@@ -0,0 +1,243 @@
+//! Triangulate a polygon file and write the mesh.
+//!
+//! cargo run --bin p2t --features cli -- test_data/bird.dat -o bird.obj --stats
+use std::io::Write;
+
+use clap::{Parser, ValueEnum};
+use poly2tri_rs::{loader, FrontBackend, Metadata, Point, SweepConfig, Triangle, Triangles};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Obj,
+    Geojson,
+    Svg,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Backend {
+    Vec,
+    Btree,
+    Chunked,
+}
+
+#[derive(Parser, Debug)]
+#[command(about = "Triangulate polygon file (.dat or .svg), write OBJ, GeoJSON or SVG")]
+struct Args {
+    /// input file, format picked by extension
+    input: String,
+
+    /// output file, stdout if omitted
+    #[arg(short, long)]
+    output: Option<std::path::PathBuf>,
+
+    /// output format, defaults to output's extension, then obj
+    #[arg(short, long)]
+    format: Option<Format>,
+
+    /// refine by adding centroids of triangles larger than this, as steiner points
+    #[arg(long)]
+    max_area: Option<f64>,
+
+    /// max refine rounds
+    #[arg(long, default_value = "8")]
+    refine_rounds: usize,
+
+    /// advancing front backend
+    #[arg(long)]
+    backend: Option<Backend>,
+
+    /// print stats to stderr
+    #[arg(long)]
+    stats: bool,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let mut builder = loader::load_file(&args.input)?;
+    if let Some(backend) = args.backend {
+        builder = builder.with_config(SweepConfig::default().front_backend(match backend {
+            Backend::Vec => FrontBackend::Vec,
+            Backend::Btree => FrontBackend::BTree,
+            Backend::Chunked => FrontBackend::Chunked,
+        }));
+    }
+
+    let start = std::time::Instant::now();
+    let mut triangles = builder.clone().build().triangulate();
+    // write_*_into ignores iteration progress, so obj output still works
+    let mut result = triangles.by_ref().collect::<Vec<_>>();
+    if let Some(max_area) = args.max_area {
+        for _ in 0..args.refine_rounds {
+            let steiner_points = result
+                .iter()
+                .filter(|t| area(t) > max_area)
+                .map(centroid)
+                .collect::<Vec<_>>();
+            if steiner_points.is_empty() {
+                break;
+            }
+            builder = builder.add_steiner_points(steiner_points);
+            triangles = builder.clone().build().triangulate();
+            result = triangles.by_ref().collect();
+        }
+    }
+    let elapsed = start.elapsed();
+    // header of output, after refine added its steiner points
+    let metadata = builder.metadata();
+
+    let format = args.format.unwrap_or_else(|| {
+        match args
+            .output
+            .as_ref()
+            .and_then(|p| p.extension())
+            .and_then(|e| e.to_str())
+        {
+            Some("geojson") | Some("json") => Format::Geojson,
+            Some("svg") => Format::Svg,
+            _ => Format::Obj,
+        }
+    });
+
+    let mut out: Box<dyn Write> = match args.output.as_ref() {
+        Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path)?)),
+        None => Box::new(std::io::BufWriter::new(std::io::stdout().lock())),
+    };
+    match format {
+        Format::Obj => write_obj(&triangles, &metadata, &mut out)?,
+        Format::Geojson => write_geojson(&result, &metadata, &mut out)?,
+        Format::Svg => write_svg(&result, &metadata, &mut out)?,
+    }
+    out.flush()?;
+
+    if args.stats {
+        let min_angle = result
+            .iter()
+            .map(min_angle)
+            .fold(f64::MAX, f64::min)
+            .to_degrees();
+        eprintln!("points: {}", triangles.vertex_count());
+        eprintln!("triangles: {}", result.len());
+        eprintln!("area: {}", result.iter().map(area).sum::<f64>());
+        eprintln!("min angle: {min_angle:.2}");
+        eprintln!("elapsed: {elapsed:?}");
+    }
+    Ok(())
+}
+
+fn area(t: &Triangle) -> f64 {
+    let [a, b, c] = t.points;
+    ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() / 2.
+}
+
+fn centroid(t: &Triangle) -> Point {
+    let [a, b, c] = t.points;
+    Point::new((a.x + b.x + c.x) / 3., (a.y + b.y + c.y) / 3.)
+}
+
+fn min_angle(t: &Triangle) -> f64 {
+    (0..3)
+        .map(|i| {
+            let (o, p, q) = (t.points[i], t.points[(i + 1) % 3], t.points[(i + 2) % 3]);
+            let (ux, uy, vx, vy) = (p.x - o.x, p.y - o.y, q.x - o.x, q.y - o.y);
+            (ux * vy - uy * vx).abs().atan2(ux * vx + uy * vy)
+        })
+        .fold(f64::MAX, f64::min)
+}
+
+fn write_obj(
+    triangles: &Triangles,
+    metadata: &Metadata,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    for (key, value) in metadata.entries() {
+        writeln!(out, "# {key}: {value}")?;
+    }
+    let mut positions = vec![0f32; triangles.vertex_count() * 2];
+    triangles.write_positions_into(&mut positions);
+    let mut indices = vec![0u32; triangles.index_count()];
+    triangles.write_indices_into(&mut indices);
+
+    for xy in positions.chunks_exact(2) {
+        writeln!(out, "v {} {} 0", xy[0], xy[1])?;
+    }
+    for face in indices.chunks_exact(3) {
+        // obj indices are 1 based
+        writeln!(out, "f {} {} {}", face[0] + 1, face[1] + 1, face[2] + 1)?;
+    }
+    Ok(())
+}
+
+/// json string with quotes and backslashes escaped, other chars are kept
+fn json_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_geojson(
+    triangles: &[Triangle],
+    metadata: &Metadata,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    // a foreign member, geojson readers keep or ignore it
+    let metadata = metadata
+        .entries()
+        .map(|(key, value)| format!("{}:{}", json_string(key), json_string(&value)))
+        .join(",");
+    write!(
+        out,
+        r#"{{"type":"FeatureCollection","metadata":{{{metadata}}},"features":["#
+    )?;
+    for (index, t) in triangles.iter().enumerate() {
+        if index > 0 {
+            write!(out, ",")?;
+        }
+        let [a, b, c] = t.points;
+        write!(
+            out,
+            r#"{{"type":"Feature","properties":{{"id":{index}}},"geometry":{{"type":"Polygon","coordinates":[[[{},{}],[{},{}],[{},{}],[{},{}]]]}}}}"#,
+            a.x, a.y, b.x, b.y, c.x, c.y, a.x, a.y
+        )?;
+    }
+    writeln!(out, "]}}")
+}
+
+fn write_svg(
+    triangles: &[Triangle],
+    metadata: &Metadata,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    let (mut min, mut max) = (
+        Point::new(f64::MAX, f64::MAX),
+        Point::new(f64::MIN, f64::MIN),
+    );
+    for p in triangles.iter().flat_map(|t| t.points.iter()) {
+        min = Point::new(min.x.min(p.x), min.y.min(p.y));
+        max = Point::new(max.x.max(p.x), max.y.max(p.y));
+    }
+    let stroke = ((max.x - min.x).max(max.y - min.y) / 1000.).max(f64::MIN_POSITIVE);
+
+    // y flipped, svg's y goes down
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        min.x,
+        -max.y,
+        max.x - min.x,
+        max.y - min.y
+    )?;
+    for (key, value) in metadata.entries() {
+        writeln!(out, "<!-- {key}: {value} -->")?;
+    }
+    writeln!(
+        out,
+        r##"<g fill="#29B6F6" stroke="#616161" stroke-width="{stroke}">"##
+    )?;
+    for t in triangles.iter() {
+        let [a, b, c] = t.points;
+        writeln!(
+            out,
+            r#"<path d="M{} {} L{} {} L{} {} Z"/>"#,
+            a.x, -a.y, b.x, -b.y, c.x, -c.y
+        )?;
+    }
+    writeln!(out, "</g>\n</svg>")
+}
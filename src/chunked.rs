@@ -0,0 +1,147 @@
+//! Triangulation in steps, for callers which can't block for the whole sweep,
+//! e.g. an async task yielding to its runtime or a wasm main thread rendering
+//! frames in between.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{Point, SweeperBuilder};
+//!
+//!    let mut chunked = SweeperBuilder::new(vec![
+//!        Point::new(0., 0.),
+//!        Point::new(10., 0.),
+//!        Point::new(10., 10.),
+//!        Point::new(0., 10.),
+//!    ])
+//!    .add_steiner_points((1..10).map(|i| Point::new(i as f64, i as f64 * 0.9)))
+//!    .build()
+//!    .triangulate_chunked(4);
+//!
+//!    while !chunked.resume() {
+//!        // other work, or `.await` a yield
+//!        let (done, total) = chunked.progress();
+//!        assert!(done <= total);
+//!    }
+//!    assert_eq!(chunked.finish().count(), 20);
+//! ```
+use crate::advancing_front::AdvancingFront;
+use crate::triangles::{TriangleId, TriangleStore};
+use crate::{Context, EdgeError, Legality, PowerDelaunay, Sweeper, Triangles};
+
+/// Sweep state between steps, see [`Sweeper::triangulate_chunked`]
+pub struct ChunkedTriangulation {
+    sweeper: Sweeper,
+    power: Option<PowerDelaunay>,
+    triangles: TriangleStore,
+    front: AdvancingFront,
+    chunk_size: usize,
+    /// y order of the next point to sweep, the lowest point is in the initial
+    /// triangle
+    next: usize,
+    /// carried between steps, a new context starts from scratch
+    legalize_tick: u32,
+    edge_errors: Vec<EdgeError>,
+    /// interior triangles, once finalized
+    result: Option<Vec<TriangleId>>,
+}
+
+impl ChunkedTriangulation {
+    pub(crate) fn new(mut sweeper: Sweeper, chunk_size: usize) -> Self {
+        let weights = sweeper.take_weights();
+        let power = (!weights.is_empty()).then(|| PowerDelaunay::new(weights));
+        let (triangles, front) = sweeper.start();
+        Self {
+            sweeper,
+            power,
+            triangles,
+            front,
+            chunk_size: chunk_size.max(1),
+            next: 1,
+            legalize_tick: 0,
+            edge_errors: vec![],
+            result: None,
+        }
+    }
+
+    /// Run the next `chunk_size` point events, with the edge events of their
+    /// points, or the final step marking interior triangles. Returns whether
+    /// triangulation is done, then take the result by [`Self::finish`].
+    pub fn resume(&mut self) -> bool {
+        if self.result.is_some() {
+            return true;
+        }
+
+        let points = self.sweeper.points();
+        let mut context = Context::new(points, &mut self.triangles, &mut self.front);
+        context.legality = self.power.as_ref().map(|power| power as &dyn Legality);
+        context.legalize = self.sweeper.config().legalize;
+        context.predicates = self.sweeper.config().predicates;
+        context.legalize_tick = self.legalize_tick;
+        context.edge_errors = std::mem::take(&mut self.edge_errors);
+
+        let total = points.len() - 2;
+        if self.next < total {
+            let end = (self.next + self.chunk_size).min(total);
+            for (point_id, point, edges) in points.iter_point_by_y(self.next).take(end - self.next)
+            {
+                Sweeper::sweep_point(point_id, point, edges, &mut context, &mut ());
+            }
+            self.next = end;
+        } else {
+            Sweeper::finalize_polygon(&mut context);
+            self.result = Some(std::mem::take(&mut context.result));
+        }
+
+        self.legalize_tick = context.legalize_tick;
+        self.edge_errors = context.edge_errors;
+        self.result.is_some()
+    }
+
+    /// Points swept so far and in total, the final step is not counted
+    pub fn progress(&self) -> (usize, usize) {
+        let total = self.sweeper.points().len() - 2;
+        (self.next.min(total), total)
+    }
+
+    /// Run remaining steps, then return the result
+    pub fn finish(mut self) -> Triangles {
+        while !self.resume() {}
+        let result = self.result.take().unwrap_or_default();
+        self.sweeper
+            .into_triangles(self.triangles, result, self.edge_errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::loader;
+    use crate::testkit::snapshot;
+    use crate::Point;
+
+    #[test]
+    fn test_chunked() {
+        let builder = loader::load_file("test_data/bird.dat")
+            .unwrap()
+            .add_weighted_steiner_points([(Point::new(0., 0.), 0.5), (Point::new(0.1, 0.), 0.)]);
+        let expected = snapshot(builder.clone().build().triangulate_with_observer(&mut ()));
+
+        for chunk_size in [0, 1, 7, 100_000] {
+            let mut chunked = builder.clone().build().triangulate_chunked(chunk_size);
+            let (_, total) = chunked.progress();
+            let mut steps = 1;
+            while !chunked.resume() {
+                steps += 1;
+            }
+            // done stays done
+            assert!(chunked.resume());
+            assert_eq!(chunked.progress(), (total, total));
+            // point events in chunks, then the final step
+            let events = total - 1;
+            assert_eq!(steps, events.div_ceil(chunk_size.max(1)) + 1);
+            assert_eq!(snapshot(chunked.finish()), expected);
+        }
+
+        // finish runs what is left
+        let chunked = builder.build().triangulate_chunked(3);
+        assert_eq!(snapshot(chunked.finish()), expected);
+    }
+}
@@ -0,0 +1,168 @@
+//! Run the same input through [spade](https://docs.rs/spade) and diff the
+//! results, for benchmarks against another triangulator and for catching
+//! divergence on inputs where the constrained delaunay triangulation is unique.
+//!
+//! spade is the only backend, it takes constraint edges like the sweep does.
+//! It is an optional dependency rather than a dev-dependency since cargo can't
+//! make dev-dependencies optional, and this module is public for downstream
+//! tests to use.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{compare, Point, SweeperBuilder};
+//!
+//!    let builder = SweeperBuilder::new(vec![
+//!        Point::new(0., 0.),
+//!        Point::new(10., 0.),
+//!        Point::new(10., 10.),
+//!        Point::new(0., 10.),
+//!    ])
+//!    .add_steiner_points([Point::new(3., 4.), Point::new(7., 2.), Point::new(6., 8.)]);
+//!    let divergence = compare::with_spade(builder).unwrap();
+//!    assert!(divergence.is_empty(), "{divergence:?}");
+//! ```
+use std::collections::HashSet;
+
+use spade::{ConstrainedDelaunayTriangulation, InsertionError, Point2, Triangulation};
+
+use crate::{SweeperBuilder, Triangles};
+
+/// spade's constrained delaunay triangulation
+pub type SpadeCdt = ConstrainedDelaunayTriangulation<Point2<f64>>;
+
+/// Triangles found by only one side, as vertex indices rotated to start at the
+/// smallest one
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Divergence {
+    pub only_p2t: Vec<[usize; 3]>,
+    pub only_other: Vec<[usize; 3]>,
+}
+
+impl Divergence {
+    pub fn is_empty(&self) -> bool {
+        self.only_p2t.is_empty() && self.only_other.is_empty()
+    }
+}
+
+/// Triangulate the input of `builder` by spade, the rings are constraints.
+/// Vertex `i` is vertex `i` of the [`Triangles`] built by `builder`. Input must
+/// have no duplicated points, spade merges them and the indices shift.
+pub fn spade_cdt(builder: &SweeperBuilder) -> Result<SpadeCdt, InsertionError> {
+    let vertices = builder
+        .vertices()
+        .map(|point| Point2::new(point.x, point.y))
+        .collect();
+    let edges = builder
+        .rings()
+        .iter()
+        .flat_map(|ring| {
+            (0..ring.len()).map(|i| [ring[i].as_usize(), ring[(i + 1) % ring.len()].as_usize()])
+        })
+        .collect();
+    SpadeCdt::bulk_load_cdt(vertices, edges)
+}
+
+/// Triangles of `cdt` inside the rings, CCW. spade triangulates the convex hull,
+/// a face is inside if an odd number of constraints lies between it and the
+/// outer face, same as holes and islands in holes of the sweep.
+pub fn spade_triangles(cdt: &SpadeCdt) -> Vec<[usize; 3]> {
+    // constraints crossed from the outer face, by face index
+    let mut depth = vec![usize::MAX; cdt.num_all_faces()];
+    let mut stack = vec![];
+    for face in cdt.inner_faces() {
+        for edge in face.adjacent_edges() {
+            if edge.rev().face().is_outer() {
+                depth[face.fix().index()] = edge.is_constraint_edge() as usize;
+                stack.push(face);
+                break;
+            }
+        }
+    }
+    while let Some(face) = stack.pop() {
+        let index = face.fix().index();
+        for edge in face.adjacent_edges() {
+            let Some(next) = edge.rev().face().as_inner() else {
+                continue;
+            };
+            let next_index = next.fix().index();
+            let next_depth = depth[index] + edge.is_constraint_edge() as usize;
+            // a face reached first by a longer path is lowered again
+            if next_depth < depth[next_index] {
+                depth[next_index] = next_depth;
+                stack.push(next);
+            }
+        }
+    }
+
+    cdt.inner_faces()
+        .filter(|face| depth[face.fix().index()] % 2 == 1)
+        .map(|face| face.vertices().map(|v| v.fix().index()))
+        .collect()
+}
+
+/// Triangulate by both, then diff the triangles
+pub fn with_spade(builder: SweeperBuilder) -> Result<Divergence, InsertionError> {
+    let cdt = spade_cdt(&builder)?;
+    let other = spade_triangles(&cdt);
+    Ok(diff(&builder.build().triangulate(), &other))
+}
+
+/// Diff the result against triangles of another triangulator, in the same
+/// vertex indices
+pub fn diff(triangles: &Triangles, other: &[[usize; 3]]) -> Divergence {
+    let mut indices = vec![0; triangles.triangle_count() * 3];
+    triangles.write_indices_into(&mut indices);
+    let p2t = indices
+        .chunks_exact(3)
+        .map(|t| canonical([t[0], t[1], t[2]].map(|i| i as usize)))
+        .collect::<HashSet<_>>();
+    let other = other.iter().copied().map(canonical).collect::<HashSet<_>>();
+
+    let mut divergence = Divergence {
+        only_p2t: p2t.difference(&other).copied().collect(),
+        only_other: other.difference(&p2t).copied().collect(),
+    };
+    divergence.only_p2t.sort();
+    divergence.only_other.sort();
+    divergence
+}
+
+/// rotate to start at the smallest index, orientation is kept
+fn canonical(t: [usize; 3]) -> [usize; 3] {
+    let min = (0..3).min_by_key(|i| t[*i]).unwrap();
+    [t[min], t[(min + 1) % 3], t[(min + 2) % 3]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{datasets, Point};
+
+    #[test]
+    fn test_with_spade() {
+        let pentagon = (0..5)
+            .map(|k| {
+                let angle = k as f64 * std::f64::consts::TAU / 5. + 0.1;
+                Point::new(500. + 200. * angle.cos(), 500. + 200. * angle.sin())
+            })
+            .collect();
+        for builder in [
+            datasets::uniform(1000, 1),
+            datasets::uniform(500, 2).add_hole(pentagon),
+            datasets::contour(1000, 3),
+            datasets::spiral(4),
+        ] {
+            let divergence = with_spade(builder).unwrap();
+            assert!(divergence.is_empty(), "{divergence:?}");
+        }
+
+        // a missing triangle shows up on the other side
+        let builder = datasets::uniform(10, 4);
+        let cdt = spade_cdt(&builder).unwrap();
+        let mut other = spade_triangles(&cdt);
+        let dropped = canonical(other.pop().unwrap());
+        let divergence = diff(&builder.build().triangulate(), &other);
+        assert_eq!(divergence.only_p2t, [dropped]);
+        assert!(divergence.only_other.is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+/// Storage used by the advancing front during sweep
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FrontBackend {
+    /// sorted vec, fastest for small and medium fronts, default
+    #[default]
+    Vec,
+    /// btree map, insert and delete stay O(log n) for very wide fronts
+    BTree,
+    /// sorted chunks of about √n nodes, a middle ground between the two
+    Chunked,
+}
+
+/// Arithmetic of the orientation tests driving the sweep, in circle tests are
+/// adaptive with either.
+///
+/// The `robust` crate behind the adaptive predicates is an unconditional
+/// dependency, there is no `robust` feature. Setting [`Predicates::Adaptive`]
+/// through [`SweepConfig::predicates`] is the only switch.
+///
+/// The `exact_orient` feature makes both modes exact, with a rational fallback
+/// where the adaptive predicate under or overflows, so the choice only matters
+/// without it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Predicates {
+    /// plain f64 determinant, may misjudge nearly collinear points. Exact with
+    /// the `exact_orient` feature.
+    #[default]
+    Fast,
+    /// adaptive precision, the sign is exact for any finite input and only
+    /// costs more near degenerate cases, e.g. noisy CAD or GIS data with nearly
+    /// collinear boundary points
+    Adaptive,
+}
+
+/// Order legalization visits triangles queued by flips
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LegalizeOrder {
+    /// last queued first, default
+    #[default]
+    Lifo,
+    /// first queued first
+    Fifo,
+}
+
+/// When legalization marks an edge delaunay, so it is skipped until one of its
+/// triangles is relinked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DelaunayFlag {
+    /// edges made by a flip, default
+    #[default]
+    Flipped,
+    /// edges made by a flip, and edges found legal
+    Checked,
+    /// never, every edge is tested on each visit
+    Off,
+    /// edges made by a flip, only within the same legalize call. Flags left by
+    /// earlier calls are stale and the edge is tested again. Edges keep 14 bits
+    /// of the call count, so all flags are cleared once per 2^14 calls
+    Tick,
+}
+
+/// How edges are legalized after each event, see [`SweepConfig::legalize`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LegalizeStrategy {
+    pub order: LegalizeOrder,
+    pub delaunay_flag: DelaunayFlag,
+}
+
+impl LegalizeStrategy {
+    /// set the order of queued triangles
+    pub fn order(mut self, order: LegalizeOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// set when edges are marked delaunay
+    pub fn delaunay_flag(mut self, delaunay_flag: DelaunayFlag) -> Self {
+        self.delaunay_flag = delaunay_flag;
+        self
+    }
+}
+
+/// Axis the sweep advances along, see [`SweepConfig::sweep_axis`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SweepAxis {
+    /// upwards, default
+    #[default]
+    Y,
+    /// rightwards, input is turned by 90 degrees before sweep and back on output
+    X,
+    /// [`SweepAxis::X`] when input is over twice as wide as tall, the advancing
+    /// front spans the narrow side
+    Auto,
+}
+
+/// Placement of the two artificial points, head and tail, the sweep starts
+/// from. They are left and right below the input, the first triangle is them
+/// and the lowest point.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GhostPoints {
+    /// 0.3 of the width out to each side and 0.3 of the height below, default
+    #[default]
+    Extents,
+    /// 0.3 of the larger extent out to each side and below, the lower corners of
+    /// a square box around input. Very tall or very flat input gets head and
+    /// tail further out than the narrow extent, so the first front edges are not
+    /// nearly flat or nearly vertical.
+    Square,
+}
+
+/// Options to tune the sweep, set by [`crate::SweeperBuilder::with_config`]
+/// or picked by [`crate::Sweeper::auto_tune`]
+#[derive(Debug, Clone)]
+pub struct SweepConfig {
+    /// storage for advancing front
+    pub front_backend: FrontBackend,
+    /// initial capacity of the advancing front, `None` uses backend's default
+    pub front_capacity: Option<usize>,
+    /// initial capacity of the triangle store, `None` reserves 3 triangles per point
+    pub triangle_capacity: Option<usize>,
+    /// triangulate monotone polygons without holes and steiner points by the linear
+    /// monotone algorithm, enabled by default
+    pub monotone_fast_path: bool,
+    /// points whose x differ by at most this are snapped to one x before sweep, so
+    /// nearly vertical stacks are ordered by y in the advancing front, same as
+    /// exactly vertical ones. `0.` disables snapping. Snapped x shows up in the
+    /// result, keep it below the smallest horizontal feature of the input
+    pub x_tolerance: f64,
+    /// scale points by a power of two before sweep when coordinates are large enough
+    /// to overflow predicates, e.g. near 1e308, and scale back in the result.
+    /// Enabled by default
+    pub auto_rescale: bool,
+    /// legalization order and delaunay flag policy, only changes speed, the
+    /// result is delaunay with any of them
+    pub legalize: LegalizeStrategy,
+    /// axis the sweep advances along, the advancing front grows with the extent
+    /// across it
+    pub sweep_axis: SweepAxis,
+    /// shear input slightly when 3 or more points share an x, e.g. grids of CAD
+    /// drawings, so the advancing front has no ties on x. Undone on output, up to
+    /// rounding, see [`crate::PrecisionReport::transform`]. Disabled by default
+    pub shear_columns: bool,
+    /// placement of head and tail, the result is the same with any of them
+    pub ghost_points: GhostPoints,
+    /// arithmetic of orientation tests, fast by default
+    pub predicates: Predicates,
+}
+
+impl Default for SweepConfig {
+    fn default() -> Self {
+        Self {
+            front_backend: Default::default(),
+            front_capacity: None,
+            triangle_capacity: None,
+            monotone_fast_path: true,
+            x_tolerance: 0.,
+            auto_rescale: true,
+            legalize: Default::default(),
+            sweep_axis: Default::default(),
+            shear_columns: false,
+            ghost_points: Default::default(),
+            predicates: Default::default(),
+        }
+    }
+}
+
+impl SweepConfig {
+    /// set the advancing front backend
+    pub fn front_backend(mut self, front_backend: FrontBackend) -> Self {
+        self.front_backend = front_backend;
+        self
+    }
+
+    /// set the initial capacity of the advancing front
+    pub fn front_capacity(mut self, capacity: usize) -> Self {
+        self.front_capacity = Some(capacity);
+        self
+    }
+
+    /// enable or disable the monotone polygon fast path
+    pub fn monotone_fast_path(mut self, enabled: bool) -> Self {
+        self.monotone_fast_path = enabled;
+        self
+    }
+
+    /// set the tolerance to snap nearly vertical points
+    pub fn x_tolerance(mut self, tolerance: f64) -> Self {
+        self.x_tolerance = tolerance;
+        self
+    }
+
+    /// enable or disable rescaling of large coordinates
+    pub fn auto_rescale(mut self, enabled: bool) -> Self {
+        self.auto_rescale = enabled;
+        self
+    }
+
+    /// set the legalization strategy
+    pub fn legalize(mut self, strategy: LegalizeStrategy) -> Self {
+        self.legalize = strategy;
+        self
+    }
+
+    /// set the axis the sweep advances along
+    pub fn sweep_axis(mut self, axis: SweepAxis) -> Self {
+        self.sweep_axis = axis;
+        self
+    }
+
+    /// enable or disable shearing of columns of points
+    pub fn shear_columns(mut self, enabled: bool) -> Self {
+        self.shear_columns = enabled;
+        self
+    }
+
+    /// set the placement of head and tail
+    pub fn ghost_points(mut self, ghost_points: GhostPoints) -> Self {
+        self.ghost_points = ghost_points;
+        self
+    }
+
+    /// set the arithmetic of orientation tests, both are exact with the
+    /// `exact_orient` feature
+    pub fn predicates(mut self, predicates: Predicates) -> Self {
+        self.predicates = predicates;
+        self
+    }
+
+    /// set the initial capacity of the triangle store
+    pub fn triangle_capacity(mut self, capacity: usize) -> Self {
+        self.triangle_capacity = Some(capacity);
+        self
+    }
+}
@@ -1,5 +1,8 @@
+use std::collections::VecDeque;
+
 use crate::{
-    advancing_front::AdvancingFront, points::Points, triangles::TriangleStore, TriangleId,
+    advancing_front::AdvancingFront, points::Points, shape::EdgeAttr, triangles::TriangleStore,
+    DelaunayFlag, EdgeError, Legality, LegalizeStrategy, Predicates, TriangleId,
 };
 
 pub struct Context<'a> {
@@ -7,9 +10,19 @@ pub struct Context<'a> {
     pub triangles: &'a mut TriangleStore,
     pub advancing_front: &'a mut AdvancingFront,
     pub result: Vec<TriangleId>,
+    /// legalization predicate, delaunay if `None`
+    pub(crate) legality: Option<&'a dyn Legality>,
+    pub(crate) legalize: LegalizeStrategy,
+    /// arithmetic of orientation tests in the sweep
+    pub(crate) predicates: Predicates,
+    /// bumped by each legalize, delaunay flags of older ticks are stale with
+    /// [`crate::DelaunayFlag::Tick`]
+    pub(crate) legalize_tick: u32,
+    /// constrained edges the sweep gave up on
+    pub(crate) edge_errors: Vec<EdgeError>,
 
     // reusable legalize task queue to reduce alloc overhead
-    pub(crate) legalize_task_queue: Vec<TriangleId>,
+    pub(crate) legalize_task_queue: VecDeque<TriangleId>,
     // reusable legalize remap triangle ids to reduce alloc overhead
     pub(crate) legalize_remap_tids: Vec<TriangleId>,
     // reusable legalize triangle id queue
@@ -27,10 +40,29 @@ impl<'a> Context<'a> {
             triangles,
             advancing_front,
             result: Vec::with_capacity(points.len()),
+            legality: None,
+            legalize: Default::default(),
+            predicates: Default::default(),
+            legalize_tick: 0,
+            edge_errors: vec![],
 
-            legalize_task_queue: Vec::with_capacity(32),
+            legalize_task_queue: VecDeque::with_capacity(32),
             legalize_remap_tids: Vec::with_capacity(32),
             triangle_id_queue: Vec::with_capacity(32),
         }
     }
+
+    /// start a new legalize tick, returns it. Edges keep the low
+    /// [`EdgeAttr::TICK_BITS`] of it only, so each time those wrap the
+    /// [`DelaunayFlag::Tick`] flags are cleared, or a flag of a full period ago
+    /// would look current
+    pub(crate) fn tick_legalize(&mut self) -> u32 {
+        self.legalize_tick = self.legalize_tick.wrapping_add(1);
+        if self.legalize_tick & EdgeAttr::TICK_MASK == 0
+            && self.legalize.delaunay_flag == DelaunayFlag::Tick
+        {
+            self.triangles.clear_delaunay_flags();
+        }
+        self.legalize_tick
+    }
 }
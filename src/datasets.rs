@@ -0,0 +1,121 @@
+//! Generated inputs for benchmarks and stress tests, each one stresses a
+//! different part of the sweep. Generation is deterministic, the same
+//! arguments give the same points on every platform.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{datasets, FrontBackend, SweepConfig};
+//!
+//!    let triangles = datasets::uniform(1000, 0)
+//!        .with_config(SweepConfig::default().front_backend(FrontBackend::BTree))
+//!        .build()
+//!        .triangulate();
+//!    assert_eq!(triangles.count(), 2002);
+//! ```
+use std::f64::consts::TAU;
+
+use crate::{Point, SweeperBuilder};
+
+/// splitmix64, enough for spreading points, no dependency on rand
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn rect(min: f64, max: f64) -> Vec<Point> {
+    vec![
+        Point::new(min, min),
+        Point::new(max, min),
+        Point::new(max, max),
+        Point::new(min, max),
+    ]
+}
+
+/// `count` random steiner points in a 1000 by 1000 square, a delaunay workload
+/// of point events and legalization
+pub fn uniform(count: usize, seed: u64) -> SweeperBuilder {
+    let mut rng = Rng(seed);
+    let points = (0..count)
+        .map(|_| Point::new(rng.next_f64() * 1000., rng.next_f64() * 1000.))
+        .collect::<Vec<_>>();
+    SweeperBuilder::new(rect(-10., 1010.)).add_steiner_points(points)
+}
+
+/// Star shaped outline of `count` vertices with a noisy radius, like the
+/// nazca contours, a workload of edge events and front fills
+pub fn contour(count: usize, seed: u64) -> SweeperBuilder {
+    let mut rng = Rng(seed);
+    let points = (0..count)
+        .map(|i| {
+            let angle = i as f64 * TAU / count as f64;
+            let radius = 100. * (1. + 0.3 * (angle * 7.).sin() + 0.2 * rng.next_f64());
+            Point::new(radius * angle.cos(), radius * angle.sin())
+        })
+        .collect();
+    SweeperBuilder::new(points)
+}
+
+/// Square panel with `side * side` octagon holes in a grid, a workload of
+/// many rings
+pub fn perforated(side: usize) -> SweeperBuilder {
+    let holes = (0..side * side).map(|i| {
+        let (x, y) = ((i % side) as f64 * 10. + 5., (i / side) as f64 * 10. + 5.);
+        (0..8)
+            .map(|k| {
+                let angle = k as f64 * TAU / 8.;
+                Point::new(x + 2. * angle.cos(), y + 2. * angle.sin())
+            })
+            .collect()
+    });
+    SweeperBuilder::new(rect(0., side as f64 * 10.)).add_holes(holes)
+}
+
+/// Thin band winding `turns` times around an archimedean spiral, sampled by
+/// 64 points per turn on each side. Its long skinny constraints cross many
+/// triangles, a workload of flip chains.
+pub fn spiral(turns: usize) -> SweeperBuilder {
+    let count = turns * 64;
+    let centerline = (0..=count).map(|i| TAU + i as f64 * TAU / 64.);
+    // arms are TAU apart, the band is far thinner
+    let side = |offset: f64| {
+        centerline.clone().map(move |angle| {
+            let radius = angle + offset;
+            Point::new(radius * angle.cos(), radius * angle.sin())
+        })
+    };
+    let points = side(0.05).chain(side(-0.05).rev()).collect();
+    SweeperBuilder::new(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::snapshot;
+
+    #[test]
+    fn test_datasets() {
+        assert_eq!(uniform(100, 1).build().triangulate().count(), 202);
+        // same seed, same points
+        assert_eq!(
+            snapshot(uniform(10, 3).build().triangulate()),
+            snapshot(uniform(10, 3).build().triangulate())
+        );
+
+        assert_eq!(contour(500, 0).build().triangulate().count(), 498);
+        // 4 + 8 * 9 points, 9 holes
+        assert_eq!(perforated(3).build().triangulate().count(), 92);
+        assert_eq!(spiral(3).build().triangulate().count(), 2 * 193 - 2);
+    }
+}
@@ -0,0 +1,188 @@
+//! Ear clipping of a small simple polygon in a `const` context, for embedding
+//! fixed UI shapes as static index arrays. No holes, no steiner points and no
+//! delaunay flips, it is O(n³) and meant for shapes of a few dozen points.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{ear_clip, Point};
+//!
+//!    // arrow pointing right
+//!    const ARROW: [Point; 7] = [
+//!        Point::new(0., 1.),
+//!        Point::new(2., 1.),
+//!        Point::new(2., 0.),
+//!        Point::new(4., 2.),
+//!        Point::new(2., 4.),
+//!        Point::new(2., 3.),
+//!        Point::new(0., 3.),
+//!    ];
+//!    // 3 indices for each of the n - 2 triangles
+//!    static INDICES: [u32; 15] = ear_clip::triangulate(&ARROW);
+//!    assert!(INDICES.iter().all(|i| *i < 7));
+//! ```
+use crate::Point;
+
+/// Vertex indices of `N - 2` triangles covering the polygon `points`, CCW
+/// whichever way the polygon winds. `M` must be `3 * (N - 2)`.
+///
+/// Input must be a simple polygon. Panics, or fails to compile in a const, when
+/// it has no area or clipping runs out of ears, e.g. on some self intersecting
+/// ones.
+pub const fn triangulate<const N: usize, const M: usize>(points: &[Point; N]) -> [u32; M] {
+    assert!(N >= 3 && M == 3 * (N - 2), "M must be 3 * (N - 2)");
+
+    // twice the signed area, its sign tells convex corners from reflex ones
+    let mut area = 0.;
+    let mut i = 0;
+    while i < N {
+        let (p, q) = (points[i], points[(i + 1) % N]);
+        area += p.x * q.y - q.x * p.y;
+        i += 1;
+    }
+    assert!(area != 0., "polygon has no area");
+    let sign = if area > 0. { 1. } else { -1. };
+
+    let mut prev = [0; N];
+    let mut next = [0; N];
+    let mut i = 0;
+    while i < N {
+        prev[i] = (i + N - 1) % N;
+        next[i] = (i + 1) % N;
+        i += 1;
+    }
+
+    let mut indices = [0; M];
+    let mut written = 0;
+    let mut remaining = N;
+    // corners checked since the last ear, a full round without one means the
+    // polygon isn't simple
+    let mut checked = 0;
+    let mut i = 0;
+    while remaining > 3 {
+        assert!(checked <= remaining, "polygon is not simple");
+        let (a, c) = (prev[i], next[i]);
+        if is_ear(points, &next, a, i, c, sign) {
+            written = push(&mut indices, written, a, i, c, sign);
+            next[a] = c;
+            prev[c] = a;
+            remaining -= 1;
+            checked = 0;
+            i = a;
+        } else {
+            checked += 1;
+            i = next[i];
+        }
+    }
+    push(&mut indices, written, prev[i], i, next[i], sign);
+    indices
+}
+
+/// Corner `b` is convex and no other remaining point is inside or on the
+/// triangle `a b c`
+const fn is_ear(points: &[Point], next: &[usize], a: usize, b: usize, c: usize, sign: f64) -> bool {
+    let (pa, pb, pc) = (points[a], points[b], points[c]);
+    if sign * orient(pa, pb, pc) <= 0. {
+        return false;
+    }
+    let mut j = next[c];
+    while j != a {
+        let p = points[j];
+        if sign * orient(pa, pb, p) >= 0.
+            && sign * orient(pb, pc, p) >= 0.
+            && sign * orient(pc, pa, p) >= 0.
+        {
+            return false;
+        }
+        j = next[j];
+    }
+    true
+}
+
+/// Write triangle `a b c` CCW at `written`, returns the new count
+const fn push(
+    indices: &mut [u32],
+    written: usize,
+    a: usize,
+    b: usize,
+    c: usize,
+    sign: f64,
+) -> usize {
+    let (a, c) = if sign > 0. { (a, c) } else { (c, a) };
+    indices[written] = a as u32;
+    indices[written + 1] = b as u32;
+    indices[written + 2] = c as u32;
+    written + 3
+}
+
+const fn orient(a: Point, b: Point, c: Point) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils;
+
+    const L_SHAPE: [Point; 6] = [
+        Point::new(0., 0.),
+        Point::new(4., 0.),
+        Point::new(4., 1.),
+        Point::new(1., 1.),
+        Point::new(1., 3.),
+        Point::new(0., 3.),
+    ];
+    const L_INDICES: [u32; 12] = triangulate(&L_SHAPE);
+
+    fn area(points: &[Point], indices: &[u32]) -> f64 {
+        indices
+            .chunks_exact(3)
+            .map(|t| {
+                let [a, b, c] = [t[0], t[1], t[2]].map(|i| points[i as usize]);
+                let area = utils::orient(a, b, c);
+                assert!(area > 0., "{t:?} is not ccw");
+                area / 2.
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_ear_clip() {
+        assert_eq!(area(&L_SHAPE, &L_INDICES), 6.);
+
+        // cw winding gives ccw triangles too
+        let mut reversed = L_SHAPE;
+        reversed.reverse();
+        let indices: [u32; 12] = triangulate(&reversed);
+        assert_eq!(area(&reversed, &indices), 6.);
+
+        // comb, two notches of 1 by 2 cut from a 5 by 3 rect
+        let comb = [
+            Point::new(0., 0.),
+            Point::new(5., 0.),
+            Point::new(5., 3.),
+            Point::new(4., 3.),
+            Point::new(4., 1.),
+            Point::new(3., 1.),
+            Point::new(3., 3.),
+            Point::new(2., 3.),
+            Point::new(2., 1.),
+            Point::new(1., 1.),
+            Point::new(1., 3.),
+            Point::new(0., 3.),
+        ];
+        let indices: [u32; 30] = triangulate(&comb);
+        assert_eq!(area(&comb, &indices), 11.);
+    }
+
+    #[test]
+    #[should_panic(expected = "polygon")]
+    fn test_ear_clip_bowtie() {
+        let bowtie = [
+            Point::new(0., 0.),
+            Point::new(2., 2.),
+            Point::new(2., 0.),
+            Point::new(0., 2.),
+        ];
+        let _: [u32; 6] = triangulate(&bowtie);
+    }
+}
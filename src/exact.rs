@@ -0,0 +1,186 @@
+//! Exact orientation on rationals, the last resort when the adaptive predicate
+//! may overflow or underflow. Every finite f64 is an integer times `2^-1074`, so
+//! with that common denominator the determinant is computed on fixed width
+//! integers, which never round.
+use crate::shape::Point;
+use crate::utils::coord;
+
+/// 2^-1074 scaled coordinates take 2099 bits, products of their differences 4200
+const LIMBS: usize = 68;
+
+/// Coordinates where the adaptive predicate is known exact, no product of
+/// differences or of their roundoff tails leaves the normal f64 range
+const SAFE_MIN: f64 = 1e-120;
+const SAFE_MAX: f64 = 1e120;
+
+/// Signed integer in two's complement
+#[derive(Clone, Copy)]
+struct Wide([u64; LIMBS]);
+
+impl Wide {
+    const ZERO: Self = Self([0; LIMBS]);
+
+    /// `value * 2^1074`, exact for finite values
+    fn from_f64(value: f64) -> Self {
+        let bits = value.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as usize;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, shift) = match exponent {
+            0 => (fraction, 0),
+            _ => (fraction | 1 << 52, exponent - 1),
+        };
+
+        let mut wide = Self::ZERO;
+        let (limb, offset) = (shift / 64, shift % 64);
+        wide.0[limb] = mantissa << offset;
+        if offset > 11 {
+            wide.0[limb + 1] = mantissa >> (64 - offset);
+        }
+        if value.is_sign_negative() {
+            wide.neg()
+        } else {
+            wide
+        }
+    }
+
+    fn is_negative(&self) -> bool {
+        self.0[LIMBS - 1] >> 63 == 1
+    }
+
+    fn neg(self) -> Self {
+        let mut result = Self::ZERO;
+        let mut carry = true;
+        for (r, l) in result.0.iter_mut().zip(self.0) {
+            (*r, carry) = (!l).overflowing_add(carry as u64);
+        }
+        result
+    }
+
+    fn add(self, other: Self) -> Self {
+        let mut result = Self::ZERO;
+        let mut carry = false;
+        for ((r, l), o) in result.0.iter_mut().zip(self.0).zip(other.0) {
+            let (sum, c1) = l.overflowing_add(o);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            (*r, carry) = (sum, c1 || c2);
+        }
+        result
+    }
+
+    fn sub(self, other: Self) -> Self {
+        self.add(other.neg())
+    }
+
+    fn abs(self) -> Self {
+        if self.is_negative() {
+            self.neg()
+        } else {
+            self
+        }
+    }
+
+    /// product, caller makes sure it fits
+    fn mul(self, other: Self) -> Self {
+        let (l, r) = (self.abs(), other.abs());
+        let mut result = Self::ZERO;
+        for i in (0..LIMBS).filter(|i| l.0[*i] != 0) {
+            let mut carry = 0u128;
+            for j in 0..LIMBS - i {
+                let t = l.0[i] as u128 * r.0[j] as u128 + result.0[i + j] as u128 + carry;
+                result.0[i + j] = t as u64;
+                carry = t >> 64;
+            }
+        }
+        if self.is_negative() != other.is_negative() {
+            result.neg()
+        } else {
+            result
+        }
+    }
+
+    fn signum(&self) -> f64 {
+        if self.is_negative() {
+            -1.
+        } else if self.0.iter().any(|limb| *limb != 0) {
+            1.
+        } else {
+            0.
+        }
+    }
+}
+
+/// Whether the adaptive predicate is exact for `points`
+fn is_safe(points: [Point; 3]) -> bool {
+    points
+        .iter()
+        .flat_map(|p| [p.x, p.y])
+        .all(|v| v == 0. || (SAFE_MIN..=SAFE_MAX).contains(&v.abs()))
+}
+
+/// Sign of the exact orientation determinant of `a`, `b`, `c`, positive when ccw
+fn exact_orient2d(a: Point, b: Point, c: Point) -> f64 {
+    let [ax, ay, bx, by, cx, cy] = [a.x, a.y, b.x, b.y, c.x, c.y].map(Wide::from_f64);
+    let left = ax.sub(cx).mul(by.sub(cy));
+    let right = ay.sub(cy).mul(bx.sub(cx));
+    left.sub(right).signum()
+}
+
+/// Orientation determinant of `a`, `b`, `c`, or a value of the same sign. Adaptive
+/// for inputs in its safe range, exact rationals for the rest
+pub(crate) fn orient2d(a: Point, b: Point, c: Point) -> f64 {
+    let finite = [a, b, c].iter().all(|p| p.x.is_finite() && p.y.is_finite());
+    if !finite || is_safe([a, b, c]) {
+        return robust::orient2d(coord(a), coord(b), coord(c));
+    }
+    exact_orient2d(a, b, c)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_exact_orient() {
+        // products underflow, adaptive says collinear
+        let u = f64::from_bits(1);
+        let (a, b, c) = (Point::new(0., 0.), Point::new(3. * u, u), Point::new(u, u));
+        assert_eq!(robust::orient2d(coord(a), coord(b), coord(c)), 0.);
+        assert_eq!(orient2d(a, b, c), 1.);
+        assert_eq!(orient2d(b, a, c), -1.);
+        assert_eq!(orient2d(a, b, Point::new(6. * u, 2. * u)), 0.);
+
+        // differences overflow
+        let max = f64::MAX;
+        let (a, b) = (Point::new(-max, -max), Point::new(max, max));
+        assert!(!robust::orient2d(coord(a), coord(b), coord(Point::new(0., u))).is_finite());
+        assert_eq!(orient2d(a, b, Point::new(0., u)), 1.);
+        assert_eq!(orient2d(a, b, Point::new(u, 0.)), -1.);
+        assert_eq!(orient2d(a, b, Point::new(0., 0.)), 0.);
+    }
+
+    #[test]
+    fn test_exact_matches_adaptive() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let mut point = || {
+                // snap to a coarse grid now and then for collinear cases
+                let scale = [1e-3, 1., 1e6][rng.gen_range(0..3)];
+                let (x, y): (f64, f64) = (rng.gen_range(-1.0..1.), rng.gen_range(-1.0..1.));
+                match rng.gen_bool(0.5) {
+                    true => Point::new((x * 4.).round() * scale, (y * 4.).round() * scale),
+                    false => Point::new(x * scale, y * scale),
+                }
+            };
+            let (a, b, c) = (point(), point(), point());
+            let adaptive = robust::orient2d(coord(a), coord(b), coord(c));
+            let sign = match adaptive {
+                v if v > 0. => 1.,
+                v if v < 0. => -1.,
+                _ => 0.,
+            };
+            assert_eq!(exact_orient2d(a, b, c), sign);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use wgpu::util::DeviceExt;
+
+use crate::{PositionFormat, Triangles, VertexBuffers, VertexLayout, VertexWriter};
+
+/// Vertex and index buffers uploaded to a wgpu device.
+///
+/// Shader locations: position at 0, uv at 1, color at 2, missing attributes are
+/// skipped, locations are not shifted.
+pub struct GpuBuffers {
+    pub vertex: wgpu::Buffer,
+    pub index: wgpu::Buffer,
+    /// number of u32 indices, for `draw_indexed`
+    pub index_count: u32,
+    pub layout: VertexLayout,
+}
+
+impl GpuBuffers {
+    /// Attributes for [`wgpu::VertexBufferLayout`]
+    pub fn vertex_attributes(&self) -> Vec<wgpu::VertexAttribute> {
+        let mut attributes = vec![wgpu::VertexAttribute {
+            format: match self.layout.position_format {
+                PositionFormat::F32x2 => wgpu::VertexFormat::Float32x2,
+                PositionFormat::F32x3 => wgpu::VertexFormat::Float32x3,
+            },
+            offset: self.layout.position as u64,
+            shader_location: 0,
+        }];
+        if let Some(offset) = self.layout.uv {
+            attributes.push(wgpu::VertexAttribute {
+                format: wgpu::VertexFormat::Float32x2,
+                offset: offset as u64,
+                shader_location: 1,
+            });
+        }
+        if let Some(offset) = self.layout.color {
+            attributes.push(wgpu::VertexAttribute {
+                format: wgpu::VertexFormat::Unorm8x4,
+                offset: offset as u64,
+                shader_location: 2,
+            });
+        }
+        attributes
+    }
+
+    /// Stride for [`wgpu::VertexBufferLayout`], wgpu requires it to be a multiple of 4
+    pub fn array_stride(&self) -> u64 {
+        self.layout.stride as u64
+    }
+}
+
+impl VertexBuffers {
+    /// Upload to `device`
+    pub fn gpu_buffers(&self, device: &wgpu::Device) -> GpuBuffers {
+        let vertex = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("p2t vertex"),
+            contents: &self.vertices,
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let indices = self
+            .indices
+            .iter()
+            .flat_map(|i| i.to_le_bytes())
+            .collect::<Vec<_>>();
+        let index = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("p2t index"),
+            contents: &indices,
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        GpuBuffers {
+            vertex,
+            index,
+            index_count: self.indices.len() as u32,
+            layout: self.layout,
+        }
+    }
+}
+
+impl Triangles {
+    /// Upload the result with positions only, as f32x2
+    pub fn gpu_buffers(&self, device: &wgpu::Device) -> GpuBuffers {
+        VertexWriter::new().write(self).gpu_buffers(device)
+    }
+}
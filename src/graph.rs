@@ -0,0 +1,81 @@
+use petgraph::graph::{NodeIndex, UnGraph};
+
+use crate::{Triangle, Triangles};
+
+/// Dual graph of the result, see [`Triangles::dual_graph`]
+pub type DualGraph = UnGraph<Triangle, f64>;
+
+impl Triangles {
+    /// Triangle adjacency as a petgraph graph, e.g. for shortest paths over a
+    /// navmesh. Node `i` is result triangle `i`, in result order, same as
+    /// [`Self::write_indices_into`]. Triangles sharing an edge are linked once,
+    /// weighted by the length of the shared edge.
+    pub fn dual_graph(&self) -> DualGraph {
+        let count = self.triangle_count();
+        let mut graph = DualGraph::with_capacity(count, count * 3 / 2);
+        for index in 0..count {
+            graph.add_node(self.triangle(index).unwrap());
+        }
+
+        let mut adjacency = vec![0; count * 3];
+        self.write_adjacency_into(&mut adjacency);
+        for (index, neighbors) in adjacency.chunks_exact(3).enumerate() {
+            for (edge, neighbor) in neighbors.iter().enumerate() {
+                // border edges are u32::MAX, shared ones are seen from both sides
+                if *neighbor == u32::MAX || *neighbor as usize <= index {
+                    continue;
+                }
+                let points = graph[NodeIndex::new(index)].points;
+                let (p, q) = (points[(edge + 1) % 3], points[(edge + 2) % 3]);
+                graph.add_edge(
+                    NodeIndex::new(index),
+                    NodeIndex::new(*neighbor as usize),
+                    (p.x - q.x).hypot(p.y - q.y),
+                );
+            }
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::algo::connected_components;
+
+    use crate::{Point, SweeperBuilder};
+
+    #[test]
+    fn test_dual_graph() {
+        let triangles = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_hole(vec![
+            Point::new(4., 4.),
+            Point::new(4., 6.),
+            Point::new(6., 6.),
+            Point::new(6., 4.),
+        ])
+        .build()
+        .triangulate();
+
+        let graph = triangles.dual_graph();
+        assert_eq!(graph.node_count(), 8);
+        // a ring of 8 triangles around the hole
+        assert_eq!(graph.edge_count(), 8);
+        assert_eq!(connected_components(&graph), 1);
+        for edge in graph.edge_indices() {
+            let (a, b) = graph.edge_endpoints(edge).unwrap();
+            let shared = graph[a]
+                .points
+                .iter()
+                .filter(|p| graph[b].points.iter().any(|q| p.eq(q)))
+                .collect::<Vec<_>>();
+            assert_eq!(shared.len(), 2);
+            let length = (shared[0].x - shared[1].x).hypot(shared[0].y - shared[1].y);
+            assert_eq!(graph[edge], length);
+        }
+    }
+}
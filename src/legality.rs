@@ -0,0 +1,307 @@
+//! Pluggable legalization predicate, see [`Legality`].
+use crate::utils::{flippable, in_circle, in_power_circle};
+use crate::{Criterion, Point, PointId, Triangle};
+
+/// Decides whether an edge is flipped while legalizing, in place of the delaunay
+/// in circle test. E.g. anisotropic criteria driven by a metric tensor, or weighted
+/// ones looking up per point weights by id.
+///
+/// Used by [`crate::Sweeper::triangulate_with_legality`].
+pub trait Legality {
+    /// Whether edge `pb - pc`, shared by ccw triangle `pa pb pc` and triangle
+    /// `pc pb pd`, should be flipped to `pa - pd`. `ids` are in the same order.
+    /// Only asked for convex quads, where the flip keeps a valid mesh.
+    fn should_flip(&self, pa: Point, pb: Point, pc: Point, pd: Point, ids: [PointId; 4]) -> bool;
+}
+
+/// Default criterion, flip when `pd` is inside circumcircle of `pa pb pc`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Delaunay;
+
+impl Legality for Delaunay {
+    fn should_flip(&self, pa: Point, pb: Point, pc: Point, pd: Point, ids: [PointId; 4]) -> bool {
+        in_circle(pa, pb, pc, pd, ids.map(|id| id.as_usize()))
+    }
+}
+
+/// Weighted delaunay, for regular triangulations, e.g. bubble meshes and Laguerre
+/// diagrams. A point with larger weight claims more neighbors. Points whose power
+/// cell is empty are still kept as vertices.
+#[derive(Debug, Clone, Default)]
+pub struct PowerDelaunay {
+    /// weight by vertex index, missing ones are 0
+    weights: Vec<f64>,
+}
+
+impl PowerDelaunay {
+    /// Weights by vertex index, same as [`crate::Triangles::write_indices_into`]
+    pub fn new(weights: Vec<f64>) -> Self {
+        Self { weights }
+    }
+
+    fn weight(&self, id: PointId) -> f64 {
+        self.weights.get(id.as_usize()).copied().unwrap_or_default()
+    }
+}
+
+impl Legality for PowerDelaunay {
+    fn should_flip(&self, pa: Point, pb: Point, pc: Point, pd: Point, ids: [PointId; 4]) -> bool {
+        in_power_circle(pa, pb, pc, pd, ids.map(|id| self.weight(id)))
+    }
+}
+
+/// Approximate minimum weight triangulation, flip when the other diagonal is
+/// shorter. A local optimum of total edge length, meshes look tidier flat shaded.
+/// Mostly used as a post pass by [`crate::Triangles::relax_with`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinWeight;
+
+impl Legality for MinWeight {
+    fn should_flip(&self, pa: Point, pb: Point, pc: Point, pd: Point, _: [PointId; 4]) -> bool {
+        let length = |p: Point, q: Point| (p.x - q.x).powi(2) + (p.y - q.y).powi(2);
+        length(pa, pd) < length(pb, pc)
+    }
+}
+
+/// What the post flip pass [`crate::Triangles::relax_with`] optimizes, pick
+/// one per use case
+///
+/// # Example
+/// ```rust
+///    use poly2tri_rs::{FlipObjective, Point, SweeperBuilder};
+///
+///    let mut triangles = SweeperBuilder::new(vec![
+///        Point::new(0., 0.),
+///        Point::new(10., 0.),
+///        Point::new(10., 10.),
+///        Point::new(0., 10.),
+///    ])
+///    .add_steiner_points([Point::new(3., 4.), Point::new(6., 5.), Point::new(5., 8.)])
+///    .build()
+///    .triangulate();
+///
+///    // flat shaded art, short edges look tidier
+///    triangles.relax_with(&FlipObjective::ShortestEdges);
+///    assert_eq!(triangles.triangle_count(), 8);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FlipObjective {
+    /// maximize the smallest angle, i.e. delaunay, same as the sweep. Best for
+    /// interpolation and simulation meshes
+    #[default]
+    Delaunay,
+    /// minimize total edge length, see [`MinWeight`]. Tidy wireframes and flat
+    /// shading
+    ShortestEdges,
+    /// minimize the worse [`Criterion::RadiusEdgeRatio`] of each flipped pair,
+    /// for evenly shaped elements without caring for angles elsewhere
+    AspectRatio,
+}
+
+impl Legality for FlipObjective {
+    fn should_flip(&self, pa: Point, pb: Point, pc: Point, pd: Point, ids: [PointId; 4]) -> bool {
+        match self {
+            Self::Delaunay => Delaunay.should_flip(pa, pb, pc, pd, ids),
+            Self::ShortestEdges => MinWeight.should_flip(pa, pb, pc, pd, ids),
+            Self::AspectRatio => {
+                let worse = |t: [[Point; 3]; 2]| {
+                    t.map(|points| Criterion::RadiusEdgeRatio.measure(&Triangle { points }))
+                        .into_iter()
+                        .fold(0., f64::max)
+                };
+                worse([[pa, pb, pd], [pa, pd, pc]]) < worse([[pa, pb, pc], [pc, pb, pd]])
+            }
+        }
+    }
+}
+
+/// Whether the edge is illegal under `legality`, [`Delaunay`] if `None`
+#[inline]
+pub(crate) fn is_illegal(
+    legality: Option<&dyn Legality>,
+    pa: Point,
+    pb: Point,
+    pc: Point,
+    pd: Point,
+    ids: [PointId; 4],
+) -> bool {
+    match legality {
+        None => in_circle(pa, pb, pc, pd, ids.map(|id| id.as_usize())),
+        Some(legality) => flippable(pa, pb, pc, pd) && legality.should_flip(pa, pb, pc, pd, ids),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::{SweeperBuilder, Triangles};
+
+    /// delaunay in a space stretched along x
+    struct Stretch(f64);
+
+    impl Legality for Stretch {
+        fn should_flip(
+            &self,
+            pa: Point,
+            pb: Point,
+            pc: Point,
+            pd: Point,
+            ids: [PointId; 4],
+        ) -> bool {
+            let stretch = |p: Point| Point::new(p.x * self.0, p.y);
+            Delaunay.should_flip(stretch(pa), stretch(pb), stretch(pc), stretch(pd), ids)
+        }
+    }
+
+    struct Never;
+
+    impl Legality for Never {
+        fn should_flip(&self, _: Point, _: Point, _: Point, _: Point, _: [PointId; 4]) -> bool {
+            false
+        }
+    }
+
+    fn sorted_indices(triangles: &Triangles) -> Vec<[u32; 3]> {
+        let mut indices = vec![0; triangles.index_count()];
+        triangles.write_indices_into(&mut indices);
+        let mut triangles = indices
+            .chunks_exact(3)
+            .map(|t| {
+                let mut t = [t[0], t[1], t[2]];
+                t.sort();
+                t
+            })
+            .collect::<Vec<_>>();
+        triangles.sort();
+        triangles
+    }
+
+    #[test]
+    fn test_legality() {
+        let mut rng = rand::thread_rng();
+        let points = (0..200)
+            .map(|_| Point::new(rng.gen_range(1.0..99.), rng.gen_range(1.0..99.)))
+            .collect::<Vec<_>>();
+        let builder = |scale: f64| {
+            SweeperBuilder::new(vec![
+                Point::new(0., 0.),
+                Point::new(100. * scale, 0.),
+                Point::new(100. * scale, 100.),
+                Point::new(0., 100.),
+            ])
+            .add_steiner_points(points.iter().map(|p| Point::new(p.x * scale, p.y)))
+        };
+
+        let delaunay = builder(1.).build().triangulate();
+        let with_default = builder(1.).build().triangulate_with_legality(&Delaunay);
+        assert_eq!(sorted_indices(&delaunay), sorted_indices(&with_default));
+
+        // same as delaunay of the stretched points
+        let stretched = builder(1.).build().triangulate_with_legality(&Stretch(4.));
+        let expected = builder(4.).build().triangulate();
+        assert_eq!(sorted_indices(&stretched), sorted_indices(&expected));
+        assert_ne!(sorted_indices(&stretched), sorted_indices(&delaunay));
+
+        // still a triangulation without any flip
+        let never = builder(1.).build().triangulate_with_legality(&Never);
+        assert_eq!(never.index_count(), delaunay.index_count());
+    }
+
+    #[test]
+    fn test_min_weight() {
+        let mut rng = rand::thread_rng();
+        let builder = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(100., 0.),
+            Point::new(100., 100.),
+            Point::new(0., 100.),
+        ])
+        .add_steiner_points(
+            (0..300).map(|_| Point::new(rng.gen_range(1.0..99.), rng.gen_range(1.0..99.))),
+        );
+        let total_length = |triangles: &Triangles| {
+            // inner edges count twice, boundary ones stay put
+            (0..triangles.triangle_count())
+                .map(|index| triangles.triangle(index).unwrap())
+                .flat_map(|t| (0..3).map(move |i| (t.points[i], t.points[(i + 1) % 3])))
+                .map(|(p, q)| ((p.x - q.x).powi(2) + (p.y - q.y).powi(2)).sqrt())
+                .sum::<f64>()
+        };
+
+        let mut triangles = builder.build().triangulate();
+        let count = triangles.triangle_count();
+        let delaunay = total_length(&triangles);
+        assert!(triangles.relax_with(&MinWeight) > 0);
+        assert!(total_length(&triangles) < delaunay);
+        assert_eq!(triangles.triangle_count(), count);
+        assert!(triangles.validate().is_ok());
+
+        // each flip lowers the worse of its pair, so the worst of the mesh never
+        // goes up
+        let worst =
+            |triangles: &Triangles| triangles.worst_triangles(1, Criterion::RadiusEdgeRatio)[0].1;
+        let before = worst(&triangles);
+        triangles.relax_with(&FlipObjective::AspectRatio);
+        assert!(worst(&triangles) <= before);
+        assert!(triangles.validate().is_ok());
+    }
+
+    #[test]
+    fn test_weighted() {
+        // jittered grid, the center point is the first steiner point
+        let center = Point::new(50., 50.);
+        let points = std::iter::once(center)
+            .chain(
+                (1..10)
+                    .flat_map(|y| (1..10).map(move |x| (x, y)))
+                    .filter(|(x, y)| *x != 5 || *y != 5)
+                    .map(|(x, y)| {
+                        let jitter = ((x * 7 + y * 3) % 5) as f64 * 0.3 - 0.6;
+                        Point::new(x as f64 * 10. + jitter, y as f64 * 10.)
+                    }),
+            )
+            .collect::<Vec<_>>();
+        let outer = vec![
+            Point::new(0., 0.),
+            Point::new(100., 0.),
+            Point::new(100., 100.),
+            Point::new(0., 100.),
+        ];
+        let builder = |weights: &dyn Fn(usize) -> f64| {
+            SweeperBuilder::new(outer.clone()).add_weighted_steiner_points(
+                points.iter().enumerate().map(|(idx, p)| (*p, weights(idx))),
+            )
+        };
+        // center is vertex 4, after the outer polyline
+        let degree = |triangles: &Triangles| {
+            sorted_indices(triangles)
+                .iter()
+                .filter(|t| t.contains(&4))
+                .count()
+        };
+
+        let unweighted = SweeperBuilder::new(outer.clone())
+            .add_steiner_points(points.clone())
+            .build()
+            .triangulate();
+        let zero = builder(&|_| 0.).build().triangulate();
+        assert_eq!(sorted_indices(&zero), sorted_indices(&unweighted));
+
+        let weighted = builder(&|idx| if idx == 0 { 150. } else { 0. })
+            .build()
+            .triangulate();
+        // center claims all 8 grid neighbors
+        assert!(degree(&unweighted) < 8);
+        assert_eq!(degree(&weighted), 8);
+
+        let mut weights = vec![0.; 4];
+        weights.push(150.);
+        let with_legality = SweeperBuilder::new(outer.clone())
+            .add_steiner_points(points.clone())
+            .build()
+            .triangulate_with_legality(&PowerDelaunay::new(weights));
+        assert_eq!(sorted_indices(&weighted), sorted_indices(&with_legality));
+    }
+}
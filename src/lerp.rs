@@ -0,0 +1,34 @@
+//! Attributes interpolated over the mesh, see [`crate::Triangles::interpolate`]
+
+/// Linear interpolation of a vertex attribute, e.g. a terrain height or a color
+pub trait Lerp: Sized {
+    /// `self` at `t = 0`, `other` at `t = 1`
+    fn lerp(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Lerp for f64 {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t as f32
+    }
+}
+
+impl<T: Lerp, const N: usize> Lerp for [T; N] {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        std::array::from_fn(|i| self[i].lerp(&other[i], t))
+    }
+}
+
+/// `values` weighted by barycentric `weights`, summing to 1
+pub(crate) fn barycentric<T: Lerp>(values: [&T; 3], weights: [f64; 3]) -> T {
+    let [a, b, c] = values;
+    let ab = weights[0] + weights[1];
+    // all weight on c leaves nothing to split between a and b
+    let t = if ab > 0. { weights[1] / ab } else { 0. };
+    a.lerp(b, t).lerp(c, weights[2])
+}
@@ -1,18 +1,88 @@
 mod advancing_front;
+#[cfg(feature = "rkyv")]
+pub mod archive;
+mod chunked;
+#[cfg(feature = "compare")]
+pub mod compare;
+mod config;
 mod context;
+#[cfg(any(test, feature = "datasets"))]
+pub mod datasets;
+pub mod ear_clip;
+#[cfg(feature = "exact_orient")]
+mod exact;
+#[cfg(feature = "wgpu")]
+mod gpu;
+#[cfg(feature = "petgraph")]
+mod graph;
+mod legality;
+mod lerp;
 pub mod loader;
+mod locate;
+pub mod lod;
+mod metadata;
+mod monotone;
+pub mod nesting;
+mod observer_mask;
+mod origin;
+#[cfg(feature = "paranoid")]
+mod paranoid;
+pub mod periodic;
 mod points;
+mod quadratic;
+mod quality;
+mod region;
+mod repair;
+pub mod seam;
 mod shape;
+mod sink;
+pub mod stream;
 mod sweeper;
+#[cfg(any(test, feature = "testkit"))]
+pub mod testkit;
+pub mod tiling;
+pub mod trace;
+mod transform;
+mod triangle_vec;
 mod triangles;
+mod tune;
 mod utils;
-pub use sweeper::{Observer, Sweeper, SweeperBuilder};
+mod validate;
+mod vertex;
+pub use chunked::ChunkedTriangulation;
+pub use config::{
+    DelaunayFlag, FrontBackend, GhostPoints, LegalizeOrder, LegalizeStrategy, Predicates,
+    SweepAxis, SweepConfig,
+};
+#[cfg(feature = "wgpu")]
+pub use gpu::GpuBuffers;
+#[cfg(feature = "petgraph")]
+pub use graph::DualGraph;
+pub use legality::{Delaunay, FlipObjective, Legality, MinWeight, PowerDelaunay};
+pub use lerp::Lerp;
+pub use metadata::Metadata;
+pub use observer_mask::ObserverMask;
+pub use origin::{EdgeSplit, InputEdgeId, PolylineId};
+pub use quadratic::QuadraticMesh;
+pub use quality::Criterion;
+pub use repair::{RepairOptions, RepairReport};
+pub use sink::{OutputTriangle, TriangleSink};
+pub use sweeper::stages;
+pub use sweeper::{
+    ConstrainedEdge, ConstraintScope, CullReport, Decision, DegenerateHole, DuplicateEdge,
+    EdgeError, HasOuter, HoleError, Interceptor, NeedsOuter, Observer, PrecisionReport, RingsError,
+    Sweeper, SweeperBuilder, Triangles,
+};
+pub use transform::Affine;
+pub use triangle_vec::{TriangleVec, TriangleVecIter};
+pub use validate::{InputError, InputReport};
+pub use vertex::{PositionFormat, VertexBuffers, VertexLayout, VertexWriter};
 
 /// exported to enable observer
 pub use context::Context;
-pub use points::PointId;
+pub use points::{PointEdges, PointId, Points};
 pub use shape::{Edge, Point};
-pub use triangles::TriangleId;
+pub use triangles::{MeshError, TriangleId};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Triangle {
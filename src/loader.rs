@@ -6,6 +6,8 @@ pub enum LoaderError {
     Io(#[from] std::io::Error),
     #[error("Inner error")]
     Inner(#[from] Box<dyn std::error::Error>),
+    #[error("Parse error: {0}")]
+    Parse(String),
 }
 
 /// Loader loads source to a [`Sweeper`].
@@ -14,6 +16,16 @@ pub trait Loader {
     fn load(&mut self, source: &str) -> Result<SweeperBuilder, LoaderError>;
 }
 
+/// Load file at `path` with the loader picked by its extension, `.svg` goes to
+/// [`SvgLoader`], others to [`PlainFileLoader`]
+pub fn load_file(path: &str) -> Result<SweeperBuilder, LoaderError> {
+    if path.ends_with(".svg") {
+        SvgLoader::default().load(path)
+    } else {
+        PlainFileLoader::default().load(path)
+    }
+}
+
 /// Loaders can load data from file
 #[derive(Default)]
 pub struct PlainFileLoader {}
@@ -47,7 +59,7 @@ impl Loader for PlainFileLoader {
                 continue;
             }
             let Some(point) = parse_point(line)? else {
-                continue
+                continue;
             };
 
             match state {
@@ -82,3 +94,183 @@ fn parse_point(line: &str) -> Result<Option<Point>, LoaderError> {
 
     Ok(Some(Point::new(x, y)))
 }
+
+/// Loads polygons from svg file. `points` of `<polygon>`/`<polyline>` and `d` of
+/// `<path>` are read, path supports `M L H V Z` commands and their relative forms.
+/// First ring is the outline, the rest are holes.
+#[derive(Default)]
+pub struct SvgLoader {}
+
+impl Loader for SvgLoader {
+    fn load(&mut self, path: &str) -> Result<SweeperBuilder, LoaderError> {
+        let value = std::fs::read_to_string(path)?;
+        let mut rings = parse_svg(&value)?.into_iter();
+        let outline = rings
+            .next()
+            .ok_or_else(|| LoaderError::Parse("no polygon found".into()))?;
+        Ok(SweeperBuilder::new(outline).add_holes(rings))
+    }
+}
+
+/// Pick out rings from svg source
+fn parse_svg(source: &str) -> Result<Vec<Vec<Point>>, LoaderError> {
+    let mut rings = vec![];
+    for element in source.split('<').skip(1) {
+        let name = element
+            .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .next()
+            .unwrap_or_default();
+        match name {
+            "polygon" | "polyline" => {
+                if let Some(points) = attribute(element, "points") {
+                    let numbers = parse_numbers(points)?;
+                    rings.push(
+                        numbers
+                            .chunks_exact(2)
+                            .map(|xy| Point::new(xy[0], xy[1]))
+                            .collect(),
+                    );
+                }
+            }
+            "path" => {
+                if let Some(d) = attribute(element, "d") {
+                    rings.extend(parse_path(d)?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // closing point duplicates the first one
+    for ring in rings.iter_mut() {
+        if ring.len() > 1 && ring[0].eq(ring.last().unwrap()) {
+            ring.pop();
+        }
+    }
+    rings.retain(|ring| ring.len() >= 3);
+    Ok(rings)
+}
+
+fn attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+    let element = &element[..element.find('>').unwrap_or(element.len())];
+    let mut rest = element;
+    while let Some(pos) = rest.find(name) {
+        let preceded = rest[..pos].chars().last().is_none_or(|c| c.is_whitespace());
+        let after = rest[pos + name.len()..].trim_start();
+        if preceded {
+            if let Some(after) = after.strip_prefix('=') {
+                let after = after.trim_start();
+                let quote = after.chars().next()?;
+                let value = &after[1..];
+                return value.find(quote).map(|end| &value[..end]);
+            }
+        }
+        rest = &rest[pos + name.len()..];
+    }
+    None
+}
+
+fn parse_numbers(value: &str) -> Result<Vec<f64>, LoaderError> {
+    value
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<f64>()
+                .map_err(|_| LoaderError::Parse(format!("invalid number {s}")))
+        })
+        .collect()
+}
+
+fn parse_path(d: &str) -> Result<Vec<Vec<Point>>, LoaderError> {
+    // split into commands, each with its numbers
+    let mut commands = vec![];
+    let mut start = None;
+    for (idx, c) in d.char_indices() {
+        if c.is_ascii_alphabetic() && c != 'e' && c != 'E' {
+            if let Some((cmd, from)) = start {
+                commands.push((cmd, &d[from..idx]));
+            }
+            start = Some((c, idx + 1));
+        }
+    }
+    if let Some((cmd, from)) = start {
+        commands.push((cmd, &d[from..]));
+    }
+
+    let mut rings = vec![];
+    let mut ring = Vec::<Point>::new();
+    let mut current = Point::new(0., 0.);
+    for (cmd, args) in commands {
+        let numbers = parse_numbers(args)?;
+        let relative = cmd.is_ascii_lowercase();
+        let offset = |x: f64, y: f64, current: Point| {
+            if relative {
+                Point::new(current.x + x, current.y + y)
+            } else {
+                Point::new(x, y)
+            }
+        };
+        match cmd.to_ascii_uppercase() {
+            'M' | 'L' => {
+                if cmd.eq_ignore_ascii_case(&'M') && !ring.is_empty() {
+                    rings.push(std::mem::take(&mut ring));
+                }
+                // extra pairs after moveto are implicit linetos
+                for xy in numbers.chunks_exact(2) {
+                    current = offset(xy[0], xy[1], current);
+                    ring.push(current);
+                }
+            }
+            'H' => {
+                for x in numbers {
+                    current = Point::new(if relative { current.x + x } else { x }, current.y);
+                    ring.push(current);
+                }
+            }
+            'V' => {
+                for y in numbers {
+                    current = Point::new(current.x, if relative { current.y + y } else { y });
+                    ring.push(current);
+                }
+            }
+            'Z' => {
+                if let Some(first) = ring.first() {
+                    current = *first;
+                }
+                if !ring.is_empty() {
+                    rings.push(std::mem::take(&mut ring));
+                }
+            }
+            other => {
+                return Err(LoaderError::Parse(format!(
+                    "unsupported path command {other}"
+                )))
+            }
+        }
+    }
+    if !ring.is_empty() {
+        rings.push(ring);
+    }
+    Ok(rings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_svg() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg">
+            <polygon points="0,0 10,0 10,10 0,10" />
+            <path id="hole" d="M2 2 h2 v2 H2 z m4 4 l2 0 0 2 -2 0 z"/>
+        </svg>"#;
+        let rings = parse_svg(svg).unwrap();
+        assert_eq!(rings.len(), 3);
+        assert_eq!(rings[0].len(), 4);
+        assert!(rings[1][2].eq(&Point::new(4., 4.)));
+        assert!(rings[2][0].eq(&Point::new(6., 6.)));
+        assert!(rings[2][3].eq(&Point::new(6., 8.)));
+
+        assert!(parse_svg(r#"<path d="M0 0 C1 1 2 2 3 3"/>"#).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Uniform grid over result triangles, finds the triangles a point may be in
+
+use crate::Point;
+
+/// Triangles bucketed by the grid cells their bounding boxes overlap, about one
+/// triangle per cell
+#[derive(Debug)]
+pub(crate) struct TriangleGrid {
+    min: Point,
+    cell: f64,
+    cols: usize,
+    rows: usize,
+    /// triangles of cell `i` are `items[starts[i]..starts[i + 1]]`
+    starts: Vec<u32>,
+    items: Vec<u32>,
+}
+
+impl TriangleGrid {
+    pub(crate) fn new(triangles: &[[Point; 3]]) -> Self {
+        let mut min = Point::new(f64::MAX, f64::MAX);
+        let mut max = Point::new(f64::MIN, f64::MIN);
+        for p in triangles.iter().flatten() {
+            min = Point::new(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        let count = triangles.len().max(1) as f64;
+        let (width, height) = ((max.x - min.x).max(0.), (max.y - min.y).max(0.));
+        // square cells, a thin mesh still gets at most one cell per triangle
+        let cell = (width * height / count)
+            .sqrt()
+            .max(width.max(height) / count)
+            .max(f64::MIN_POSITIVE);
+        let mut grid = Self {
+            min,
+            cell,
+            cols: ((width / cell).ceil() as usize).max(1),
+            rows: ((height / cell).ceil() as usize).max(1),
+            starts: vec![],
+            items: vec![],
+        };
+
+        let ranges = triangles
+            .iter()
+            .map(|t| {
+                let [xs, ys] = [t.map(|p| p.x), t.map(|p| p.y)];
+                let low = Point::new(
+                    xs.into_iter().fold(f64::MAX, f64::min),
+                    ys.into_iter().fold(f64::MAX, f64::min),
+                );
+                let high = Point::new(
+                    xs.into_iter().fold(f64::MIN, f64::max),
+                    ys.into_iter().fold(f64::MIN, f64::max),
+                );
+                let ((x0, y0), (x1, y1)) = (grid.coords(low), grid.coords(high));
+                (x0..=x1, y0..=y1)
+            })
+            .collect::<Vec<_>>();
+        let mut counts = vec![0u32; grid.cols * grid.rows + 1];
+        for (xs, ys) in ranges.iter() {
+            for y in ys.clone() {
+                for x in xs.clone() {
+                    counts[y * grid.cols + x + 1] += 1;
+                }
+            }
+        }
+        for i in 1..counts.len() {
+            counts[i] += counts[i - 1];
+        }
+        let mut fill = counts.clone();
+        grid.items = vec![0; *counts.last().unwrap() as usize];
+        for (index, (xs, ys)) in ranges.into_iter().enumerate() {
+            for y in ys {
+                for x in xs.clone() {
+                    let slot = &mut fill[y * grid.cols + x];
+                    grid.items[*slot as usize] = index as u32;
+                    *slot += 1;
+                }
+            }
+        }
+        grid.starts = counts;
+        grid
+    }
+
+    /// column and row of the cell `p` is in, clamped to the grid
+    fn coords(&self, p: Point) -> (usize, usize) {
+        let x = ((p.x - self.min.x) / self.cell).floor().max(0.) as usize;
+        let y = ((p.y - self.min.y) / self.cell).floor().max(0.) as usize;
+        (x.min(self.cols - 1), y.min(self.rows - 1))
+    }
+
+    /// Cell `p` is in, `None` outside the grid
+    pub(crate) fn cell(&self, p: Point) -> Option<usize> {
+        let (x, y) = (
+            (p.x - self.min.x) / self.cell,
+            (p.y - self.min.y) / self.cell,
+        );
+        // the far border belongs to the last cell
+        if !(x >= 0. && y >= 0. && x <= self.cols as f64 && y <= self.rows as f64) {
+            return None;
+        }
+        let (x, y) = self.coords(p);
+        Some(y * self.cols + x)
+    }
+
+    /// Triangles which may contain points of `cell`
+    pub(crate) fn candidates(&self, cell: usize) -> &[u32] {
+        &self.items[self.starts[cell] as usize..self.starts[cell + 1] as usize]
+    }
+}
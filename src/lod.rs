@@ -0,0 +1,308 @@
+//! Levels of detail from a single triangulation, for terrain and map renderers
+//! that switch meshes by zoom. The full detail mesh is decimated by collapsing
+//! steiner points into their nearest neighbor, constrained edges and their points
+//! are kept, so every level covers the same polygon. All levels index into one
+//! vertex pool, upload it once and swap index buffers.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{Point, SweeperBuilder};
+//!
+//!    let steiner_points = (1..10)
+//!        .flat_map(|x| (1..10).map(move |y| Point::new(x as f64, y as f64)));
+//!    let lod = SweeperBuilder::new(vec![
+//!        Point::new(0., 0.),
+//!        Point::new(10., 0.),
+//!        Point::new(10., 10.),
+//!        Point::new(0., 10.),
+//!    ])
+//!    .add_steiner_points(steiner_points)
+//!    .build()
+//!    .triangulate_lod(&[0.5, 0.]);
+//!
+//!    assert_eq!(lod.level_count(), 3);
+//!    assert_eq!(lod.level(0).unwrap().len(), 164);
+//!    assert!(lod.level(2).unwrap().len() < lod.level(1).unwrap().len());
+//! ```
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::utils::orient;
+use crate::{Point, Triangles};
+
+/// Meshes from full detail to coarsest, sharing a vertex pool
+#[derive(Debug, Clone)]
+pub struct LodMeshes {
+    vertices: Vec<Point>,
+    levels: Vec<Vec<[u32; 3]>>,
+}
+
+impl LodMeshes {
+    /// Level 0 is the result of `triangles`, regardless of iteration progress,
+    /// then one level for each of `keep`, the fraction of steiner points kept. Each
+    /// level is decimated from the one before, so `keep` is clamped to at most the
+    /// previous fraction. A point is kept anyway when removing it would flip a
+    /// triangle.
+    pub fn new(triangles: &Triangles, keep: &[f64]) -> Self {
+        let vertices = (0..triangles.vertex_count())
+            .map(|index| triangles.vertex(index))
+            .collect::<Vec<_>>();
+        let mut flat = vec![0; triangles.index_count()];
+        triangles.write_indices_into(&mut flat);
+        let full = flat
+            .chunks_exact(3)
+            .map(|c| [c[0], c[1], c[2]])
+            .collect::<Vec<_>>();
+
+        let fixed = triangles.constrained_vertices();
+        let mut decimator = Decimator::new(&vertices, &full, fixed);
+        let removable = decimator.removable();
+        let mut levels = vec![full];
+        let mut fraction = 1f64;
+        for &k in keep {
+            fraction = fraction.min(k.clamp(0., 1.));
+            let target = removable - (removable as f64 * fraction).round() as usize;
+            decimator.decimate(target);
+            levels.push(decimator.triangles());
+        }
+
+        Self { vertices, levels }
+    }
+
+    /// Shared vertex pool, as input in input order. Coarser levels use a subset.
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// Number of levels, including the full detail level 0
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Vertex indices of triangles of level `level`, counter clockwise
+    pub fn level(&self, level: usize) -> Option<&[[u32; 3]]> {
+        self.levels.get(level).map(Vec::as_slice)
+    }
+}
+
+/// Index mesh with vertex to triangle adjacency, for half edge collapses
+struct Decimator<'a> {
+    vertices: &'a [Point],
+    triangles: Vec<Option<[u32; 3]>>,
+    /// triangles around each vertex, dead ones are removed lazily
+    fan: Vec<Vec<usize>>,
+    /// on a constrained edge, or already removed
+    fixed: Vec<bool>,
+    removed: usize,
+}
+
+impl<'a> Decimator<'a> {
+    fn new(vertices: &'a [Point], triangles: &[[u32; 3]], mut fixed: Vec<bool>) -> Self {
+        let mut fan = vec![vec![]; vertices.len()];
+        let mut used = vec![false; vertices.len()];
+        for (index, triangle) in triangles.iter().enumerate() {
+            for v in triangle {
+                fan[*v as usize].push(index);
+                used[*v as usize] = true;
+            }
+        }
+        // points outside the result, e.g. steiner points in holes
+        for (fixed, used) in fixed.iter_mut().zip(used) {
+            *fixed |= !used;
+        }
+
+        Self {
+            vertices,
+            triangles: triangles.iter().copied().map(Some).collect(),
+            fan,
+            fixed,
+            removed: 0,
+        }
+    }
+
+    fn removable(&self) -> usize {
+        self.fixed.iter().filter(|fixed| !**fixed).count()
+    }
+
+    fn triangles(&self) -> Vec<[u32; 3]> {
+        self.triangles.iter().flatten().copied().collect()
+    }
+
+    /// Collapse points, shortest edge first, until `target` are removed in total
+    fn decimate(&mut self, target: usize) {
+        let mut heap = BinaryHeap::new();
+        for v in 0..self.vertices.len() {
+            if let Some(length) = self.shortest_edge(v) {
+                heap.push(Reverse((length.to_bits(), v)));
+            }
+        }
+
+        while self.removed < target {
+            let Some(Reverse((bits, v))) = heap.pop() else {
+                break;
+            };
+            // lengths are not negative, their bits sort the same as them
+            match self.shortest_edge(v) {
+                Some(length) if length.to_bits() == bits => {}
+                Some(length) => {
+                    heap.push(Reverse((length.to_bits(), v)));
+                    continue;
+                }
+                None => continue,
+            }
+
+            let neighbors = self.neighbors(v);
+            if self.collapse(v) {
+                for n in neighbors {
+                    if let Some(length) = self.shortest_edge(n) {
+                        heap.push(Reverse((length.to_bits(), n)));
+                    }
+                }
+            }
+        }
+    }
+
+    fn distance(&self, a: usize, b: usize) -> f64 {
+        let (a, b) = (self.vertices[a], self.vertices[b]);
+        (a.x - b.x).hypot(a.y - b.y)
+    }
+
+    /// Length of the shortest edge at removable `v`
+    fn shortest_edge(&self, v: usize) -> Option<f64> {
+        if self.fixed[v] {
+            return None;
+        }
+        self.neighbors(v)
+            .into_iter()
+            .map(|n| self.distance(v, n))
+            .min_by(f64::total_cmp)
+    }
+
+    fn live_fan(&self, v: usize) -> impl Iterator<Item = (usize, [u32; 3])> + '_ {
+        self.fan[v]
+            .iter()
+            .filter_map(|t| self.triangles[*t].map(|triangle| (*t, triangle)))
+    }
+
+    fn neighbors(&self, v: usize) -> Vec<usize> {
+        let mut neighbors = self
+            .live_fan(v)
+            .flat_map(|(_, triangle)| triangle)
+            .map(|n| n as usize)
+            .filter(|n| *n != v)
+            .collect::<Vec<_>>();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+
+    /// Move `v` onto its nearest neighbor where no triangle flips. Returns whether
+    /// `v` is removed.
+    fn collapse(&mut self, v: usize) -> bool {
+        let fan = self.live_fan(v).collect::<Vec<_>>();
+        let mut neighbors = self.neighbors(v);
+        // an interior point is surrounded by as many neighbors as triangles,
+        // otherwise it is on the mesh border
+        if neighbors.len() != fan.len() {
+            return false;
+        }
+        neighbors.sort_by(|a, b| self.distance(v, *a).total_cmp(&self.distance(v, *b)));
+
+        let Some(u) = neighbors.into_iter().find(|u| {
+            fan.iter().all(|(_, triangle)| {
+                if triangle.contains(&(*u as u32)) {
+                    return true;
+                }
+                let [a, b, c] = triangle.map(|p| {
+                    let p = if p as usize == v { *u } else { p as usize };
+                    self.vertices[p]
+                });
+                orient(a, b, c) > 0.
+            })
+        }) else {
+            return false;
+        };
+
+        for (index, triangle) in fan {
+            if triangle.contains(&(u as u32)) {
+                self.triangles[index] = None;
+            } else {
+                self.triangles[index] =
+                    Some(triangle.map(|p| if p as usize == v { u as u32 } else { p }));
+                self.fan[u].push(index);
+            }
+        }
+        self.fan[v].clear();
+        self.fixed[v] = true;
+        self.removed += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SweeperBuilder;
+
+    #[test]
+    fn test_lod() {
+        let outer = vec![
+            Point::new(0., 0.),
+            Point::new(20., 0.),
+            Point::new(20., 20.),
+            Point::new(0., 20.),
+        ];
+        let hole = vec![
+            Point::new(8., 8.),
+            Point::new(8., 12.),
+            Point::new(12., 12.),
+            Point::new(12., 8.),
+        ];
+        let steiner_points = (1..20)
+            .flat_map(|x| (1..20).map(move |y| Point::new(x as f64 + 0.1, y as f64 + 0.2)))
+            .filter(|p| !(7.5..12.5).contains(&p.x) || !(7.5..12.5).contains(&p.y));
+        let lod = SweeperBuilder::new(outer.clone())
+            .add_hole(hole.clone())
+            .add_steiner_points(steiner_points)
+            .build()
+            .triangulate_lod(&[0.5, 0.8, 0.]);
+        assert_eq!(lod.level_count(), 4);
+
+        let vertices = lod.vertices();
+        let area = |level: &[[u32; 3]]| {
+            level
+                .iter()
+                .map(|t| {
+                    let [a, b, c] = t.map(|i| vertices[i as usize]);
+                    assert!(orient(a, b, c) > 0.);
+                    ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)) / 2.
+                })
+                .sum::<f64>()
+        };
+        let has_edge = |level: &[[u32; 3]], p: u32, q: u32| {
+            level
+                .iter()
+                .any(|t| (0..3).any(|i| t[i] == p && t[(i + 1) % 3] == q))
+        };
+
+        let counts = (0..4)
+            .map(|i| lod.level(i).unwrap().len())
+            .collect::<Vec<_>>();
+        // 0.8 is clamped to 0.5, each collapse removes 2 triangles
+        assert_eq!(counts[1], counts[2]);
+        assert!(counts[3] < counts[1] && counts[1] < counts[0]);
+        assert_eq!((counts[0] - counts[1]) % 2, 0);
+        for i in 0..4 {
+            let level = lod.level(i).unwrap();
+            assert!((area(level) - 384.).abs() < 1e-9);
+            // outer and hole edges are kept
+            for ring in [0..4, 4..8] {
+                for p in ring.clone() {
+                    let q = if p + 1 == ring.end { ring.start } else { p + 1 };
+                    assert!(has_edge(level, p, q) || has_edge(level, q, p));
+                }
+            }
+        }
+        assert!(lod.level(4).is_none());
+    }
+}
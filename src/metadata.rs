@@ -0,0 +1,123 @@
+//! How a mesh was produced, for headers of serialized results, see
+//! [`crate::SweeperBuilder::metadata`].
+use crate::points::PointsBuilder;
+use crate::{PointId, SweepConfig};
+
+/// Crate version, config, predicate mode and input fingerprint of a mesh, so a
+/// mesh loaded later can be traced back to how it was produced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    /// crate version
+    pub version: &'static str,
+    /// sweep config, debug formatted
+    pub config: String,
+    /// `exact` with the `exact_orient` feature, `adaptive` otherwise
+    pub predicates: &'static str,
+    /// hash of input points, rings and weights, same on all platforms and runs
+    pub input_fingerprint: u64,
+}
+
+impl Metadata {
+    pub(crate) fn new(
+        config: &SweepConfig,
+        points_builder: &PointsBuilder,
+        rings: &[Vec<PointId>],
+        weights: &[f64],
+    ) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            config: format!("{config:?}"),
+            predicates: if cfg!(feature = "exact_orient") {
+                "exact"
+            } else {
+                "adaptive"
+            },
+            input_fingerprint: fingerprint(points_builder, rings, weights),
+        }
+    }
+
+    /// Key value pairs, in a fixed order, for writers
+    pub fn entries(&self) -> [(&'static str, String); 4] {
+        [
+            ("version", self.version.to_string()),
+            ("config", self.config.clone()),
+            ("predicates", self.predicates.to_string()),
+            (
+                "input_fingerprint",
+                format!("{:016x}", self.input_fingerprint),
+            ),
+        ]
+    }
+}
+
+/// 64 bit FNV-1a
+struct Fnv(u64);
+
+impl Fnv {
+    fn write(&mut self, value: u64) {
+        for byte in value.to_le_bytes() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+fn fingerprint(points_builder: &PointsBuilder, rings: &[Vec<PointId>], weights: &[f64]) -> u64 {
+    let mut hasher = Fnv(0xcbf29ce484222325);
+    hasher.write(points_builder.len() as u64);
+    for idx in 0..points_builder.len() {
+        let point = points_builder.get_point(PointId::from_usize(idx));
+        // -0. and 0. are the same point
+        hasher.write((point.x + 0.).to_bits());
+        hasher.write((point.y + 0.).to_bits());
+    }
+    for ring in rings {
+        hasher.write(ring.len() as u64);
+        for point_id in ring {
+            hasher.write(point_id.as_usize() as u64);
+        }
+    }
+    for weight in weights {
+        hasher.write(weight.to_bits());
+    }
+    hasher.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, SweeperBuilder};
+
+    #[test]
+    fn test_metadata() {
+        let builder = || {
+            SweeperBuilder::new(vec![
+                Point::new(0., 0.),
+                Point::new(10., 0.),
+                Point::new(10., 10.),
+                Point::new(0., 10.),
+            ])
+            .add_steiner_points([Point::new(5., 5.)])
+        };
+        let metadata = builder().metadata();
+        assert_eq!(metadata, builder().metadata());
+        assert_eq!(metadata.version, env!("CARGO_PKG_VERSION"));
+        assert!(metadata.config.starts_with("SweepConfig"));
+
+        let moved = builder()
+            .add_steiner_points([Point::new(5., 6.)])
+            .metadata();
+        assert_ne!(moved.input_fingerprint, metadata.input_fingerprint);
+        let with_hole = builder()
+            .add_hole(vec![
+                Point::new(2., 2.),
+                Point::new(2., 3.),
+                Point::new(3., 3.),
+            ])
+            .metadata();
+        assert_ne!(with_hole.input_fingerprint, metadata.input_fingerprint);
+
+        let [.., (key, value)] = metadata.entries();
+        assert_eq!(key, "input_fingerprint");
+        assert_eq!(value, format!("{:016x}", metadata.input_fingerprint));
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use crate::points::Points;
+use crate::shape::InnerTriangle;
+use crate::triangles::{TriangleId, TriangleStore};
+use crate::utils::Orientation;
+use crate::{PointId, Predicates, Sweeper};
+
+/// Triangulate a polygon which is monotone in sweep order (y, then x), convex
+/// polygons included. Points store should only contain the polygon, ids follow
+/// the polyline order.
+///
+/// Runs the linear time monotone algorithm, then flips edges until the result is
+/// constrained delaunay, same as the sweep result.
+/// Returns `None` when the polygon is not monotone or degenerated, then caller
+/// should fall back to sweep.
+pub(crate) fn triangulate(
+    points: &Points,
+    predicates: Predicates,
+) -> Option<(TriangleStore, Vec<TriangleId>)> {
+    // points store has head and tail appended
+    let len = points.len() - 2;
+    if len < 3 {
+        return None;
+    }
+
+    let mut rank = vec![0; len];
+    let mut sorted = Vec::with_capacity(len);
+    for (order, (point_id, _, _)) in points.iter_point_by_y(0).enumerate() {
+        rank[point_id.as_usize()] = order;
+        sorted.push(point_id);
+    }
+    let bottom = sorted[0].as_usize();
+    let top = sorted[len - 1].as_usize();
+
+    // walking forward from bottom, rank increases till top, then decreases back
+    let mut forward = vec![false; len];
+    let mut idx = bottom;
+    while idx != top {
+        let next = (idx + 1) % len;
+        if rank[next] < rank[idx] {
+            return None;
+        }
+        forward[idx] = true;
+        idx = next;
+    }
+    while idx != bottom {
+        let next = (idx + 1) % len;
+        if rank[next] > rank[idx] {
+            return None;
+        }
+        idx = next;
+    }
+
+    // forward chain goes up, for a ccw polygon interior is on its left, so it is
+    // the right chain
+    let area = (0..len)
+        .map(|i| {
+            let p = PointId::from_usize(i).get(points);
+            let q = PointId::from_usize((i + 1) % len).get(points);
+            p.x * q.y - q.x * p.y
+        })
+        .sum::<f64>();
+    if area == 0. {
+        return None;
+    }
+    let is_left = |point_id: PointId| forward[point_id.as_usize()] != (area > 0.);
+
+    let mut triangles = TriangleStore::with_capacity(len - 2);
+    let mut emit = |a: PointId, b: PointId, c: PointId| -> Option<()> {
+        let triangle = match predicates.orient_2d(a.get(points), b.get(points), c.get(points)) {
+            Orientation::CCW => InnerTriangle::new(a, b, c),
+            Orientation::CW => InnerTriangle::new(a, c, b),
+            Orientation::Collinear => return None,
+        };
+        triangles.insert(triangle);
+        Some(())
+    };
+
+    let mut stack = vec![sorted[0], sorted[1]];
+    for (j, &point_id) in sorted.iter().enumerate().skip(2) {
+        let top_id = *stack.last().unwrap();
+        if j == len - 1 || is_left(point_id) != is_left(top_id) {
+            // opposite chain, every stack vertex is visible
+            while stack.len() > 1 {
+                let v = stack.pop().unwrap();
+                emit(point_id, v, *stack.last().unwrap())?;
+            }
+            stack.clear();
+            stack.push(sorted[j - 1]);
+            stack.push(point_id);
+        } else {
+            let mut last = stack.pop().unwrap();
+            let expected = if is_left(point_id) {
+                Orientation::CCW
+            } else {
+                Orientation::CW
+            };
+            while let Some(&v) = stack.last() {
+                let orient =
+                    predicates.orient_2d(v.get(points), point_id.get(points), last.get(points));
+                if orient != expected {
+                    break;
+                }
+                emit(point_id, last, v)?;
+                last = stack.pop().unwrap();
+            }
+            stack.push(last);
+            stack.push(point_id);
+        }
+    }
+
+    if triangles.len() != len - 2 {
+        // self intersected polygon
+        return None;
+    }
+
+    // connect neighbors, and mark polygon edges as constrained
+    let mut edges = HashMap::<(PointId, PointId), TriangleId>::with_capacity(len * 2);
+    let result = triangles.iter().map(|(id, _)| id).collect::<Vec<_>>();
+    for &triangle_id in result.iter() {
+        let points = triangle_id.get(&triangles).points;
+        for i in 0..3 {
+            let (p, q) = (points[i], points[(i + 1) % 3]);
+            let key = if p < q { (p, q) } else { (q, p) };
+            if let Some(other) = edges.remove(&key) {
+                triangles.mark_neighbor(triangle_id, other);
+            } else {
+                edges.insert(key, triangle_id);
+            }
+        }
+    }
+    for i in 0..len {
+        let (p, q) = (PointId::from_usize(i), PointId::from_usize((i + 1) % len));
+        let key = if p < q { (p, q) } else { (q, p) };
+        let triangle_id = edges.get(&key)?;
+        triangles
+            .get_mut(*triangle_id)
+            .unwrap()
+            .set_constrained_for_edge(p, q);
+    }
+
+    Sweeper::legalize_all(&mut triangles, points);
+
+    for &triangle_id in result.iter() {
+        triangles.get_mut(triangle_id).unwrap().interior = true;
+    }
+
+    Some((triangles, result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::points::PointsBuilder;
+    use crate::{Point, SweeperBuilder};
+
+    fn polygon(polyline: Vec<Point>) -> Points {
+        let mut builder = PointsBuilder::with_capacity(polyline.len());
+        builder.add_steiner_points(polyline);
+        builder.build()
+    }
+
+    fn sorted_triangles(triangles: impl Iterator<Item = crate::Triangle>) -> Vec<[(f64, f64); 3]> {
+        let mut result = triangles
+            .map(|t| {
+                let mut points = t.points.map(|p| (p.x, p.y));
+                points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                points
+            })
+            .collect::<Vec<_>>();
+        result.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        result
+    }
+
+    fn assert_same_as_sweep(polyline: Vec<Point>) {
+        let sweeper = SweeperBuilder::new(polyline).build();
+        let fast = sorted_triangles(sweeper.clone().triangulate());
+        let sweep = sorted_triangles(sweeper.triangulate_with_observer(&mut ()));
+        assert_eq!(fast, sweep);
+    }
+
+    #[test]
+    fn test_monotone_detect() {
+        let convex = polygon(vec![
+            Point::new(0., 0.),
+            Point::new(10., 1.),
+            Point::new(12., 7.),
+            Point::new(4., 11.),
+            Point::new(-3., 5.),
+        ]);
+        assert!(triangulate(&convex, Predicates::Fast).is_some());
+
+        // u shape is not y monotone
+        let u_shape = polygon(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.5),
+            Point::new(10.5, 10.),
+            Point::new(7., 9.5),
+            Point::new(6.5, 3.),
+            Point::new(3., 3.5),
+            Point::new(3.5, 10.5),
+            Point::new(0.2, 10.),
+        ]);
+        assert!(triangulate(&u_shape, Predicates::Fast).is_none());
+    }
+
+    #[test]
+    fn test_monotone_same_as_sweep() {
+        // convex, cw and ccw
+        let convex = vec![
+            Point::new(0., 0.),
+            Point::new(10., 1.),
+            Point::new(12., 7.),
+            Point::new(4., 11.),
+            Point::new(-3., 5.),
+        ];
+        assert_same_as_sweep(convex.clone());
+        assert_same_as_sweep(convex.into_iter().rev().collect());
+
+        // zigzag chains on both sides
+        let mut zigzag = vec![];
+        for i in 0..20 {
+            let x = if i % 2 == 0 {
+                10.
+            } else {
+                14. + i as f64 * 0.1
+            };
+            zigzag.push(Point::new(x, i as f64 * 2.));
+        }
+        for i in (0..20).rev() {
+            let x = if i % 2 == 0 { 0. } else { -3. - i as f64 * 0.1 };
+            zigzag.push(Point::new(x, i as f64 * 2. + 1.));
+        }
+        assert!(triangulate(&polygon(zigzag.clone()), Predicates::Fast).is_some());
+        assert_same_as_sweep(zigzag);
+    }
+
+    #[test]
+    fn test_monotone_degenerate_same_as_sweep() {
+        // collinear points on every side, bottom and top sides share y
+        let rectangle = vec![
+            Point::new(0., 0.),
+            Point::new(3., 0.),
+            Point::new(6., 0.),
+            Point::new(6., 2.),
+            Point::new(6., 4.),
+            Point::new(3., 4.),
+            Point::new(0., 4.),
+            Point::new(0., 2.),
+        ];
+        assert!(triangulate(&polygon(rectangle.clone()), Predicates::Fast).is_some());
+        assert_same_as_sweep(rectangle.clone());
+        assert_same_as_sweep(rectangle.into_iter().rev().collect());
+
+        // cocircular square, any diagonal is delaunay
+        let square = vec![
+            Point::new(0., 0.),
+            Point::new(1., 0.),
+            Point::new(1., 1.),
+            Point::new(0., 1.),
+        ];
+        assert!(triangulate(&polygon(square.clone()), Predicates::Fast).is_some());
+        assert_same_as_sweep(square);
+
+        // vertices on both chains at the same y
+        let mut ladder = vec![];
+        for i in 0..8 {
+            ladder.push(Point::new(5. + (i % 3) as f64, i as f64));
+        }
+        for i in (0..8).rev() {
+            ladder.push(Point::new(-((i % 2) as f64), i as f64));
+        }
+        assert!(triangulate(&polygon(ladder.clone()), Predicates::Fast).is_some());
+        assert_same_as_sweep(ladder);
+
+        // flat top and bottom with collinear chain points
+        let trapezoid = vec![
+            Point::new(0., 0.),
+            Point::new(4., 0.),
+            Point::new(5., 1.),
+            Point::new(6., 2.),
+            Point::new(2., 2.),
+            Point::new(1., 2.),
+            Point::new(0.5, 1.),
+        ];
+        assert!(triangulate(&polygon(trapezoid.clone()), Predicates::Fast).is_some());
+        assert_same_as_sweep(trapezoid.clone());
+        assert_same_as_sweep(trapezoid.into_iter().rev().collect());
+    }
+}
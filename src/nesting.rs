@@ -0,0 +1,142 @@
+//! Containment hierarchy of rings, e.g. polygons, their holes and islands in
+//! the holes, for ring lists without outer and hole roles like GeoJSON or
+//! shapefiles. Inspect or edit the tree, then build with
+//! [`SweeperBuilder::from_hierarchy`].
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{nesting, Point, SweeperBuilder};
+//!
+//!    let square = |min: f64, max: f64| {
+//!        vec![
+//!            Point::new(min, min),
+//!            Point::new(max, min),
+//!            Point::new(max, max),
+//!            Point::new(min, max),
+//!        ]
+//!    };
+//!    let rings = vec![square(2., 8.), square(0., 10.), square(4., 6.)];
+//!    let mut roots = nesting::hierarchy(&rings);
+//!    assert_eq!(roots.len(), 1);
+//!    assert_eq!(roots[0].ring, 1);
+//!    assert_eq!(roots[0].children[0].ring, 0);
+//!
+//!    // drop the island inside the hole
+//!    roots[0].children[0].children.clear();
+//!    let triangles = SweeperBuilder::from_hierarchy(&rings, &roots[0]).build().triangulate();
+//!    assert_eq!(triangles.count(), 8);
+//! ```
+use crate::utils::{point_in_ring, signed_area};
+use crate::Point;
+
+/// A ring and the rings directly inside it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RingNode {
+    /// index of the ring in input
+    pub ring: usize,
+    pub children: Vec<RingNode>,
+}
+
+impl RingNode {
+    /// Ring indices of this node and all below, depth first
+    pub fn rings(&self) -> Vec<usize> {
+        let mut rings = vec![self.ring];
+        for child in self.children.iter() {
+            rings.extend(child.rings());
+        }
+        rings
+    }
+}
+
+/// Trees of rings by containment, roots are rings inside no other one. Each ring
+/// is a child of the smallest ring containing it. Children keep input order.
+pub fn hierarchy(rings: &[Vec<Point>]) -> Vec<RingNode> {
+    let areas = rings
+        .iter()
+        .map(|ring| signed_area(ring).abs())
+        .collect::<Vec<_>>();
+    let parents = (0..rings.len())
+        .map(|i| {
+            (0..rings.len())
+                .filter(|j| i != *j && contains(&rings[*j], &rings[i]))
+                .min_by(|a, b| areas[*a].total_cmp(&areas[*b]))
+        })
+        .collect::<Vec<_>>();
+
+    fn node(ring: usize, parents: &[Option<usize>]) -> RingNode {
+        RingNode {
+            ring,
+            children: (0..parents.len())
+                .filter(|child| parents[*child] == Some(ring))
+                .map(|child| node(child, parents))
+                .collect(),
+        }
+    }
+    (0..rings.len())
+        .filter(|ring| parents[*ring].is_none())
+        .map(|ring| node(ring, &parents))
+        .collect()
+}
+
+/// Whether `inner` is inside `outer`. A point of `inner` off `outer` tells which
+/// side it is on, an edge midpoint if all vertices are on it.
+fn contains(outer: &[Point], inner: &[Point]) -> bool {
+    let midpoints = (0..inner.len()).map(|i| {
+        let (p, q) = (inner[i], inner[(i + 1) % inner.len()]);
+        Point::new((p.x + q.x) / 2., (p.y + q.y) / 2.)
+    });
+    inner
+        .iter()
+        .copied()
+        .chain(midpoints)
+        .find_map(|p| point_in_ring(outer, p))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<Point> {
+        vec![
+            Point::new(x, y),
+            Point::new(x + size, y),
+            Point::new(x + size, y + size),
+            Point::new(x, y + size),
+        ]
+    }
+
+    #[test]
+    fn test_hierarchy() {
+        let rings = vec![
+            square(1., 1., 3.),
+            square(20., 0., 10.),
+            square(0., 0., 10.),
+            square(2., 2., 1.),
+            square(6., 6., 2.),
+        ];
+        let roots = hierarchy(&rings);
+        let leaf = |ring| RingNode {
+            ring,
+            children: vec![],
+        };
+        assert_eq!(
+            roots,
+            [
+                leaf(1),
+                RingNode {
+                    ring: 2,
+                    children: vec![
+                        RingNode {
+                            ring: 0,
+                            children: vec![leaf(3)],
+                        },
+                        leaf(4),
+                    ],
+                },
+            ]
+        );
+        assert_eq!(roots[1].rings(), [2, 0, 3, 4]);
+        assert!(hierarchy(&[]).is_empty());
+    }
+}
@@ -0,0 +1,267 @@
+//! Filtering and sampling of [`Observer`] events, see [`Observer::mask`]
+use std::ops::BitOr;
+
+use crate::{ConstrainedEdge, Context, Edge, Observer, PointId, TriangleId};
+
+/// Which [`Observer`] events are dispatched, returned by [`Observer::mask`].
+/// Kinds combine with `|`, e.g. `ObserverMask::POINT_EVENT | ObserverMask::FINALIZED`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObserverMask {
+    kinds: u8,
+    every: u32,
+}
+
+impl ObserverMask {
+    pub const NONE: Self = Self::kinds(0);
+    pub const POINT_EVENT: Self = Self::kinds(1);
+    pub const EDGE_EVENT: Self = Self::kinds(1 << 1);
+    /// [`Observer::will_legalize`], [`Observer::legalize_step`],
+    /// [`Observer::triangle_rotated`], [`Observer::flipped`] and
+    /// [`Observer::legalized`]
+    pub const LEGALIZE: Self = Self::kinds(1 << 2);
+    pub const SWEEP_DONE: Self = Self::kinds(1 << 3);
+    pub const FINALIZED: Self = Self::kinds(1 << 4);
+    /// [`Observer::edge_step`], not sampled, the steps of an edge come before
+    /// its edge event
+    pub const EDGE_STEP: Self = Self::kinds(1 << 5);
+    pub const ALL: Self = Self::kinds(0b111111);
+
+    const fn kinds(kinds: u8) -> Self {
+        Self { kinds, every: 1 }
+    }
+
+    /// Dispatch only every `n`th point event, edge event and legalization, the
+    /// first one included. Sweep done and finalized are milestones, always
+    /// dispatched when set.
+    pub fn every(mut self, n: u32) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.kinds & other.kinds == other.kinds
+    }
+}
+
+impl Default for ObserverMask {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl BitOr for ObserverMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            kinds: self.kinds | rhs.kinds,
+            every: self.every.max(rhs.every),
+        }
+    }
+}
+
+/// Counter of one sampled event kind
+#[derive(Default)]
+struct Sampler {
+    seen: u32,
+}
+
+impl Sampler {
+    fn sample(&mut self, enabled: bool, every: u32) -> bool {
+        if !enabled {
+            return false;
+        }
+        let hit = self.seen == 0;
+        self.seen = (self.seen + 1) % every;
+        hit
+    }
+}
+
+/// Forwards events `inner` asked for by its mask, read once at start
+pub(crate) struct Masked<'a, O: Observer> {
+    inner: &'a mut O,
+    mask: ObserverMask,
+    points: Sampler,
+    edges: Sampler,
+    legalizations: Sampler,
+    /// the running legalization is sampled
+    legalizing: bool,
+}
+
+impl<'a, O: Observer> Masked<'a, O> {
+    pub(crate) fn new(inner: &'a mut O) -> Self {
+        Self {
+            mask: inner.mask(),
+            inner,
+            points: Sampler::default(),
+            edges: Sampler::default(),
+            legalizations: Sampler::default(),
+            legalizing: false,
+        }
+    }
+}
+
+impl<O: Observer> Observer for Masked<'_, O> {
+    #[inline]
+    fn point_event(&mut self, point_id: PointId, context: &Context) {
+        let enabled = self.mask.contains(ObserverMask::POINT_EVENT);
+        if self.points.sample(enabled, self.mask.every) {
+            self.inner.point_event(point_id, context);
+        }
+    }
+
+    #[inline]
+    fn edge_event(&mut self, edge: Edge, context: &Context) {
+        let enabled = self.mask.contains(ObserverMask::EDGE_EVENT);
+        if self.edges.sample(enabled, self.mask.every) {
+            self.inner.edge_event(edge, context);
+        }
+    }
+
+    #[inline]
+    fn edge_step(&mut self, edge: &ConstrainedEdge, context: &Context) {
+        if self.mask.contains(ObserverMask::EDGE_STEP) {
+            self.inner.edge_step(edge, context);
+        }
+    }
+
+    fn sweep_done(&mut self, context: &Context) {
+        if self.mask.contains(ObserverMask::SWEEP_DONE) {
+            self.inner.sweep_done(context);
+        }
+    }
+
+    fn finalized(&mut self, context: &Context) {
+        if self.mask.contains(ObserverMask::FINALIZED) {
+            self.inner.finalized(context);
+        }
+    }
+
+    #[inline]
+    fn will_legalize(&mut self, triangle_id: TriangleId, context: &Context) {
+        let enabled = self.mask.contains(ObserverMask::LEGALIZE);
+        self.legalizing = self.legalizations.sample(enabled, self.mask.every);
+        if self.legalizing {
+            self.inner.will_legalize(triangle_id, context);
+        }
+    }
+
+    #[inline]
+    fn legalize_step(&mut self, triangle_id: TriangleId, context: &Context) {
+        if self.legalizing {
+            self.inner.legalize_step(triangle_id, context);
+        }
+    }
+
+    #[inline]
+    fn triangle_rotated(
+        &mut self,
+        triangle_id: TriangleId,
+        opposite_triangle_id: TriangleId,
+        context: &Context,
+    ) {
+        if self.legalizing {
+            self.inner
+                .triangle_rotated(triangle_id, opposite_triangle_id, context);
+        }
+    }
+
+    #[inline]
+    fn flipped(
+        &mut self,
+        triangle_id: TriangleId,
+        opposite_triangle_id: TriangleId,
+        before: [PointId; 2],
+        after: [PointId; 2],
+        context: &Context,
+    ) {
+        if self.legalizing {
+            self.inner
+                .flipped(triangle_id, opposite_triangle_id, before, after, context);
+        }
+    }
+
+    #[inline]
+    fn legalized(&mut self, triangle_id: TriangleId, context: &Context) {
+        if self.legalizing {
+            self.inner.legalized(triangle_id, context);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Point, SweeperBuilder};
+
+    #[derive(Default)]
+    struct Counter {
+        mask: ObserverMask,
+        points: usize,
+        edges: usize,
+        legalized: usize,
+        finalized: usize,
+    }
+
+    impl Observer for Counter {
+        fn mask(&self) -> ObserverMask {
+            self.mask
+        }
+
+        fn point_event(&mut self, _: PointId, _: &Context) {
+            self.points += 1;
+        }
+
+        fn edge_event(&mut self, _: Edge, _: &Context) {
+            self.edges += 1;
+        }
+
+        fn legalized(&mut self, _: TriangleId, _: &Context) {
+            self.legalized += 1;
+        }
+
+        fn finalized(&mut self, _: &Context) {
+            self.finalized += 1;
+        }
+    }
+
+    #[test]
+    fn test_observer_mask() {
+        let sweeper = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_steiner_points((1..10).map(|i| Point::new(i as f64, (i * i % 10) as f64 + 0.5)))
+        .build();
+
+        let mut all = Counter::default();
+        sweeper.clone().triangulate_with_observer(&mut all);
+        // lowest point is in the initial triangle, no event
+        assert_eq!((all.points, all.edges, all.finalized), (12, 4, 1));
+        assert!(all.legalized > 0);
+
+        let mut milestones = Counter {
+            mask: ObserverMask::FINALIZED,
+            ..Default::default()
+        };
+        sweeper.clone().triangulate_with_observer(&mut milestones);
+        assert_eq!(
+            (milestones.points, milestones.edges, milestones.legalized),
+            (0, 0, 0)
+        );
+        assert_eq!(milestones.finalized, 1);
+
+        let mut sampled = Counter {
+            mask: (ObserverMask::POINT_EVENT | ObserverMask::LEGALIZE).every(5),
+            ..Default::default()
+        };
+        sweeper.triangulate_with_observer(&mut sampled);
+        assert_eq!(
+            (sampled.points, sampled.edges, sampled.finalized),
+            (3, 0, 0)
+        );
+        assert_eq!(sampled.legalized, all.legalized.div_ceil(5));
+    }
+}
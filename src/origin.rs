@@ -0,0 +1,352 @@
+//! Maps constrained mesh edges back to the input ring edges they come from, see
+//! [`crate::Triangles::edge_origin`] and [`crate::Triangles::boundary_vertices`].
+use std::collections::HashMap;
+
+use crate::points::Points;
+use crate::triangles::TriangleStore;
+use crate::utils::{orient_2d, Orientation};
+use crate::{Point, PointId};
+
+/// An input edge, edge `index` of a ring goes from its point `index` to the next one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputEdgeId {
+    /// 0 for the outer polyline, then holes in the order added. After
+    /// [`crate::SweeperBuilder::repair_input`], rings are the repaired ones
+    pub ring: usize,
+    /// edge index in the ring
+    pub index: usize,
+}
+
+/// An input polyline, see [`crate::Triangles::edge_polyline`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PolylineId {
+    Outer,
+    /// hole `k` in the order added, ring `k + 1` of [`InputEdgeId`]
+    Hole(usize),
+    /// collapsed degenerate hole or constraint polyline `m` in the order added,
+    /// see [`crate::DegenerateHole::Collapse`] and
+    /// [`crate::SweeperBuilder::add_constraint`]
+    Constraint(usize),
+}
+
+/// An input edge the sweep split at points on it, see
+/// [`crate::Triangles::edge_splits`]
+#[derive(Debug, Clone)]
+pub struct EdgeSplit {
+    pub edge: InputEdgeId,
+    /// points strictly inside the edge, in order from its start, as input
+    pub points: Vec<Point>,
+}
+
+/// Origin of constrained mesh edges, keyed by [`key`]
+pub(crate) type EdgeOrigins = HashMap<(PointId, PointId), InputEdgeId>;
+
+/// Key of the mesh edge between `p` and `q`, in either direction
+pub(crate) fn key(p: PointId, q: PointId) -> (PointId, PointId) {
+    if p < q {
+        (p, q)
+    } else {
+        (q, p)
+    }
+}
+
+/// Constrained mesh edges, to walk input edges the sweep split at points on them
+struct ConstrainedEdges {
+    neighbors: HashMap<PointId, Vec<PointId>>,
+}
+
+impl ConstrainedEdges {
+    fn new(triangles: &TriangleStore) -> Self {
+        // the flag may be set on one side only, so hole triangles are included
+        let mut neighbors = HashMap::<PointId, Vec<PointId>>::new();
+        for (_, triangle) in triangles.iter() {
+            for i in 0..3 {
+                if !triangle.is_constrained(i) {
+                    continue;
+                }
+                let (p, q) = (triangle.points[(i + 1) % 3], triangle.points[(i + 2) % 3]);
+                for (from, to) in [(p, q), (q, p)] {
+                    let list = neighbors.entry(from).or_default();
+                    if !list.contains(&to) {
+                        list.push(to);
+                    }
+                }
+            }
+        }
+        Self { neighbors }
+    }
+
+    /// Points from `p` walking constrained edges toward `q`, ends with `q` unless
+    /// the edge is not in the mesh, e.g. a shared edge cancelled out
+    fn path(&self, points: &Points, p: PointId, q: PointId) -> Vec<PointId> {
+        let (p_point, q_point) = (p.get(points), q.get(points));
+        let mut path = vec![p];
+        if p_point.eq(&q_point) {
+            return path;
+        }
+
+        let mut current = p;
+        while current != q {
+            let current_point = current.get(points);
+            let next = self.neighbors.get(&current).and_then(|neighbors| {
+                neighbors.iter().copied().find(|n| {
+                    if *n == q {
+                        return true;
+                    }
+                    let n_point = n.get(points);
+                    !n_point.eq(&p_point)
+                        && !n_point.eq(&q_point)
+                        && matches!(orient_2d(p_point, q_point, n_point), Orientation::Collinear)
+                        && (n_point.x - current_point.x) * (q_point.x - p_point.x)
+                            + (n_point.y - current_point.y) * (q_point.y - p_point.y)
+                            > 0.
+                })
+            });
+            let Some(next) = next else {
+                break;
+            };
+            path.push(next);
+            current = next;
+        }
+        path
+    }
+}
+
+pub(crate) fn edge_origins(
+    points: &Points,
+    triangles: &TriangleStore,
+    rings: &[Vec<PointId>],
+    constraints: &[Vec<PointId>],
+) -> EdgeOrigins {
+    let constrained = ConstrainedEdges::new(triangles);
+    let mut origins = EdgeOrigins::new();
+    for (ring_index, ring) in rings.iter().enumerate() {
+        for index in 0..ring.len() {
+            let id = InputEdgeId {
+                ring: ring_index,
+                index,
+            };
+            let path = constrained.path(points, ring[index], ring[(index + 1) % ring.len()]);
+            for piece in path.windows(2) {
+                origins.insert(key(piece[0], piece[1]), id);
+            }
+        }
+    }
+    // constraints are open, numbered on past the rings. A ring edge they lie on
+    // keeps its ring
+    for (line_index, line) in constraints.iter().enumerate() {
+        for index in 0..line.len().saturating_sub(1) {
+            let id = InputEdgeId {
+                ring: rings.len() + line_index,
+                index,
+            };
+            let path = constrained.path(points, line[index], line[index + 1]);
+            for piece in path.windows(2) {
+                origins.entry(key(piece[0], piece[1])).or_insert(id);
+            }
+        }
+    }
+    origins
+}
+
+/// Vertices of each ring in order, with points splitting its edges
+pub(crate) fn boundary_vertices(
+    points: &Points,
+    triangles: &TriangleStore,
+    rings: &[Vec<PointId>],
+) -> Vec<Vec<PointId>> {
+    let constrained = ConstrainedEdges::new(triangles);
+    rings
+        .iter()
+        .map(|ring| {
+            let mut vertices = Vec::with_capacity(ring.len());
+            for index in 0..ring.len() {
+                let q = ring[(index + 1) % ring.len()];
+                let path = constrained.path(points, ring[index], q);
+                vertices.extend(path.into_iter().filter(|p| *p != q));
+            }
+            vertices
+        })
+        .collect()
+}
+
+/// Input edges with points splitting them, in ring then edge order, the points
+/// in order along the edge
+pub(crate) fn edge_splits(
+    points: &Points,
+    triangles: &TriangleStore,
+    rings: &[Vec<PointId>],
+) -> Vec<(InputEdgeId, Vec<PointId>)> {
+    let constrained = ConstrainedEdges::new(triangles);
+    let mut splits = vec![];
+    for (ring_index, ring) in rings.iter().enumerate() {
+        for index in 0..ring.len() {
+            let q = ring[(index + 1) % ring.len()];
+            let path = constrained.path(points, ring[index], q);
+            let inner = path
+                .into_iter()
+                .skip(1)
+                .filter(|p| *p != q)
+                .collect::<Vec<_>>();
+            if !inner.is_empty() {
+                let id = InputEdgeId {
+                    ring: ring_index,
+                    index,
+                };
+                splits.push((id, inner));
+            }
+        }
+    }
+    splits
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, SweeperBuilder};
+
+    use super::*;
+
+    #[test]
+    fn test_edge_origin() {
+        let outer = vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ];
+        let hole = vec![
+            Point::new(4., 4.),
+            Point::new(4., 6.),
+            Point::new(6., 6.),
+            Point::new(6., 4.),
+        ];
+        let rings = [outer.clone(), hole.clone()];
+        let mut triangles = SweeperBuilder::new(outer)
+            .add_hole(hole)
+            // on outer edges, they split them
+            .add_steiner_points([Point::new(5., 0.), Point::new(10., 3.), Point::new(10., 7.)])
+            .build()
+            .triangulate();
+
+        let result = triangles.by_ref().collect::<Vec<_>>();
+        let mut lengths = HashMap::<InputEdgeId, f64>::new();
+        for (index, triangle) in result.iter().enumerate() {
+            for edge in 0..3 {
+                let Some(id) = triangles.edge_origin(index, edge) else {
+                    continue;
+                };
+                let ring = &rings[id.ring];
+                let (a, b) = (ring[id.index], ring[(id.index + 1) % ring.len()]);
+                let (p, q) = (
+                    triangle.points[(edge + 1) % 3],
+                    triangle.points[(edge + 2) % 3],
+                );
+                for point in [p, q] {
+                    assert_eq!((b.x - a.x) * (point.y - a.y), (b.y - a.y) * (point.x - a.x));
+                }
+                *lengths.entry(id).or_default() +=
+                    ((q.x - p.x).powi(2) + (q.y - p.y).powi(2)).sqrt();
+            }
+        }
+
+        // every input edge is covered once, outer edges by one triangle, hole
+        // edges too as hole triangles are not in result
+        assert_eq!(lengths.len(), 8);
+        for (id, length) in lengths {
+            let expected = if id.ring == 0 { 10. } else { 2. };
+            assert_eq!(length, expected, "{id:?}");
+        }
+    }
+
+    #[test]
+    fn test_edge_polyline() {
+        let square = |min: f64, max: f64| {
+            vec![
+                Point::new(min, min),
+                Point::new(max, min),
+                Point::new(max, max),
+                Point::new(min, max),
+            ]
+        };
+        let triangles = SweeperBuilder::new(square(0., 10.))
+            .add_hole(square(1., 3.))
+            .add_hole(square(6., 8.))
+            // collapsed into a constraint
+            .add_hole(vec![
+                Point::new(4., 2.),
+                Point::new(4., 8.),
+                Point::new(4., 5.),
+            ])
+            .build()
+            .triangulate();
+
+        let mut counts = HashMap::<PolylineId, usize>::new();
+        for index in 0..triangles.triangle_count() {
+            for edge in 0..3 {
+                let polyline = triangles.edge_polyline(index, edge);
+                let origin = triangles.edge_origin(index, edge);
+                match polyline {
+                    Some(PolylineId::Constraint(_)) => assert!(origin.is_none()),
+                    Some(PolylineId::Hole(k)) => assert_eq!(origin.unwrap().ring, k + 1),
+                    Some(PolylineId::Outer) => assert_eq!(origin.unwrap().ring, 0),
+                    None => assert!(origin.is_none()),
+                }
+                if let Some(polyline) = polyline {
+                    *counts.entry(polyline).or_default() += 1;
+                }
+            }
+        }
+        assert_eq!(counts[&PolylineId::Outer], 4);
+        assert_eq!(counts[&PolylineId::Hole(0)], 4);
+        assert_eq!(counts[&PolylineId::Hole(1)], 4);
+        // 2 pieces split at the middle point, seen from both sides
+        assert_eq!(counts[&PolylineId::Constraint(0)], 4);
+    }
+
+    #[test]
+    fn test_boundary_vertices() {
+        let outer = vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ];
+        let hole = vec![
+            Point::new(4., 4.),
+            Point::new(4., 6.),
+            Point::new(6., 6.),
+            Point::new(6., 4.),
+        ];
+        let triangles = SweeperBuilder::new(outer)
+            .add_hole(hole)
+            .add_steiner_points([Point::new(5., 0.), Point::new(10., 3.), Point::new(10., 7.)])
+            .build()
+            .triangulate();
+
+        assert_eq!(
+            triangles.boundary_vertices(),
+            vec![vec![0, 8, 1, 9, 10, 2, 3], vec![4, 5, 6, 7]]
+        );
+    }
+
+    #[test]
+    fn test_edge_splits() {
+        let triangles = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_steiner_points([Point::new(5., 0.), Point::new(10., 7.), Point::new(10., 3.)])
+        .build()
+        .triangulate();
+
+        let splits = triangles.edge_splits();
+        assert_eq!(splits.len(), 2);
+        assert_eq!(splits[0].edge, InputEdgeId { ring: 0, index: 0 });
+        assert_eq!(splits[1].edge, InputEdgeId { ring: 0, index: 1 });
+        let xy = |split: &EdgeSplit| split.points.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+        assert_eq!(xy(&splits[0]), [(5., 0.)]);
+        assert_eq!(xy(&splits[1]), [(10., 3.), (10., 7.)]);
+    }
+}
@@ -0,0 +1,115 @@
+//! Internal invariant checks after every point and edge event, with the
+//! `paranoid` feature. Slow, meant for pinpointing the event where the mesh first
+//! gets corrupted when debugging a new dataset.
+use crate::advancing_front::PointKey;
+use crate::triangles::MeshError;
+use crate::utils::orient;
+use crate::{Context, PointId, TriangleId};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub(crate) enum Violation {
+    #[error("front nodes {0:?} and {1:?} are out of order")]
+    FrontOrder(PointId, PointId),
+    #[error("front node {node:?} has triangle {triangle:?} without edge to next node {next:?}")]
+    FrontTriangle {
+        node: PointId,
+        next: PointId,
+        triangle: TriangleId,
+    },
+    #[error("triangle {0:?} is clockwise")]
+    Inverted(TriangleId),
+    #[error(transparent)]
+    Mesh(#[from] MeshError),
+}
+
+/// Panic with the first violation found, naming the `event` it follows
+pub(crate) fn assert_invariants(context: &Context, event: std::fmt::Arguments) {
+    if let Err(violation) = check(context) {
+        panic!("invariant broken after {event}: {violation}");
+    }
+}
+
+/// Check advancing front order and front node triangles, that triangles are
+/// counter clockwise, then the triangle store with
+/// [`crate::triangles::TriangleStore::validate`]
+pub(crate) fn check(context: &Context) -> Result<(), Violation> {
+    let mut prev = None;
+    for node in context.advancing_front.iter() {
+        // the last node's triangle is never read, it may be stale
+        if let (Some(triangle_id), Some(next)) = (node.triangle, node.next()) {
+            let has_edge = context.triangles.get(triangle_id).is_some_and(|t| {
+                t.points.contains(&node.point_id()) && t.points.contains(&next.point_id())
+            });
+            if !has_edge {
+                return Err(Violation::FrontTriangle {
+                    node: node.point_id(),
+                    next: next.point_id(),
+                    triangle: triangle_id,
+                });
+            }
+        }
+        if let Some((prev_id, prev_key)) = prev {
+            if prev_key >= PointKey::from(node.point()) {
+                return Err(Violation::FrontOrder(prev_id, node.point_id()));
+            }
+        }
+        prev = Some((node.point_id(), PointKey::from(node.point())));
+    }
+
+    for (triangle_id, triangle) in context.triangles.iter() {
+        let [a, b, c] = triangle.points.map(|p| p.get(context.points));
+        if orient(a, b, c) < 0. {
+            return Err(Violation::Inverted(triangle_id));
+        }
+    }
+
+    context.triangles.validate(context.points)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advancing_front::AdvancingFront;
+    use crate::points::PointsBuilder;
+    use crate::shape::InnerTriangle;
+    use crate::triangles::TriangleStore;
+    use crate::Point;
+
+    #[test]
+    fn test_check() {
+        let mut points = PointsBuilder::default();
+        let p0 = points.add_steiner_point(Point::new(0., 0.));
+        let p1 = points.add_steiner_point(Point::new(2., 0.));
+        let p2 = points.add_steiner_point(Point::new(1., 2.));
+        let p3 = points.add_steiner_point(Point::new(3., 2.));
+        let points = points.build();
+
+        let mut triangles = TriangleStore::new();
+        // front is p0, p2, p1
+        let t0 = triangles.insert(InnerTriangle::new(p2, p0, p1));
+        let t1 = triangles.insert(InnerTriangle::new(p2, p1, p3));
+        triangles.mark_neighbor(t0, t1);
+        let mut front = AdvancingFront::new(triangles.get(t0).unwrap(), t0, &points);
+
+        let context = Context::new(&points, &mut triangles, &mut front);
+        assert_eq!(check(&context), Ok(()));
+
+        // one sided link
+        context.triangles.get_mut(t1).unwrap().neighbors = [TriangleId::INVALID; 3];
+        assert_eq!(
+            check(&context),
+            Err(Violation::Mesh(MeshError::NotMutual(t0, t1)))
+        );
+        context.triangles.mark_neighbor(t0, t1);
+        context
+            .triangles
+            .get_mut(t0)
+            .unwrap()
+            .set_constrained(1, true);
+        assert_eq!(
+            check(&context),
+            Err(Violation::Mesh(MeshError::Constrained(t0, t1)))
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Triangulate on a periodic domain, a torus, where left and right, top and bottom
+//! borders of a rect wrap around. For tileable textures and terrain.
+//!
+//! Points and holes are copied into the 8 neighbor periods as ghosts, the 3 x 3
+//! block is triangulated as usual, then each triangle whose centroid lies in the
+//! center period is kept, with its vertices mapped back to the input points.
+//! Every triangle of the torus is kept exactly once.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{periodic::PeriodicBuilder, Point};
+//!
+//!    let points = (0..100).map(|i| Point::new((i % 10) as f64 + (i % 3) as f64 * 0.2, (i / 10) as f64 + 0.5));
+//!    let torus = PeriodicBuilder::new(Point::new(0., 0.), Point::new(10., 10.))
+//!        .add_points(points)
+//!        .triangulate()
+//!        .unwrap();
+//!    // euler characteristic of torus is 0
+//!    assert_eq!(torus.triangles.len(), 2 * torus.points.len());
+//! ```
+use std::collections::HashMap;
+
+use crate::utils::point_key;
+use crate::{Point, SweeperBuilder};
+
+#[derive(thiserror::Error, Debug)]
+pub enum PeriodicError {
+    /// a kept triangle joins a point to its own ghost, or reaches beyond the ghosts
+    #[error("too few points, triangle {0:?} wraps onto itself")]
+    TooSparse([Point; 3]),
+}
+
+/// Builder for a periodic triangulation over rect `min` to `max`
+#[derive(Debug, Clone)]
+pub struct PeriodicBuilder {
+    min: Point,
+    max: Point,
+    points: Vec<Point>,
+    holes: Vec<Vec<Point>>,
+}
+
+/// Triangle of a periodic triangulation
+#[derive(Debug, Clone, Copy)]
+pub struct PeriodicTriangle {
+    /// index of each vertex in [`PeriodicTriangles::points`]
+    pub indices: [usize; 3],
+    /// vertex positions, unwrapped, so vertices across a border lie outside the
+    /// rect by one period
+    pub points: [Point; 3],
+}
+
+/// Result of [`PeriodicBuilder::triangulate`]
+#[derive(Debug, Clone)]
+pub struct PeriodicTriangles {
+    /// points wrapped into the rect, points added first then hole vertices
+    pub points: Vec<Point>,
+    pub triangles: Vec<PeriodicTriangle>,
+}
+
+impl PeriodicBuilder {
+    /// Create a builder for the rect from `min` to `max`
+    pub fn new(min: Point, max: Point) -> Self {
+        assert!(
+            max.x > min.x && max.y > min.y,
+            "max should be larger than min"
+        );
+        Self {
+            min,
+            max,
+            points: vec![],
+            holes: vec![],
+        }
+    }
+
+    /// Add points, ones outside are wrapped into the rect
+    pub fn add_points(mut self, points: impl IntoIterator<Item = Point>) -> Self {
+        let points = points.into_iter().map(|p| self.wrap(p)).collect::<Vec<_>>();
+        self.points.extend(points);
+        self
+    }
+
+    /// Add a hole, it should lie inside the rect without touching its borders
+    pub fn add_hole(mut self, polyline: Vec<Point>) -> Self {
+        self.holes.push(polyline);
+        self
+    }
+
+    fn wrap(&self, p: Point) -> Point {
+        let (width, height) = (self.max.x - self.min.x, self.max.y - self.min.y);
+        Point::new(
+            self.min.x + (p.x - self.min.x).rem_euclid(width),
+            self.min.y + (p.y - self.min.y).rem_euclid(height),
+        )
+    }
+
+    /// Triangulate points and holes with their ghosts, keep triangles of the
+    /// center period
+    pub fn triangulate(self) -> Result<PeriodicTriangles, PeriodicError> {
+        let (width, height) = (self.max.x - self.min.x, self.max.y - self.min.y);
+        let offsets = (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| (x as f64 * width, y as f64 * height)))
+            .collect::<Vec<_>>();
+
+        // input index of every copy, by position
+        let mut indices = HashMap::<(u64, u64), usize>::new();
+        let mut points = self.points.clone();
+        points.extend(self.holes.iter().flatten().copied());
+        for (dx, dy) in offsets.iter() {
+            for (index, p) in points.iter().enumerate() {
+                indices.insert(point_key(Point::new(p.x + dx, p.y + dy)), index);
+            }
+        }
+
+        // some room between ghosts and the outer rect, so no ghost lies on it
+        let (pad_x, pad_y) = (width * 1.25, height * 1.25);
+        let mut builder = SweeperBuilder::new(vec![
+            Point::new(self.min.x - pad_x, self.min.y - pad_y),
+            Point::new(self.max.x + pad_x, self.min.y - pad_y),
+            Point::new(self.max.x + pad_x, self.max.y + pad_y),
+            Point::new(self.min.x - pad_x, self.max.y + pad_y),
+        ]);
+        for (dx, dy) in offsets.iter() {
+            let shift = |p: &Point| Point::new(p.x + dx, p.y + dy);
+            builder = builder.add_steiner_points(self.points.iter().map(shift));
+            for hole in self.holes.iter() {
+                builder = builder.add_hole(hole.iter().map(shift).collect());
+            }
+        }
+
+        let mut triangles = vec![];
+        for triangle in builder.build().triangulate() {
+            let [a, b, c] = triangle.points;
+            let centroid = Point::new((a.x + b.x + c.x) / 3., (a.y + b.y + c.y) / 3.);
+            if centroid.x < self.min.x
+                || centroid.x >= self.max.x
+                || centroid.y < self.min.y
+                || centroid.y >= self.max.y
+            {
+                continue;
+            }
+            let mut vertex_indices = [0; 3];
+            for (slot, p) in vertex_indices.iter_mut().zip(triangle.points) {
+                *slot = *indices
+                    .get(&point_key(p))
+                    .ok_or(PeriodicError::TooSparse(triangle.points))?;
+            }
+            let [i, j, k] = vertex_indices;
+            if i == j || j == k || k == i {
+                return Err(PeriodicError::TooSparse(triangle.points));
+            }
+            triangles.push(PeriodicTriangle {
+                indices: vertex_indices,
+                points: triangle.points,
+            });
+        }
+
+        Ok(PeriodicTriangles { points, triangles })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    fn area(points: &[Point; 3]) -> f64 {
+        let [a, b, c] = *points;
+        ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() / 2.
+    }
+
+    #[test]
+    fn test_periodic() {
+        let mut rng = rand::thread_rng();
+        let points = (0..300)
+            .map(|_| Point::new(rng.gen_range(-5.0..15.), rng.gen_range(0.0..10.)))
+            .collect::<Vec<_>>();
+        let torus = PeriodicBuilder::new(Point::new(0., 0.), Point::new(10., 10.))
+            .add_points(points)
+            .triangulate()
+            .unwrap();
+
+        assert!(torus
+            .points
+            .iter()
+            .all(|p| (0.0..10.).contains(&p.x) && (0.0..10.).contains(&p.y)));
+        assert_eq!(torus.triangles.len(), 2 * torus.points.len());
+        let total = torus.triangles.iter().map(|t| area(&t.points)).sum::<f64>();
+        assert!((total - 100.).abs() < 1e-9);
+
+        // every edge, counted by its wrapped vertices and direction, is shared by two
+        let mut edges = HashMap::<(usize, usize), usize>::new();
+        for t in torus.triangles.iter() {
+            for i in 0..3 {
+                let (p, q) = (t.indices[i], t.indices[(i + 1) % 3]);
+                *edges.entry((p.min(q), p.max(q))).or_default() += 1;
+            }
+        }
+        assert!(edges.values().all(|count| *count % 2 == 0));
+    }
+
+    #[test]
+    fn test_periodic_hole() {
+        let points = (0..100).map(|i| Point::new((i % 10) as f64 + 0.5, (i / 10) as f64 + 0.3));
+        let torus = PeriodicBuilder::new(Point::new(0., 0.), Point::new(10., 10.))
+            .add_points(points.filter(|p| !(4.0..6.).contains(&p.x) || !(4.0..6.).contains(&p.y)))
+            .add_hole(vec![
+                Point::new(4., 4.),
+                Point::new(4., 6.),
+                Point::new(6., 6.),
+                Point::new(6., 4.),
+            ])
+            .triangulate()
+            .unwrap();
+        let total = torus.triangles.iter().map(|t| area(&t.points)).sum::<f64>();
+        assert!((total - 96.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_too_sparse() {
+        let result = PeriodicBuilder::new(Point::new(0., 0.), Point::new(10., 10.))
+            .add_points([Point::new(5., 5.)])
+            .triangulate();
+        assert!(matches!(result, Err(PeriodicError::TooSparse(_))));
+    }
+}
@@ -1,6 +1,8 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
-use crate::shape::Point;
+use crate::shape::{Edge, Point};
+use crate::GhostPoints;
 
 /// Type alias to the underlying type for PointId.
 /// Despite of maximum number supported, type size also affect performance
@@ -17,6 +19,11 @@ impl PointId {
         self.0 as usize
     }
 
+    /// Create id from index in `Points`
+    pub(crate) fn from_usize(index: usize) -> Self {
+        Self(index as NumType)
+    }
+
     /// helper method used in the crate when I know the `PointId` is valid in `Points`
     pub(crate) fn get(&self, points: &Points) -> Point {
         unsafe { points.get_point_uncheck(*self) }
@@ -26,12 +33,18 @@ impl PointId {
 #[derive(Clone, Default)]
 pub struct PointsBuilder {
     points: Vec<PointWithEdge>,
+    /// edge lists of points with more than two edges, see [`PointEdges::Many`]
+    spilled: Vec<Vec<PointId>>,
+    /// times each edge added more than once was added, by lower and upper point
+    repeated: HashMap<(PointId, PointId), u32>,
 }
 
 impl PointsBuilder {
     pub fn with_capacity(cap: usize) -> Self {
         Self {
             points: Vec::with_capacity(cap),
+            spilled: vec![],
+            repeated: HashMap::new(),
         }
     }
 
@@ -54,30 +67,264 @@ impl PointsBuilder {
             }));
     }
 
-    pub(crate) fn get_point_mut(&mut self, point_id: PointId) -> Option<&mut PointWithEdge> {
-        self.points.get_mut(point_id.as_usize())
+    /// Reserve room for `additional` more points
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.points.reserve(additional);
+    }
+
+    pub(crate) fn get_point(&self, point_id: PointId) -> Point {
+        self.points[point_id.as_usize()].point
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Add a constrained edge, it is stored at its upper point. Adding an existing
+    /// edge again removes it, e.g. a hole sharing an edge with the outer polyline,
+    /// returns false in that case.
+    pub(crate) fn add_edge(&mut self, edge: Edge) -> bool {
+        let added = self.toggle_edge(edge);
+        let (p, q) = (edge.p, edge.q);
+        // an edge seen once is not looked up, most input has no repeats
+        if !added {
+            *self.repeated.entry((p, q)).or_insert(1) += 1;
+        } else if let Some(count) = self.repeated.get_mut(&(p, q)) {
+            *count += 1;
+        }
+        added
+    }
+
+    /// Remove a constrained edge if it is kept, e.g. a ring edge split in two.
+    /// Unlike adding it again, it is not counted as repeated.
+    pub(crate) fn remove_edge(&mut self, edge: Edge) {
+        if !self.toggle_edge(edge) {
+            return;
+        }
+        // it wasn't there, toggle back
+        self.toggle_edge(edge);
+    }
+
+    /// Add `edge`, or remove it when already kept, returns whether it is added
+    fn toggle_edge(&mut self, edge: Edge) -> bool {
+        let p = edge.p;
+        let edges = &mut self.points[edge.q.as_usize()].edges;
+        let (new_edges, added) = match *edges {
+            PointEdges::None => (PointEdges::One(p), true),
+            PointEdges::One(p0) if p0 == p => (PointEdges::None, false),
+            PointEdges::One(p0) => (PointEdges::Two(p0, p), true),
+            PointEdges::Two(p0, p1) if p0 == p => (PointEdges::One(p1), false),
+            PointEdges::Two(p0, p1) if p1 == p => (PointEdges::One(p0), false),
+            PointEdges::Two(p0, p1) => {
+                self.spilled.push(vec![p0, p1, p]);
+                (PointEdges::Many((self.spilled.len() - 1) as NumType), true)
+            }
+            PointEdges::Many(index) => {
+                let lower = &mut self.spilled[index as usize];
+                let added = match lower.iter().position(|p0| *p0 == p) {
+                    Some(pos) => {
+                        lower.remove(pos);
+                        false
+                    }
+                    None => {
+                        lower.push(p);
+                        true
+                    }
+                };
+                (PointEdges::Many(index), added)
+            }
+        };
+        *edges = new_edges;
+        added
+    }
+
+    /// Add a constrained edge which is kept when added again, e.g. a collapsed
+    /// hole on an edge of a ring
+    pub(crate) fn add_open_edge(&mut self, edge: Edge) {
+        if !self.add_edge(edge) {
+            self.add_edge(edge);
+            // one more add, counted twice by toggling back
+            *self.repeated.get_mut(&(edge.p, edge.q)).unwrap() -= 1;
+        }
+    }
+
+    /// Edges added more than once, with times added and whether they are kept
+    pub(crate) fn repeated_edges(&self) -> impl Iterator<Item = (Edge, u32, bool)> + '_ {
+        self.repeated.iter().map(|(&(p, q), &count)| {
+            let edge = Edge { p, q };
+            (edge, count, self.has_edge(edge))
+        })
+    }
+
+    /// Whether `edge` is kept
+    pub(crate) fn has_edge(&self, edge: Edge) -> bool {
+        let p = edge.p;
+        match self.points[edge.q.as_usize()].edges {
+            PointEdges::None => false,
+            PointEdges::One(p0) => p0 == p,
+            PointEdges::Two(p0, p1) => p0 == p || p1 == p,
+            PointEdges::Many(index) => self.spilled[index as usize].contains(&p),
+        }
+    }
+
+    /// Snap points whose x is within `tolerance` of the first point of its run, in
+    /// x order, to that point's x. Returns the largest move
+    pub(crate) fn snap_x(&mut self, tolerance: f64) -> f64 {
+        let mut order = (0..self.points.len()).collect::<Vec<_>>();
+        order.sort_unstable_by(|l, r| self.points[*l].point.x.total_cmp(&self.points[*r].point.x));
+
+        let mut moved = 0f64;
+        let mut anchor = f64::NAN;
+        for idx in order {
+            let point = &mut self.points[idx].point;
+            if point.x - anchor <= tolerance {
+                moved = moved.max(point.x - anchor);
+                point.x = anchor;
+            } else {
+                anchor = point.x;
+            }
+        }
+        moved
+    }
+
+    /// Shear factor `k` for `x + k * y` which splits columns of 3 or more points
+    /// with the same x, after mapping by `f`. Points of a column get increasing x
+    /// by y, and stay between the neighboring columns. `None` without columns
+    pub(crate) fn column_shear(&self, f: impl Fn(Point) -> Point) -> Option<f64> {
+        let mut points = self.points.iter().map(|p| f(p.point)).collect::<Vec<_>>();
+        points.sort_unstable_by(|l, r| l.x.total_cmp(&r.x));
+
+        let (mut min_y, mut max_y) = (f64::MAX, f64::MIN);
+        let (mut min_gap, mut run, mut has_column) = (f64::MAX, 1, false);
+        for (i, p) in points.iter().enumerate() {
+            (min_y, max_y) = (min_y.min(p.y), max_y.max(p.y));
+            let Some(prev) = i.checked_sub(1).map(|i| points[i]) else {
+                continue;
+            };
+            if p.x == prev.x {
+                run += 1;
+                has_column |= run >= 3;
+            } else {
+                min_gap = min_gap.min(p.x - prev.x);
+                run = 1;
+            }
+        }
+        if !has_column || max_y <= min_y {
+            return None;
+        }
+        Some((min_gap / (max_y - min_y) / 2.).min(1e-3))
+    }
+
+    /// Head and tail, left and right below all points. Around the origin without
+    /// points, nothing is swept then
+    fn ghosts(&self, ghost_points: GhostPoints) -> [Point; 2] {
+        let (mut xmin, mut xmax, mut ymin, mut ymax) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+        for p in self.points.iter() {
+            xmax = xmax.max(p.point.x);
+            xmin = xmin.min(p.point.x);
+            ymax = ymax.max(p.point.y);
+            ymin = ymin.min(p.point.y);
+        }
+        if self.points.is_empty() {
+            (xmin, xmax, ymin, ymax) = (0., 0., 0., 0.);
+        }
+
+        let (mut dx, mut dy) = ((xmax - xmin) * 0.3, (ymax - ymin) * 0.3);
+        if ghost_points == GhostPoints::Square {
+            (dx, dy) = (dx.max(dy), dx.max(dy));
+        }
+        [
+            Point::new(xmin - dx, ymin - dy),
+            Point::new(xmax + dx, ymin - dy),
+        ]
+    }
+
+    /// Largest absolute coordinate, NaN is ignored
+    pub(crate) fn max_magnitude(&self) -> f64 {
+        self.points
+            .iter()
+            .map(|p| p.point.x.abs().max(p.point.y.abs()))
+            .fold(0., f64::max)
+    }
+
+    /// Width and height of the bounding box of points mapped by `f`
+    pub(crate) fn extent(&self, f: impl Fn(Point) -> Point) -> (f64, f64) {
+        let (mut min, mut max) = ([f64::MAX; 2], [f64::MIN; 2]);
+        for p in self.points.iter().map(|p| f(p.point)) {
+            min = [min[0].min(p.x), min[1].min(p.y)];
+            max = [max[0].max(p.x), max[1].max(p.y)];
+        }
+        ((max[0] - min[0]).max(0.), (max[1] - min[1]).max(0.))
+    }
+
+    /// Map all points by `f`, edges are ordered again as `f` may change which end
+    /// is lower
+    pub(crate) fn transform(&mut self, mut f: impl FnMut(Point) -> Point) {
+        let mut edges = vec![];
+        for (q, point) in self.points.iter_mut().enumerate() {
+            let (inline, spilled) = match point.edges {
+                PointEdges::None => ([None, None], &[][..]),
+                PointEdges::One(p) => ([Some(p), None], &[][..]),
+                PointEdges::Two(p0, p1) => ([Some(p0), Some(p1)], &[][..]),
+                PointEdges::Many(index) => ([None, None], self.spilled[index as usize].as_slice()),
+            };
+            let q = PointId::from_usize(q);
+            edges.extend(
+                inline
+                    .into_iter()
+                    .flatten()
+                    .chain(spilled.iter().copied())
+                    .map(|p| (p, q)),
+            );
+            point.point = f(point.point);
+            point.edges = PointEdges::None;
+        }
+        self.spilled.clear();
+        // re-adding is not repeating, and ends may swap
+        let repeated = std::mem::take(&mut self.repeated);
+        for (p, q) in edges {
+            let edge = Edge::new((p, &self.get_point(p)), (q, &self.get_point(q)));
+            self.add_edge(edge);
+        }
+        self.repeated = repeated
+            .into_iter()
+            .map(|((p, q), count)| {
+                let edge = Edge::new((p, &self.get_point(p)), (q, &self.get_point(q)));
+                ((edge.p, edge.q), count)
+            })
+            .collect();
+    }
+
+    /// Multiply all coordinates by `factor`
+    pub(crate) fn scale(&mut self, factor: f64) {
+        for p in self.points.iter_mut() {
+            p.point = Point::new(p.point.x * factor, p.point.y * factor);
+        }
     }
 
     pub fn build(self) -> Points {
-        Points::new(self.points)
+        self.build_with(GhostPoints::default())
+    }
+
+    /// Build with head and tail placed by `ghost_points`. They are placed here, once
+    /// all points are added, so a builder may start empty.
+    pub(crate) fn build_with(self, ghost_points: GhostPoints) -> Points {
+        let ghosts = self.ghosts(ghost_points);
+        let mut points = Points::new(self.points, ghosts);
+        points.spilled = self.spilled;
+        points
     }
 }
 
+/// Lower points of edges ending at a point
 #[derive(Clone, Copy)]
 pub enum PointEdges {
     None,
     One(PointId),
     Two(PointId, PointId),
-}
-
-impl PointEdges {
-    pub fn push(&mut self, point_id: PointId) {
-        *self = match self {
-            PointEdges::None => PointEdges::One(point_id),
-            PointEdges::One(p0) => PointEdges::Two(*p0, point_id),
-            PointEdges::Two(_, _) => panic!("one point only has two edges"),
-        };
-    }
+    /// more than two edges, e.g. where a hole touches another ring. It is the
+    /// index of the list spilled to [`Points`], iterate with [`Points::edges`]
+    Many(NumType),
 }
 
 #[derive(Clone, Copy)]
@@ -86,53 +333,24 @@ pub struct PointWithEdge {
     pub edges: PointEdges,
 }
 
-impl Iterator for PointEdges {
-    type Item = PointId;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut result = None;
-        let new_self = match std::mem::replace(self, PointEdges::None) {
-            PointEdges::None => PointEdges::None,
-            PointEdges::One(p) => {
-                result = Some(p);
-                PointEdges::None
-            }
-            PointEdges::Two(p0, p1) => {
-                result = Some(p0);
-                PointEdges::One(p1)
-            }
-        };
-        *self = new_self;
-        result
-    }
-}
-
 /// Point store
 #[derive(Clone)]
 pub struct Points {
     points: Vec<PointWithEdge>,
     y_sorted: Vec<PointId>,
+    /// edge lists of points with more than two edges
+    spilled: Vec<Vec<PointId>>,
     pub head: PointId,
     pub tail: PointId,
 }
 
 impl Points {
-    pub fn new(mut points: Vec<PointWithEdge>) -> Self {
-        let mut xmax = f64::MIN;
-        let mut xmin = f64::MAX;
-        let mut ymax = f64::MIN;
-        let mut ymin = f64::MAX;
-
+    /// Index `points` by y, then append head and tail, see [`PointsBuilder::build`]
+    fn new(mut points: Vec<PointWithEdge>, [head, tail]: [Point; 2]) -> Self {
         let mut unsorted_points = points
             .iter()
             .enumerate()
-            .map(|(idx, p)| {
-                xmax = xmax.max(p.point.x);
-                xmin = xmin.min(p.point.x);
-                ymax = ymax.max(p.point.y);
-                ymin = ymin.min(p.point.y);
-                (PointId(idx as NumType), p.point)
-            })
+            .map(|(idx, p)| (PointId(idx as NumType), p.point))
             .collect::<Vec<_>>();
 
         // sort by y
@@ -157,38 +375,44 @@ impl Points {
             .map(|(idx, _)| idx)
             .collect::<Vec<_>>();
 
-        let (head, tail) = {
-            let dx = (xmax - xmin) * 0.3;
-            let dy = (ymax - ymin) * 0.3;
-
-            let head = Point::new(xmin - dx, ymin - dy);
-            let head_id = PointId(points.len() as NumType);
-            points.push(PointWithEdge {
-                point: head,
-                edges: PointEdges::None,
-            });
-
-            let tail = Point::new(xmax + dx, ymin - dy);
-            let tail_id = PointId(points.len() as NumType);
+        let mut push = |point| {
             points.push(PointWithEdge {
-                point: tail,
+                point,
                 edges: PointEdges::None,
             });
-            (head_id, tail_id)
+            PointId((points.len() - 1) as NumType)
         };
+        let (head, tail) = (push(head), push(tail));
 
         Self {
             points,
             y_sorted: sorted_ids,
+            spilled: vec![],
             head,
             tail,
         }
     }
 
+    /// Number of points, head and tail included
     pub fn len(&self) -> usize {
         self.points.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Iterate lower points of `edges`
+    pub fn edges(&self, edges: PointEdges) -> impl Iterator<Item = PointId> + '_ {
+        let (inline, spilled) = match edges {
+            PointEdges::None => ([None, None], &[][..]),
+            PointEdges::One(p) => ([Some(p), None], &[][..]),
+            PointEdges::Two(p0, p1) => ([Some(p0), Some(p1)], &[][..]),
+            PointEdges::Many(index) => ([None, None], self.spilled[index as usize].as_slice()),
+        };
+        inline.into_iter().flatten().chain(spilled.iter().copied())
+    }
+
     /// get point for id
     pub fn get_point(&self, point_id: PointId) -> Option<Point> {
         self.points
@@ -197,8 +421,18 @@ impl Points {
             .cloned()
     }
 
+    /// Move a point, its y order is left as is
+    pub(crate) fn set_point(&mut self, point_id: PointId, point: Point) {
+        self.points[point_id.as_usize()].point = point;
+    }
+
+    /// Edges ending at point, see [`Self::edges`]
+    pub(crate) fn point_edges(&self, point_id: PointId) -> PointEdges {
+        self.points[point_id.as_usize()].edges
+    }
+
     /// get point for id
-    pub unsafe fn get_point_uncheck(&self, point_id: PointId) -> Point {
+    pub(crate) unsafe fn get_point_uncheck(&self, point_id: PointId) -> Point {
         unsafe { self.points.get_unchecked(point_id.as_usize()).point }
     }
 
@@ -207,7 +441,7 @@ impl Points {
         order: usize,
     ) -> impl Iterator<Item = (PointId, Point, PointEdges)> + 'a {
         self.y_sorted.iter().skip(order).map(|id| {
-            let point = unsafe { self.points.get_unchecked(id.as_usize()).clone() };
+            let point = unsafe { *self.points.get_unchecked(id.as_usize()) };
             (*id, point.point, point.edges)
         })
     }
@@ -218,6 +452,44 @@ impl Points {
         self.y_sorted.get(order).cloned()
     }
 
+    /// Append a point with no edges, it is not part of the y order, caller feeds
+    /// it to the sweep by itself
+    pub(crate) fn push(&mut self, point: Point) -> PointId {
+        let point_id = PointId(self.points.len() as NumType);
+        self.points.push(PointWithEdge {
+            point,
+            edges: PointEdges::None,
+        });
+        point_id
+    }
+
+    /// Drop points which are not kept, remaining ones are renumbered in order.
+    /// Returns new id for each old id, `None` for dropped ones.
+    /// Points referred by y order, edges, head or tail should be kept and should
+    /// not move, i.e. every point before them is kept too.
+    pub(crate) fn retain(&mut self, keep: &[bool]) -> Vec<Option<PointId>> {
+        debug_assert_eq!(keep.len(), self.points.len());
+        let mut next = 0;
+        let remap = keep
+            .iter()
+            .map(|keep| {
+                keep.then(|| {
+                    next += 1;
+                    PointId((next - 1) as NumType)
+                })
+            })
+            .collect::<Vec<_>>();
+        debug_assert!(remap[self.head.as_usize()] == Some(self.head));
+        debug_assert!(remap[self.tail.as_usize()] == Some(self.tail));
+
+        let mut index = 0;
+        self.points.retain(|_| {
+            index += 1;
+            keep[index - 1]
+        });
+        remap
+    }
+
     /// iter all points
     pub fn iter(&self) -> impl Iterator<Item = (PointId, &Point, PointEdges)> {
         self.points
@@ -0,0 +1,108 @@
+//! 6-node quadratic triangles for finite element solvers. Each mesh edge gets one
+//! midside node at its midpoint, shared by the triangles on both sides.
+use std::collections::HashMap;
+
+use crate::{Point, Triangles};
+
+/// Mesh of 6-node triangles. Nodes of an element are the corners counter
+/// clockwise, then the midside nodes of edges 0-1, 1-2 and 2-0, the usual
+/// convention of e.g. gmsh and vtk.
+#[derive(Debug, Clone, Default)]
+pub struct QuadraticMesh {
+    nodes: Vec<Point>,
+    corner_count: usize,
+    elements: Vec<[u32; 6]>,
+}
+
+impl QuadraticMesh {
+    /// Elements for result of `triangles`, regardless of iteration progress
+    pub fn new(triangles: &Triangles) -> Self {
+        let mut nodes = (0..triangles.vertex_count())
+            .map(|index| triangles.vertex(index))
+            .collect::<Vec<_>>();
+        let corner_count = nodes.len();
+        let mut flat = vec![0; triangles.index_count()];
+        triangles.write_indices_into(&mut flat);
+
+        let mut midside = HashMap::<(u32, u32), u32>::with_capacity(flat.len());
+        let elements = flat
+            .chunks_exact(3)
+            .map(|c| {
+                let mut element = [c[0], c[1], c[2], 0, 0, 0];
+                for i in 0..3 {
+                    let (a, b) = (c[i], c[(i + 1) % 3]);
+                    element[3 + i] = *midside.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                        let (p, q) = (nodes[a as usize], nodes[b as usize]);
+                        nodes.push(Point::new((p.x + q.x) / 2., (p.y + q.y) / 2.));
+                        (nodes.len() - 1) as u32
+                    });
+                }
+                element
+            })
+            .collect();
+
+        Self {
+            nodes,
+            corner_count,
+            elements,
+        }
+    }
+
+    /// Node positions, corner nodes first as input in input order, then midside
+    /// nodes in order of first use
+    pub fn nodes(&self) -> &[Point] {
+        &self.nodes
+    }
+
+    /// Number of corner nodes, index of the first midside node
+    pub fn corner_count(&self) -> usize {
+        self.corner_count
+    }
+
+    /// Node indices of each element, see [`QuadraticMesh`] for the order
+    pub fn elements(&self) -> &[[u32; 6]] {
+        &self.elements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SweeperBuilder;
+
+    #[test]
+    fn test_quadratic() {
+        let mesh = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_steiner_points([Point::new(5., 5.)])
+        .build()
+        .triangulate_quadratic();
+
+        assert_eq!(mesh.elements().len(), 4);
+        assert_eq!(mesh.corner_count(), 5);
+        // 4 border edges, 4 edges to the center
+        assert_eq!(mesh.nodes().len(), 5 + 8);
+        for element in mesh.elements() {
+            let node = |i: usize| mesh.nodes()[element[i] as usize];
+            for i in 0..3 {
+                let (a, b, mid) = (node(i), node((i + 1) % 3), node(3 + i));
+                assert_eq!((mid.x, mid.y), ((a.x + b.x) / 2., (a.y + b.y) / 2.));
+            }
+            assert!(element[..3].iter().all(|i| (*i as usize) < 5));
+        }
+
+        // edges to the center are shared, each midside node is used twice
+        let mut uses = vec![0; mesh.nodes().len()];
+        for element in mesh.elements() {
+            for i in &element[3..] {
+                uses[*i as usize] += 1;
+            }
+        }
+        let center = uses[5..].iter().filter(|n| **n == 2).count();
+        assert_eq!(center, 4);
+    }
+}
@@ -0,0 +1,154 @@
+//! Triangle quality metrics, to find where the mesh is bad before refining it.
+use crate::{Point, Triangle, Triangles};
+
+/// Quality measure of a triangle, see [`Triangles::worst_triangles`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criterion {
+    /// smallest angle in degrees, smaller is worse
+    MinAngle,
+    /// largest angle in degrees, larger is worse
+    MaxAngle,
+    /// circumradius over shortest edge, larger is worse. `1 / √3` for equilateral
+    RadiusEdgeRatio,
+}
+
+impl Criterion {
+    /// Measure `triangle`
+    pub fn measure(&self, triangle: &Triangle) -> f64 {
+        let [a, b, c] = triangle.points;
+        let lengths = [distance(b, c), distance(c, a), distance(a, b)];
+        match self {
+            Self::MinAngle => angles(lengths).into_iter().fold(f64::MAX, f64::min),
+            Self::MaxAngle => angles(lengths).into_iter().fold(0., f64::max),
+            Self::RadiusEdgeRatio => {
+                let area2 = ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs();
+                let radius = lengths[0] * lengths[1] * lengths[2] / (2. * area2);
+                radius / lengths.into_iter().fold(f64::MAX, f64::min)
+            }
+        }
+    }
+
+    /// Order values, worse first
+    fn worse(&self, l: f64, r: f64) -> std::cmp::Ordering {
+        match self {
+            Self::MinAngle => l.total_cmp(&r),
+            Self::MaxAngle | Self::RadiusEdgeRatio => r.total_cmp(&l),
+        }
+    }
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+/// Angles in degrees, each opposite to the edge of `lengths` at the same index
+fn angles(lengths: [f64; 3]) -> [f64; 3] {
+    [0, 1, 2].map(|i| {
+        let (a, b, c) = (lengths[i], lengths[(i + 1) % 3], lengths[(i + 2) % 3]);
+        ((b * b + c * c - a * a) / (2. * b * c))
+            .clamp(-1., 1.)
+            .acos()
+            .to_degrees()
+    })
+}
+
+impl Triangles {
+    /// The `n` worst result triangles by `criterion`, worst first, as result index
+    /// and value. Index is the same as [`Triangles::triangle`].
+    pub fn worst_triangles(&self, n: usize, criterion: Criterion) -> Vec<(usize, f64)> {
+        let mut values = (0..self.index_count() / 3)
+            .filter_map(|index| Some((index, criterion.measure(&self.triangle(index)?))))
+            .collect::<Vec<_>>();
+        values.sort_by(|l, r| criterion.worse(l.1, r.1));
+        values.truncate(n);
+        values
+    }
+
+    /// Count of result triangles by min angle, in `bins` even bins over 0 to 60
+    /// degrees
+    pub fn angle_histogram(&self, bins: usize) -> Vec<usize> {
+        let mut histogram = vec![0; bins];
+        if bins == 0 {
+            return histogram;
+        }
+        for index in 0..self.index_count() / 3 {
+            let Some(triangle) = self.triangle(index) else {
+                continue;
+            };
+            let angle = Criterion::MinAngle.measure(&triangle);
+            let bin = (angle / 60. * bins as f64) as usize;
+            histogram[bin.min(bins - 1)] += 1;
+        }
+        histogram
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SweeperBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let equilateral = Triangle {
+            points: [
+                Point::new(0., 0.),
+                Point::new(2., 0.),
+                Point::new(1., 3f64.sqrt()),
+            ],
+        };
+        assert!((Criterion::MinAngle.measure(&equilateral) - 60.).abs() < 1e-9);
+        assert!((Criterion::MaxAngle.measure(&equilateral) - 60.).abs() < 1e-9);
+        let ratio = Criterion::RadiusEdgeRatio.measure(&equilateral);
+        assert!((ratio - 1. / 3f64.sqrt()).abs() < 1e-9);
+
+        let right = Triangle {
+            points: [Point::new(0., 0.), Point::new(1., 0.), Point::new(0., 1.)],
+        };
+        assert!((Criterion::MinAngle.measure(&right) - 45.).abs() < 1e-9);
+        assert!((Criterion::MaxAngle.measure(&right) - 90.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_worst_triangles() {
+        // a point close to the bottom edge makes slivers
+        let triangles = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_steiner_points([Point::new(5., 0.1), Point::new(3., 6.), Point::new(7., 4.)])
+        .build()
+        .triangulate();
+        let count = triangles.index_count() / 3;
+
+        for criterion in [
+            Criterion::MinAngle,
+            Criterion::MaxAngle,
+            Criterion::RadiusEdgeRatio,
+        ] {
+            let all = triangles.worst_triangles(usize::MAX, criterion);
+            assert_eq!(all.len(), count);
+            for pair in all.windows(2) {
+                assert!(criterion.worse(pair[0].1, pair[1].1).is_le());
+            }
+
+            let worst = triangles.worst_triangles(2, criterion);
+            assert_eq!(worst, all[..2]);
+            let (index, value) = worst[0];
+            assert_eq!(
+                criterion.measure(&triangles.triangle(index).unwrap()),
+                value
+            );
+        }
+
+        let (_, min_angle) = triangles.worst_triangles(1, Criterion::MinAngle)[0];
+        assert!(min_angle < 2.);
+
+        let histogram = triangles.angle_histogram(6);
+        assert_eq!(histogram.iter().sum::<usize>(), count);
+        assert!(histogram[0] > 0);
+    }
+}
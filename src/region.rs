@@ -0,0 +1,189 @@
+//! Material ids of result triangles from region seed points, like regional
+//! attributes of Triangle
+use crate::points::Points;
+use crate::triangles::{TriangleId, TriangleStore};
+use crate::utils::{orient, point_in_ring, signed_area};
+use crate::{Point, PointId};
+
+/// Material id of each result triangle, in result order. Each seed floods the
+/// region holding it, i.e. interior triangles reachable without crossing a
+/// constrained edge. A region keeps the id of its first seed, triangles in no
+/// seeded region are `None`. Seeds outside the result are ignored.
+pub(crate) fn label_regions(
+    points: &Points,
+    triangles: &TriangleStore,
+    result: &[TriangleId],
+    seeds: &[(Point, u32)],
+) -> Vec<Option<u32>> {
+    let mut result_index = vec![usize::MAX; triangles.len()];
+    for (index, triangle_id) in result.iter().enumerate() {
+        result_index[triangle_id.as_usize()] = index;
+    }
+
+    let mut materials = vec![None; result.len()];
+    let mut stack = vec![];
+    for (seed, material) in seeds {
+        let Some(start) = result.iter().position(|t| {
+            let [a, b, c] = t.get(triangles).points.map(|p| p.get(points));
+            orient(a, b, *seed) >= 0. && orient(b, c, *seed) >= 0. && orient(c, a, *seed) >= 0.
+        }) else {
+            continue;
+        };
+
+        stack.push(start);
+        while let Some(index) = stack.pop() {
+            if materials[index].is_some() {
+                continue;
+            }
+            materials[index] = Some(*material);
+            let triangle = result[index].get(triangles);
+            for i in 0..3 {
+                let neighbor = triangle.neighbors[i];
+                if triangle.is_constrained(i) || neighbor.invalid() {
+                    continue;
+                }
+                // stay inside the result
+                match result_index[neighbor.as_usize()] {
+                    usize::MAX => {}
+                    next => stack.push(next),
+                }
+            }
+        }
+    }
+    materials
+}
+
+/// Triangles inside each hole, hole `k` is ring `k + 1`. Triangles outside the
+/// result are flooded into pieces up to constrained edges, a piece goes to the
+/// innermost ring around it. Pieces outside the outer polyline are dropped.
+pub(crate) fn hole_triangles(
+    points: &Points,
+    triangles: &TriangleStore,
+    rings: &[Vec<PointId>],
+) -> Vec<Vec<TriangleId>> {
+    let rings = rings
+        .iter()
+        .map(|ring| ring.iter().map(|p| p.get(points)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let areas = rings
+        .iter()
+        .map(|ring| signed_area(ring).abs())
+        .collect::<Vec<_>>();
+
+    let mut holes = vec![vec![]; rings.len().saturating_sub(1)];
+    let mut seen = vec![false; triangles.len()];
+    let mut stack = vec![];
+    let mut piece = vec![];
+    for (start, triangle) in triangles.iter() {
+        if triangle.interior || seen[start.as_usize()] {
+            continue;
+        }
+        seen[start.as_usize()] = true;
+        stack.push(start);
+        piece.clear();
+        while let Some(triangle_id) = stack.pop() {
+            piece.push(triangle_id);
+            let triangle = triangle_id.get(triangles);
+            for i in 0..3 {
+                let neighbor = triangle.neighbors[i];
+                if triangle.is_constrained(i)
+                    || neighbor.invalid()
+                    || seen[neighbor.as_usize()]
+                    || neighbor.get(triangles).interior
+                {
+                    continue;
+                }
+                seen[neighbor.as_usize()] = true;
+                stack.push(neighbor);
+            }
+        }
+
+        // rings don't cross, the smallest one around is the innermost
+        let [a, b, c] = triangle.points.map(|p| p.get(points));
+        let centroid = Point::new((a.x + b.x + c.x) / 3., (a.y + b.y + c.y) / 3.);
+        let ring = (0..rings.len())
+            .filter(|ring| point_in_ring(&rings[*ring], centroid) == Some(true))
+            .min_by(|l, r| areas[*l].total_cmp(&areas[*r]));
+        if let Some(ring) = ring.filter(|ring| *ring > 0) {
+            holes[ring - 1].extend(piece.iter().copied());
+        }
+    }
+    holes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, SweeperBuilder};
+
+    fn square(min: f64, max: f64) -> Vec<Point> {
+        vec![
+            Point::new(min, min),
+            Point::new(max, min),
+            Point::new(max, max),
+            Point::new(min, max),
+        ]
+    }
+
+    #[test]
+    fn test_label_regions() {
+        // an island inside the hole
+        let triangles = SweeperBuilder::new(square(0., 10.))
+            .add_hole(square(3., 7.))
+            .add_hole(square(4., 6.))
+            .add_region_seed(Point::new(1., 1.), 1)
+            .add_region_seed(Point::new(5., 5.), 2)
+            .add_region_seed(Point::new(9., 9.), 3)
+            .add_region_seed(Point::new(20., 20.), 4)
+            .build()
+            .triangulate();
+
+        let count = triangles.triangle_count();
+        let materials = (0..count)
+            .map(|index| triangles.material(index))
+            .collect::<Vec<_>>();
+        assert_eq!(materials.iter().filter(|m| **m == Some(2)).count(), 2);
+        assert_eq!(
+            materials.iter().filter(|m| **m == Some(1)).count(),
+            count - 2
+        );
+        assert!(triangles.material(count).is_none());
+
+        // monotone fast path
+        let triangles = SweeperBuilder::new(square(0., 10.))
+            .add_region_seed(Point::new(5., 5.), 7)
+            .build()
+            .triangulate();
+        assert_eq!(triangles.material(0), Some(7));
+        assert_eq!(triangles.material(1), Some(7));
+        let triangles = SweeperBuilder::new(square(0., 10.)).build().triangulate();
+        assert_eq!(triangles.material(0), None);
+    }
+
+    #[test]
+    fn test_hole_triangles() {
+        let area = |t: crate::Triangle| {
+            let [a, b, c] = t.points;
+            ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2.
+        };
+        let builder = SweeperBuilder::new(square(0., 10.))
+            .add_hole(square(1., 4.))
+            .add_hole(square(5., 9.))
+            // an island in the second hole
+            .add_hole(square(6., 7.));
+        let triangles = builder
+            .clone()
+            .triangulate_holes(true)
+            .build()
+            .triangulate();
+        assert_eq!(triangles.hole_count(), 3);
+        let areas = (0..4)
+            .map(|hole| triangles.hole_triangles(hole).map(area).sum::<f64>())
+            .collect::<Vec<_>>();
+        assert_eq!(areas, [9., 15., 0., 0.]);
+        assert_eq!(triangles.map(area).sum::<f64>(), 100. - 9. - 15.);
+
+        let triangles = builder.build().triangulate();
+        assert_eq!(triangles.hole_count(), 0);
+        assert_eq!(triangles.hole_triangles(0).count(), 0);
+    }
+}
@@ -0,0 +1,574 @@
+//! Sanitation passes for messy input, used by [`crate::SweeperBuilder::repair_input`].
+//!
+//! Passes run in order: merge close points, drop closing and repeated points,
+//! orient rings, close gaps between vertices and edges, split edges at
+//! intersections. Rings which degenerate along the way are dropped.
+use std::collections::HashMap;
+
+use crate::points::PointsBuilder;
+use crate::shape::Edge;
+use crate::utils::{dedup_ring, point_key, signed_area};
+use crate::{Point, PointId};
+
+/// Options for [`crate::SweeperBuilder::repair_input`]
+#[derive(Debug, Clone)]
+pub struct RepairOptions {
+    /// points within this distance are merged into the first one, `0.` only
+    /// merges exact duplicates
+    pub merge_distance: f64,
+    /// orient outer ring ccw and holes cw
+    pub orient: bool,
+    /// a vertex within this distance of an edge is inserted into the edge, closing
+    /// the gap, e.g. rings traced from a scan which nearly touch. `0.` disables
+    pub snap_distance: f64,
+    /// split edges at intersections and touching points
+    pub split_intersections: bool,
+}
+
+impl Default for RepairOptions {
+    fn default() -> Self {
+        Self {
+            merge_distance: 0.,
+            orient: true,
+            snap_distance: 0.,
+            split_intersections: true,
+        }
+    }
+}
+
+impl RepairOptions {
+    /// set the merge distance
+    pub fn merge_distance(mut self, distance: f64) -> Self {
+        self.merge_distance = distance;
+        self
+    }
+
+    /// enable or disable orienting rings
+    pub fn orient(mut self, enabled: bool) -> Self {
+        self.orient = enabled;
+        self
+    }
+
+    /// set the snap distance
+    pub fn snap_distance(mut self, distance: f64) -> Self {
+        self.snap_distance = distance;
+        self
+    }
+
+    /// enable or disable splitting edges at intersections
+    pub fn split_intersections(mut self, enabled: bool) -> Self {
+        self.split_intersections = enabled;
+        self
+    }
+}
+
+/// What [`crate::SweeperBuilder::repair_input`] changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// points merged into another one, closing points included
+    pub points_merged: usize,
+    /// points inserted into edges at intersections
+    pub edges_split: usize,
+    /// vertices inserted into a close edge, see [`RepairOptions::snap_distance`]
+    pub gaps_closed: usize,
+    /// rings reversed to get the expected orientation
+    pub rings_reoriented: usize,
+    /// rings left with less than 3 points, or all points collinear
+    pub rings_dropped: usize,
+    /// overlapping edges dropped, e.g. a hole sharing an edge with the outer ring.
+    /// They cancel out in pairs, so the area on both sides is outside
+    pub edges_dropped: usize,
+}
+
+/// Repaired input, rings share point ids where they touch
+pub(crate) struct Repaired {
+    pub points_builder: PointsBuilder,
+    pub rings: Vec<Vec<PointId>>,
+    pub report: RepairReport,
+}
+
+/// Repair `rings`, the first one is the outer, and steiner points
+pub(crate) fn repair(
+    rings: Vec<Vec<Point>>,
+    steiner_points: Vec<Point>,
+    options: &RepairOptions,
+) -> Repaired {
+    let mut report = RepairReport::default();
+    let mut merger = Merger::new(options.merge_distance);
+
+    let mut rings = rings
+        .into_iter()
+        .map(|ring| {
+            ring.into_iter()
+                .map(|p| merger.merge(p, &mut report))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let steiner_points = steiner_points
+        .into_iter()
+        .map(|p| merger.merge(p, &mut report))
+        .collect::<Vec<_>>();
+
+    rings = clean_rings(rings, &mut report);
+
+    if options.orient {
+        for (idx, ring) in rings.iter_mut().enumerate() {
+            if (signed_area(ring) > 0.) != (idx == 0) {
+                ring.reverse();
+                report.rings_reoriented += 1;
+            }
+        }
+    }
+
+    if options.snap_distance > 0. {
+        rings = close_gaps(rings, options.snap_distance, &mut report);
+        rings = clean_rings(rings, &mut report);
+    }
+
+    if options.split_intersections {
+        rings = split_intersections(rings, &mut merger, &mut report);
+        rings = clean_rings(rings, &mut report);
+    }
+
+    build(rings, steiner_points, report)
+}
+
+/// drop repeated points, spikes and collinear rings. Unlike tiling, zero area
+/// rings are kept, e.g. a bow tie
+fn clean_rings(rings: Vec<Vec<Point>>, report: &mut RepairReport) -> Vec<Vec<Point>> {
+    let count = rings.len();
+    let rings = rings
+        .into_iter()
+        .map(dedup_ring)
+        .filter(|ring| {
+            ring.len() >= 3
+                && ring
+                    .iter()
+                    .any(|p| det(sub(ring[1], ring[0]), sub(*p, ring[0])) != 0.)
+        })
+        .collect::<Vec<_>>();
+    report.rings_dropped += count - rings.len();
+    rings
+}
+
+/// Snaps points to the first seen point within distance, on a grid of
+/// distance sized cells
+pub(crate) struct Merger {
+    distance: f64,
+    cells: HashMap<(i64, i64), Vec<Point>>,
+    exact: HashMap<(u64, u64), Point>,
+}
+
+impl Merger {
+    pub(crate) fn new(distance: f64) -> Self {
+        Self {
+            distance,
+            cells: HashMap::new(),
+            exact: HashMap::new(),
+        }
+    }
+
+    fn cell(&self, p: Point) -> (i64, i64) {
+        (
+            (p.x / self.distance).floor() as i64,
+            (p.y / self.distance).floor() as i64,
+        )
+    }
+
+    fn find(&self, p: Point) -> Option<Point> {
+        if let Some(found) = self.exact.get(&point_key(p)) {
+            return Some(*found);
+        }
+        if self.distance <= 0. {
+            return None;
+        }
+        let (cx, cy) = self.cell(p);
+        (cx - 1..=cx + 1)
+            .flat_map(|x| (cy - 1..=cy + 1).map(move |y| (x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .find(|q| (q.x - p.x).hypot(q.y - p.y) <= self.distance)
+            .copied()
+    }
+
+    fn merge(&mut self, p: Point, report: &mut RepairReport) -> Point {
+        if let Some(found) = self.find(p) {
+            report.points_merged += 1;
+            return found;
+        }
+        self.insert(p);
+        p
+    }
+
+    /// like merge, but a snapped point is not counted
+    pub(crate) fn snap(&mut self, p: Point) -> Point {
+        self.find(p).unwrap_or_else(|| {
+            self.insert(p);
+            p
+        })
+    }
+
+    fn insert(&mut self, p: Point) {
+        self.exact.insert(point_key(p), p);
+        if self.distance > 0. {
+            let cell = self.cell(p);
+            self.cells.entry(cell).or_default().push(p);
+        }
+    }
+}
+
+fn det(u: Point, v: Point) -> f64 {
+    u.x * v.y - u.y * v.x
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    Point::new(a.x - b.x, a.y - b.y)
+}
+
+/// parameter of `p` along `a -> b` when it lies strictly inside the segment
+fn inside_param(a: Point, b: Point, p: Point) -> Option<f64> {
+    if det(sub(b, a), sub(p, a)) != 0. {
+        return None;
+    }
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let t = ((p.x - a.x) * dx + (p.y - a.y) * dy) / (dx * dx + dy * dy);
+    (t > 0. && t < 1.).then_some(t)
+}
+
+/// Call `f` with each pair of segments, as (ring, index of first point), whose
+/// bounding boxes grown by `margin` overlap, found by sweeping segments sorted by
+/// min x
+fn for_each_close_pair(
+    rings: &[Vec<Point>],
+    margin: f64,
+    mut f: impl FnMut((usize, usize), (Point, Point), (usize, usize), (Point, Point)),
+) {
+    let mut segments = rings
+        .iter()
+        .enumerate()
+        .flat_map(|(r, ring)| (0..ring.len()).map(move |i| (r, i)))
+        .collect::<Vec<_>>();
+    let ends = |&(r, i): &(usize, usize)| {
+        let ring = &rings[r];
+        (ring[i], ring[(i + 1) % ring.len()])
+    };
+    let min_x = |s: &(usize, usize)| {
+        let (a, b) = ends(s);
+        a.x.min(b.x)
+    };
+    segments.sort_by(|l, r| min_x(l).total_cmp(&min_x(r)));
+
+    let mut active = Vec::<(usize, usize)>::new();
+    for s in segments {
+        let (a, b) = ends(&s);
+        let x = a.x.min(b.x);
+        active.retain(|t| {
+            let (c, d) = ends(t);
+            c.x.max(d.x) + margin >= x
+        });
+
+        for &t in active.iter() {
+            let (c, d) = ends(&t);
+            if a.y.max(b.y) + margin < c.y.min(d.y) || c.y.max(d.y) + margin < a.y.min(b.y) {
+                continue;
+            }
+            f(s, (a, b), t, (c, d));
+        }
+        active.push(s);
+    }
+}
+
+/// Pairs of segments which cross or overlap, or where an end of one lies inside
+/// the other. Segments sharing an end only touch
+pub(crate) fn intersecting_segments(rings: &[Vec<Point>]) -> Vec<((usize, usize), (usize, usize))> {
+    let mut pairs = vec![];
+    for_each_close_pair(rings, 0., |s, (a, b), t, (c, d)| {
+        let (r, q) = (sub(b, a), sub(d, c));
+        let denom = det(r, q);
+        let intersects = if denom == 0. {
+            [c, d].iter().any(|o| inside_param(a, b, *o).is_some())
+                || [a, b].iter().any(|o| inside_param(c, d, *o).is_some())
+        } else if [a, b].iter().any(|p| p.eq(&c) || p.eq(&d)) {
+            false
+        } else {
+            let ts = det(sub(c, a), q) / denom;
+            let tt = det(sub(c, a), r) / denom;
+            (0. ..=1.).contains(&ts)
+                && (0. ..=1.).contains(&tt)
+                && ((ts > 0. && ts < 1.) || (tt > 0. && tt < 1.))
+        };
+        if intersects {
+            pairs.push((s.min(t), s.max(t)));
+        }
+    });
+    pairs.sort();
+    pairs
+}
+
+/// Insert intersection points into both edges
+fn split_intersections(
+    rings: Vec<Vec<Point>>,
+    merger: &mut Merger,
+    report: &mut RepairReport,
+) -> Vec<Vec<Point>> {
+    let mut splits = HashMap::<(usize, usize), Vec<(f64, Point)>>::new();
+    for_each_close_pair(&rings, 0., |s, (a, b), t, (c, d)| {
+        let (r, q) = (sub(b, a), sub(d, c));
+        let denom = det(r, q);
+        if denom == 0. {
+            // parallel, when collinear endpoints of one split the other
+            for (seg, (p, q), others) in [(s, (a, b), [c, d]), (t, (c, d), [a, b])] {
+                for o in others {
+                    if let Some(param) = inside_param(p, q, o) {
+                        splits.entry(seg).or_default().push((param, o));
+                    }
+                }
+            }
+            return;
+        }
+
+        // not parallel, they only meet at the shared end, rounding may put the
+        // crossing a bit off it
+        if [a, b].iter().any(|p| p.eq(&c) || p.eq(&d)) {
+            return;
+        }
+        let ts = det(sub(c, a), q) / denom;
+        let tt = det(sub(c, a), r) / denom;
+        if !(0. ..=1.).contains(&ts) || !(0. ..=1.).contains(&tt) {
+            return;
+        }
+        let point = if ts == 0. {
+            a
+        } else if ts == 1. {
+            b
+        } else if tt == 0. {
+            c
+        } else if tt == 1. {
+            d
+        } else {
+            merger.snap(Point::new(a.x + ts * (b.x - a.x), a.y + ts * (b.y - a.y)))
+        };
+        for (seg, (p, q), param) in [(s, (a, b), ts), (t, (c, d), tt)] {
+            if !point.eq(&p) && !point.eq(&q) && param > 0. && param < 1. {
+                splits.entry(seg).or_default().push((param, point));
+            }
+        }
+    });
+
+    insert_splits(rings, splits, &mut report.edges_split)
+}
+
+/// Insert each vertex within `distance` of an edge, not ending at it or at its
+/// ring neighbors, into the edge
+fn close_gaps(rings: Vec<Vec<Point>>, distance: f64, report: &mut RepairReport) -> Vec<Vec<Point>> {
+    let mut splits = HashMap::<(usize, usize), Vec<(f64, Point)>>::new();
+    for_each_close_pair(&rings, distance, |s, (a, b), t, (c, d)| {
+        // adjacent edges meet at a vertex, their other ends are no gap
+        let adjacent = |(r0, i0): (usize, usize), (r1, i1): (usize, usize)| {
+            r0 == r1 && (i0 + 1) % rings[r0].len() == i1
+        };
+        if adjacent(s, t) || adjacent(t, s) {
+            return;
+        }
+        for (seg, (p, q), others) in [(s, (a, b), [c, d]), (t, (c, d), [a, b])] {
+            for o in others {
+                if o.eq(&p) || o.eq(&q) {
+                    continue;
+                }
+                let (dx, dy) = (q.x - p.x, q.y - p.y);
+                let param = ((o.x - p.x) * dx + (o.y - p.y) * dy) / (dx * dx + dy * dy);
+                let gap = det(sub(q, p), sub(o, p)).abs() / dx.hypot(dy);
+                if param > 0. && param < 1. && gap <= distance {
+                    splits.entry(seg).or_default().push((param, o));
+                }
+            }
+        }
+    });
+    insert_splits(rings, splits, &mut report.gaps_closed)
+}
+
+/// Insert points into edges, keyed by (ring, index of first point), in order of
+/// their parameter along the edge. `count` is increased by points inserted.
+fn insert_splits(
+    rings: Vec<Vec<Point>>,
+    mut splits: HashMap<(usize, usize), Vec<(f64, Point)>>,
+    count: &mut usize,
+) -> Vec<Vec<Point>> {
+    rings
+        .iter()
+        .enumerate()
+        .map(|(r, ring)| {
+            let mut result = Vec::with_capacity(ring.len());
+            for (i, p) in ring.iter().enumerate() {
+                result.push(*p);
+                if let Some(mut points) = splits.remove(&(r, i)) {
+                    points.sort_by(|l, r| l.0.total_cmp(&r.0));
+                    points.dedup_by(|l, r| l.1.eq(&r.1));
+                    *count += points.len();
+                    result.extend(points.into_iter().map(|(_, p)| p));
+                }
+            }
+            result
+        })
+        .collect()
+}
+
+/// Build points, equal points across rings share one id
+fn build(rings: Vec<Vec<Point>>, steiner_points: Vec<Point>, report: RepairReport) -> Repaired {
+    let mut report = report;
+    let mut points_builder = PointsBuilder::with_capacity(
+        rings.iter().map(|r| r.len()).sum::<usize>() + steiner_points.len(),
+    );
+    let mut ids = HashMap::<(u64, u64), PointId>::new();
+    let mut id_of = |p: Point, points_builder: &mut PointsBuilder| {
+        *ids.entry(point_key(p))
+            .or_insert_with(|| points_builder.add_steiner_point(p))
+    };
+
+    let rings = rings
+        .into_iter()
+        .map(|ring| {
+            ring.into_iter()
+                .map(|p| id_of(p, &mut points_builder))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    for ring in rings.iter() {
+        for i in 0..ring.len() {
+            let (p1, p2) = (ring[i], ring[(i + 1) % ring.len()]);
+            let edge = Edge::new(
+                (p1, &points_builder.get_point(p1)),
+                (p2, &points_builder.get_point(p2)),
+            );
+            if !points_builder.add_edge(edge) {
+                // overlapping edges cancel out in pairs
+                report.edges_dropped += 2;
+            }
+        }
+    }
+
+    for p in steiner_points {
+        id_of(p, &mut points_builder);
+    }
+
+    Repaired {
+        points_builder,
+        rings,
+        report,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x0: f64, y0: f64, x1: f64, y1: f64) -> Vec<Point> {
+        vec![
+            Point::new(x0, y0),
+            Point::new(x1, y0),
+            Point::new(x1, y1),
+            Point::new(x0, y1),
+        ]
+    }
+
+    #[test]
+    fn test_merge_and_orient() {
+        let mut outer = square(0., 0., 10., 10.);
+        outer.reverse();
+        outer.insert(1, outer[0]);
+        outer.push(outer[0]);
+        let hole = square(4., 4., 6., 6.);
+        let hole_thin = vec![
+            Point::new(4., 4.),
+            Point::new(6., 4.),
+            Point::new(6., 4.001),
+        ];
+
+        let repaired = repair(
+            vec![outer.clone(), hole.clone()],
+            vec![Point::new(1., 1.), Point::new(1., 1.)],
+            &RepairOptions::default(),
+        );
+        assert_eq!(
+            repaired.report,
+            RepairReport {
+                points_merged: 3,
+                rings_reoriented: 2,
+                ..Default::default()
+            }
+        );
+        assert_eq!(repaired.rings.iter().map(|r| r.len()).sum::<usize>(), 8);
+        assert_eq!(repaired.points_builder.build().len() - 2, 9);
+
+        let repaired = repair(
+            vec![square(0., 0., 10., 10.), hole_thin],
+            vec![],
+            &RepairOptions::default().merge_distance(0.01),
+        );
+        // (6, 4.001) merges into (6, 4), the hole degenerates
+        assert_eq!(repaired.report.points_merged, 1);
+        assert_eq!(repaired.report.rings_dropped, 1);
+        assert_eq!(repaired.rings.len(), 1);
+    }
+
+    #[test]
+    fn test_split_intersections() {
+        // bow tie, crosses at (1, 1)
+        let bow_tie = vec![
+            Point::new(0., 0.),
+            Point::new(2., 2.),
+            Point::new(2., 0.),
+            Point::new(0., 2.),
+        ];
+        let repaired = repair(vec![bow_tie], vec![], &RepairOptions::default());
+        assert_eq!(repaired.report.edges_split, 2);
+        assert_eq!(repaired.rings[0].len(), 6);
+        // crossing point shared by both visits
+        assert_eq!(repaired.rings[0][1], repaired.rings[0][4]);
+        assert_eq!(repaired.points_builder.build().len() - 2, 5);
+
+        // hole touching outer's edge at (5, 0)
+        let hole = vec![Point::new(5., 0.), Point::new(6., 2.), Point::new(4., 2.)];
+        let repaired = repair(
+            vec![square(0., 0., 10., 10.), hole],
+            vec![],
+            &RepairOptions::default(),
+        );
+        assert_eq!(repaired.report.edges_split, 1);
+        assert_eq!(repaired.rings[0].len(), 5);
+        assert_eq!(repaired.points_builder.build().len() - 2, 7);
+    }
+
+    #[test]
+    fn test_close_gaps() {
+        // hole nearly touching outer's edge at (5, 0)
+        let hole = vec![
+            Point::new(5., 0.001),
+            Point::new(6., 2.),
+            Point::new(4., 2.),
+        ];
+        let rings = vec![square(0., 0., 10., 10.), hole];
+        let repaired = repair(rings.clone(), vec![], &RepairOptions::default());
+        assert_eq!(repaired.report.gaps_closed, 0);
+        assert_eq!(repaired.rings[0].len(), 4);
+
+        let options = RepairOptions::default().snap_distance(0.01);
+        let repaired = repair(rings, vec![], &options);
+        assert_eq!(repaired.report.gaps_closed, 1);
+        assert_eq!(repaired.report.edges_split, 0);
+        assert_eq!(repaired.rings[0].len(), 5);
+        // the outer edge bends to the hole vertex
+        assert!(repaired.rings[1].contains(&repaired.rings[0][1]));
+        assert_eq!(repaired.points_builder.build().len() - 2, 7);
+
+        // adjacent edges at a sharp vertex are no gap
+        let spike = vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(0., 0.001),
+        ];
+        let repaired = repair(vec![spike], vec![], &options);
+        assert_eq!(repaired.report.gaps_closed, 0);
+    }
+}
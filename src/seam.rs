@@ -0,0 +1,405 @@
+//! Matching borders for polygons triangulated independently, e.g. parcels of a
+//! map layer or terrain patches. A vertex of one polygon lying on a border edge of
+//! its neighbor would otherwise be a T-junction, a crack once the meshes are
+//! rendered side by side.
+//!
+//! Each ring edge gets every registered vertex lying exactly on it, so both sides
+//! of a shared border have the same points. The sweep keeps constrained edges and
+//! adds no points to them, so the seams then triangulate identically.
+//!
+//! Vertices are welded across polygons, ones within
+//! [`SeamRegistry::weld_distance`] of an earlier vertex move onto it, and
+//! [`SeamRegistry::triangulate`] meshes all polygons over one point pool, so a
+//! shared border vertex has the same id in every polygon. A malformed polygon
+//! gets an error entry of its own, the others are meshed anyway.
+//!
+//! When only one side is triangulated here, [`crate::Triangles::edge_splits`]
+//! lists the points its steiner points or other rings put on its edges, for the
+//! neighbor to insert.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{seam::SeamRegistry, Point};
+//!
+//!    let builders = SeamRegistry::new()
+//!        .add_polygon(
+//!            vec![
+//!                Point::new(0., 0.),
+//!                Point::new(10., 0.),
+//!                Point::new(10., 10.),
+//!                Point::new(0., 10.),
+//!            ],
+//!            vec![],
+//!        )
+//!        // its left border has a point in the middle of the first square's edge
+//!        .add_polygon(
+//!            vec![
+//!                Point::new(10., 0.),
+//!                Point::new(20., 0.),
+//!                Point::new(20., 10.),
+//!                Point::new(10., 10.),
+//!                Point::new(10., 5.),
+//!            ],
+//!            vec![],
+//!        )
+//!        .builders();
+//!    let counts = builders
+//!        .into_iter()
+//!        .map(|builder| builder.build().triangulate().count())
+//!        .collect::<Vec<_>>();
+//!    assert_eq!(counts, [3, 3]);
+//! ```
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::repair::Merger;
+use crate::tiling::Polygon;
+use crate::utils::{dedup_ring, orient, point_key};
+use crate::{InputReport, Point, SweeperBuilder};
+
+/// Polygons sharing borders, see the [module docs](self)
+#[derive(Debug, Clone, Default)]
+pub struct SeamRegistry {
+    polygons: Vec<Polygon>,
+    weld_distance: f64,
+    /// (min, max), see [`Self::bbox`]
+    bbox: Option<(Point, Point)>,
+}
+
+/// What [`SeamRegistry::triangulate`] did with one polygon
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeatureMesh {
+    /// its triangles are `triangles[range]` of the [`WeldedMesh`]
+    Meshed(Range<usize>),
+    /// outside [`SeamRegistry::bbox`]
+    Skipped,
+    /// rejected by [`SweeperBuilder::try_build`]
+    Invalid(InputReport),
+}
+
+/// Meshes of all registered polygons over one point pool, see
+/// [`SeamRegistry::triangulate`]
+#[derive(Debug, Clone, Default)]
+pub struct WeldedMesh {
+    pub points: Vec<Point>,
+    pub triangles: Vec<[usize; 3]>,
+    /// outcome of each polygon, in the order registered
+    pub features: Vec<FeatureMesh>,
+}
+
+impl SeamRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a polygon with holes, results keep the order of registration
+    pub fn add_polygon(mut self, outer: Vec<Point>, holes: Vec<Vec<Point>>) -> Self {
+        self.polygons.push(Polygon { outer, holes });
+        self
+    }
+
+    /// Register multiple polygons
+    pub fn add_polygons(mut self, polygons: impl IntoIterator<Item = Polygon>) -> Self {
+        self.polygons.extend(polygons);
+        self
+    }
+
+    /// Weld vertices within `distance` of an earlier registered vertex onto it,
+    /// e.g. borders digitized separately for each feature. `0.` welds only exact
+    /// duplicates, the default
+    pub fn weld_distance(mut self, distance: f64) -> Self {
+        self.weld_distance = distance;
+        self
+    }
+
+    /// Skip polygons whose outer polyline's bounding box misses `[min, max]`
+    /// in [`Self::triangulate`], like a GeoJSON bbox filter. Skipped polygons
+    /// are still welded and conformed with, so seams match between runs over
+    /// neighboring boxes.
+    pub fn bbox(mut self, min: Point, max: Point) -> Self {
+        self.bbox = Some((min, max));
+        self
+    }
+
+    /// Registered polygons with vertices welded, and every vertex lying on a
+    /// ring edge inserted into it, in order along the edge
+    pub fn conformed(&self) -> Vec<Polygon> {
+        let polygons = self.welded();
+        let index = VertexIndex::new(
+            polygons
+                .iter()
+                .flat_map(|polygon| polygon.outer.iter().chain(polygon.holes.iter().flatten()))
+                .copied()
+                .collect(),
+        );
+        polygons
+            .iter()
+            .map(|polygon| Polygon {
+                outer: index.conform(&polygon.outer),
+                holes: polygon
+                    .holes
+                    .iter()
+                    .map(|hole| index.conform(hole))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Builder for each registered polygon, with conformed rings
+    pub fn builders(&self) -> Vec<SweeperBuilder> {
+        self.conformed()
+            .into_iter()
+            .map(|polygon| SweeperBuilder::new(polygon.outer).add_holes(polygon.holes))
+            .collect()
+    }
+
+    /// Triangulate every registered polygon, vertices at the same position share
+    /// one id across polygons. Ids are in order of first use, polygon by polygon.
+    /// Input of each polygon is validated, a malformed one is left out with its
+    /// problems and doesn't stop the rest.
+    pub fn triangulate(&self) -> WeldedMesh {
+        let mut mesh = WeldedMesh::default();
+        let mut ids = HashMap::<(u64, u64), usize>::new();
+        for (polygon, builder) in self.polygons.iter().zip(self.builders()) {
+            if !self.in_bbox(&polygon.outer) {
+                mesh.features.push(FeatureMesh::Skipped);
+                continue;
+            }
+            let triangles = match builder.try_build() {
+                Ok(sweeper) => sweeper.triangulate(),
+                Err(report) => {
+                    mesh.features.push(FeatureMesh::Invalid(report));
+                    continue;
+                }
+            };
+            let mut indices = vec![0; triangles.index_count()];
+            triangles.write_indices_into(&mut indices);
+
+            let start = mesh.triangles.len();
+            for t in indices.chunks_exact(3) {
+                let triangle = [t[0], t[1], t[2]].map(|index| {
+                    let point = triangles.vertex(index as usize);
+                    *ids.entry(point_key(point)).or_insert_with(|| {
+                        mesh.points.push(point);
+                        mesh.points.len() - 1
+                    })
+                });
+                mesh.triangles.push(triangle);
+            }
+            mesh.features
+                .push(FeatureMesh::Meshed(start..mesh.triangles.len()));
+        }
+        mesh
+    }
+
+    fn in_bbox(&self, outer: &[Point]) -> bool {
+        let Some((min, max)) = self.bbox else {
+            return true;
+        };
+        let (mut lo, mut hi) = (
+            Point::new(f64::MAX, f64::MAX),
+            Point::new(f64::MIN, f64::MIN),
+        );
+        for p in outer {
+            lo = Point::new(lo.x.min(p.x), lo.y.min(p.y));
+            hi = Point::new(hi.x.max(p.x), hi.y.max(p.y));
+        }
+        lo.x <= max.x && lo.y <= max.y && hi.x >= min.x && hi.y >= min.y
+    }
+
+    /// Registered polygons with vertices welded, in order of registration
+    fn welded(&self) -> Vec<Polygon> {
+        let mut merger = Merger::new(self.weld_distance);
+        let mut weld = |ring: &[Point]| dedup_ring(ring.iter().map(|p| merger.snap(*p)).collect());
+        self.polygons
+            .iter()
+            .map(|polygon| Polygon {
+                outer: weld(&polygon.outer),
+                holes: polygon.holes.iter().map(|hole| weld(hole)).collect(),
+            })
+            .collect()
+    }
+}
+
+/// Ring vertices sorted by x then y, for range lookup along an edge
+struct VertexIndex {
+    vertices: Vec<Point>,
+}
+
+impl VertexIndex {
+    fn new(mut vertices: Vec<Point>) -> Self {
+        vertices.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        vertices.dedup_by(|a, b| a.eq(b));
+        Self { vertices }
+    }
+
+    fn conform(&self, ring: &[Point]) -> Vec<Point> {
+        let mut conformed = Vec::with_capacity(ring.len());
+        for (i, a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            conformed.push(*a);
+            conformed.extend(self.on_edge(*a, b));
+        }
+        conformed
+    }
+
+    /// Vertices strictly inside segment a -> b, ordered from a
+    fn on_edge(&self, a: Point, b: Point) -> Vec<Point> {
+        let (x0, x1) = (a.x.min(b.x), a.x.max(b.x));
+        let (y0, y1) = (a.y.min(b.y), a.y.max(b.y));
+        let start = self.vertices.partition_point(|p| p.x < x0);
+        let mut found = self.vertices[start..]
+            .iter()
+            .take_while(|p| p.x <= x1)
+            .filter(|p| p.y >= y0 && p.y <= y1 && !p.eq(&a) && !p.eq(&b) && orient(a, b, **p) == 0.)
+            .copied()
+            .collect::<Vec<_>>();
+        let along = |p: &Point| (p.x - a.x) * (b.x - a.x) + (p.y - a.y) * (b.y - a.y);
+        found.sort_by(|p, q| along(p).total_cmp(&along(q)));
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InputError;
+
+    /// mesh edges of `builder` on the line x = `x`, as (y0, y1)
+    fn seam_edges(builder: SweeperBuilder, x: f64) -> Vec<(f64, f64)> {
+        let mut edges = builder
+            .build()
+            .triangulate()
+            .flat_map(|t| {
+                (0..3)
+                    .map(|i| (t.points[i], t.points[(i + 1) % 3]))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|(p, q)| p.x == x && q.x == x)
+            .map(|(p, q)| (p.y.min(q.y), p.y.max(q.y)))
+            .collect::<Vec<_>>();
+        edges.sort_by(|l, r| l.partial_cmp(r).unwrap());
+        edges
+    }
+
+    #[test]
+    fn test_seam() {
+        let left = Polygon {
+            outer: vec![
+                Point::new(0., 0.),
+                Point::new(10., 0.),
+                Point::new(10., 10.),
+                Point::new(0., 10.),
+            ],
+            holes: vec![vec![
+                Point::new(4., 2.),
+                Point::new(4., 8.),
+                Point::new(6., 8.),
+                Point::new(6., 2.),
+            ]],
+        };
+        let right = Polygon {
+            outer: vec![
+                Point::new(10., 0.),
+                Point::new(20., 0.),
+                Point::new(20., 10.),
+                Point::new(10., 10.),
+                Point::new(10., 7.),
+                Point::new(10., 3.),
+            ],
+            holes: vec![],
+        };
+        let registry = SeamRegistry::new().add_polygons([left.clone(), right.clone()]);
+
+        // independently, the seam of left is one edge, right's is 3
+        let left_builder = SweeperBuilder::new(left.outer).add_holes(left.holes);
+        assert_eq!(seam_edges(left_builder, 10.), [(0., 10.)]);
+
+        let [left, right] = <[_; 2]>::try_from(registry.builders()).ok().unwrap();
+        let seam = seam_edges(right, 10.);
+        assert_eq!(seam, [(0., 3.), (3., 7.), (7., 10.)]);
+        assert_eq!(seam_edges(left, 10.), seam);
+
+        let conformed = registry.conformed();
+        assert_eq!(conformed[0].outer.len(), 6);
+        assert_eq!(conformed[0].outer[2].y, 3.);
+        assert_eq!(conformed[1].outer.len(), 6);
+    }
+
+    #[test]
+    fn test_weld() {
+        let square = |x: f64, drift: f64| {
+            vec![
+                Point::new(x + drift, 0.),
+                Point::new(x + 10., 0.),
+                Point::new(x + 10., 10.),
+                Point::new(x - drift, 10.),
+            ]
+        };
+        // right's left border is digitized slightly off the shared one
+        let registry = SeamRegistry::new()
+            .add_polygon(square(0., 0.), vec![])
+            .add_polygon(square(10., 1e-9), vec![]);
+
+        let mesh = registry.triangulate();
+        assert_eq!(mesh.points.len(), 8);
+
+        let mesh = registry.weld_distance(1e-6).triangulate();
+        assert_eq!(mesh.points.len(), 6);
+        assert_eq!(
+            mesh.features,
+            [FeatureMesh::Meshed(0..2), FeatureMesh::Meshed(2..4)]
+        );
+        // both sides of the seam use the same ids
+        let on_seam = |feature: usize| {
+            let FeatureMesh::Meshed(range) = mesh.features[feature].clone() else {
+                unreachable!()
+            };
+            let mut ids = mesh.triangles[range]
+                .iter()
+                .flatten()
+                .filter(|id| mesh.points[**id].x == 10.)
+                .copied()
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids.dedup();
+            ids
+        };
+        assert_eq!(on_seam(0).len(), 2);
+        assert_eq!(on_seam(1), on_seam(0));
+    }
+
+    #[test]
+    fn test_feature_isolation() {
+        let square = |x: f64| {
+            vec![
+                Point::new(x, 0.),
+                Point::new(x + 10., 0.),
+                Point::new(x + 10., 10.),
+                Point::new(x, 10.),
+            ]
+        };
+        let bowtie = vec![
+            Point::new(20., 0.),
+            Point::new(30., 10.),
+            Point::new(30., 0.),
+            Point::new(20., 10.),
+        ];
+        let mesh = SeamRegistry::new()
+            .add_polygon(square(0.), vec![])
+            .add_polygon(bowtie, vec![])
+            .add_polygon(square(100.), vec![])
+            .add_polygon(square(10.), vec![])
+            .bbox(Point::new(-5., -5.), Point::new(50., 50.))
+            .triangulate();
+
+        assert_eq!(mesh.features[0], FeatureMesh::Meshed(0..2));
+        let FeatureMesh::Invalid(report) = &mesh.features[1] else {
+            panic!("{:?}", mesh.features[1]);
+        };
+        assert!(matches!(report.problems[0], InputError::Intersections(_)));
+        assert_eq!(mesh.features[2], FeatureMesh::Skipped);
+        assert_eq!(mesh.features[3], FeatureMesh::Meshed(2..4));
+        assert_eq!(mesh.points.len(), 6);
+    }
+}
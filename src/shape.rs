@@ -21,7 +21,7 @@ impl Edge {
                 q = p1_id;
                 p = p2_id;
             } else if p1.x == p2.x {
-                assert!(false, "repeat points");
+                panic!("repeat points");
             }
         }
 
@@ -42,37 +42,47 @@ impl Default for Point {
 }
 
 impl Point {
-    pub fn new(x: f64, y: f64) -> Self {
+    pub const fn new(x: f64, y: f64) -> Self {
         Self { x, y }
     }
 
     /// whether two points are same.
     /// Note: the lib don't support duplicate point, so eq means they are same point
     ///    not two point with equal values
+    #[allow(clippy::should_implement_trait)]
     pub fn eq(&self, other: &Self) -> bool {
         self.x == other.x && self.y == other.y
     }
 }
 
+/// Constrained and delaunay flags of an edge, and the legalize tick the delaunay
+/// flag was set at, see [`crate::DelaunayFlag::Tick`]
 #[derive(Default, Clone, Copy)]
-pub struct EdgeAttr(u8);
+pub struct EdgeAttr(u16);
 
 impl std::fmt::Debug for EdgeAttr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("EdgeAttr")
             .field("constrained", &self.is_constrained())
             .field("delaunay", &self.is_delaunay())
+            .field("tick", &self.tick())
             .finish()
     }
 }
 
 impl EdgeAttr {
-    const CONSTRAINED: u8 = 1;
-    const CONSTRAINED_UNSET: u8 = Self::ALL ^ Self::CONSTRAINED;
-    const DELAUNAY: u8 = 1 << 1;
-    const DELAUNAY_UNSET: u8 = Self::ALL ^ Self::DELAUNAY;
+    const CONSTRAINED: u16 = 1;
+    const CONSTRAINED_UNSET: u16 = Self::ALL ^ Self::CONSTRAINED;
+    const DELAUNAY: u16 = 1 << 1;
+    const DELAUNAY_UNSET: u16 = Self::ALL ^ Self::DELAUNAY;
+    const FLAGS: u16 = Self::CONSTRAINED | Self::DELAUNAY;
 
-    const ALL: u8 = 0xFF;
+    const ALL: u16 = 0xFFFF;
+
+    /// bits of tick kept, the rest of the attr
+    pub(crate) const TICK_BITS: u32 = 14;
+    pub(crate) const TICK_MASK: u32 = (1 << Self::TICK_BITS) - 1;
+    const TICK_SHIFT: u32 = 2;
 
     fn set_constrained(&mut self, val: bool) {
         if !val {
@@ -97,6 +107,21 @@ impl EdgeAttr {
     fn is_delaunay(&self) -> bool {
         self.0 & Self::DELAUNAY != 0
     }
+
+    /// set delaunay flag, stamped with `tick`, which is wrapped to [`Self::TICK_BITS`]
+    fn set_delaunay_at(&mut self, tick: u32) {
+        let tick = (tick & Self::TICK_MASK) as u16;
+        self.0 = (self.0 & Self::FLAGS) | Self::DELAUNAY | (tick << Self::TICK_SHIFT);
+    }
+
+    fn tick(&self) -> u32 {
+        (self.0 >> Self::TICK_SHIFT) as u32
+    }
+
+    /// whether delaunay flag is set at `tick`, flags of other ticks are stale
+    fn is_delaunay_at(&self, tick: u32) -> bool {
+        self.is_delaunay() && self.tick() == tick & Self::TICK_MASK
+    }
 }
 
 /// The triangle struct used internally.
@@ -195,6 +220,15 @@ impl InnerTriangle {
         self.edge_attrs[edge_index].is_delaunay()
     }
 
+    #[inline(always)]
+    pub(crate) fn set_delaunay_at(&mut self, edge_index: usize, tick: u32) {
+        self.edge_attrs[edge_index].set_delaunay_at(tick);
+    }
+
+    pub(crate) fn is_delaunay_at(&self, edge_index: usize, tick: u32) -> bool {
+        self.edge_attrs[edge_index].is_delaunay_at(tick)
+    }
+
     pub fn edge_attr_ccw(&self, p: PointId) -> EdgeAttr {
         if p == self.points[0] {
             self.edge_attrs[2]
@@ -482,6 +516,18 @@ mod tests {
         assert!(attr.is_delaunay());
         attr.set_delaunay(false);
         assert!(!attr.is_delaunay());
+
+        attr.set_constrained(true);
+        attr.set_delaunay_at(5);
+        assert!(attr.is_delaunay_at(5));
+        assert!(!attr.is_delaunay_at(6));
+        assert!(attr.is_constrained());
+        // tick wraps
+        attr.set_delaunay_at((1 << EdgeAttr::TICK_BITS) + 6);
+        assert!(attr.is_delaunay_at(6));
+        assert!(attr.is_constrained() && attr.is_delaunay());
+        attr.set_delaunay(false);
+        assert!(!attr.is_delaunay_at(6));
     }
 
     #[test]
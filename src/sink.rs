@@ -0,0 +1,18 @@
+use crate::Triangle;
+
+/// Triangle handed to a [`TriangleSink`]
+pub type OutputTriangle = Triangle;
+
+/// Receives result triangles one by one, so they can be written to a GPU staging
+/// buffer, file or network stream without collecting the whole result first.
+///
+/// Any `FnMut(&OutputTriangle)` closure is a sink.
+pub trait TriangleSink {
+    fn emit(&mut self, tri: &OutputTriangle);
+}
+
+impl<F: FnMut(&OutputTriangle)> TriangleSink for F {
+    fn emit(&mut self, tri: &OutputTriangle) {
+        self(tri)
+    }
+}
@@ -0,0 +1,693 @@
+//! Out-of-core triangulation for point sets larger than memory, e.g. LIDAR scans.
+//!
+//! Points are sorted externally: input is cut into chunks, each chunk is sorted and
+//! spilled to a temp file, then runs are merged while sweeping in y order. The
+//! points are wrapped by a bounding rect, and the delaunay triangulation inside
+//! the rect is produced.
+//!
+//! A triangle is finished once its circumcircle lies below the advancing front,
+//! no later point nor fill can flip it. Finished triangles are handed to the sink
+//! and dropped, so only the advancing front and the band of unfinished triangles
+//! below it stay in memory.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{stream::StreamBuilder, Point, Triangle};
+//!
+//!    let points = (0..1000).map(|i| Point::new((i % 37) as f64, (i / 37) as f64 + (i % 7) as f64 * 0.1));
+//!    let mut triangles = vec![];
+//!    let stats = StreamBuilder::new()
+//!        .chunk_size(256)
+//!        .triangulate_into(points, &mut |triangle: &Triangle| triangles.push(*triangle))
+//!        .unwrap();
+//!    assert_eq!(stats.triangles, triangles.len());
+//!    assert_eq!(stats.runs, 4);
+//! ```
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+use crate::advancing_front::AdvancingFront;
+use crate::points::{Points, PointsBuilder};
+use crate::shape::InnerTriangle;
+use crate::sweeper::parse_polyline;
+use crate::triangles::{TriangleId, TriangleStore};
+use crate::{Context, Point, PointId, SweepConfig, Sweeper, Triangle, TriangleSink};
+
+/// sequence to keep temp file names unique within the process
+static RUN_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(thiserror::Error, Debug)]
+pub enum StreamError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("point {0:?} is not finite")]
+    NotFinite(Point),
+    #[error("point {0:?} is not inside bounds")]
+    OutOfBounds(Point),
+}
+
+/// Summary of a streaming triangulation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreamStats {
+    /// distinct points swept, duplicated ones are merged
+    pub points: usize,
+    /// triangles emitted to sink
+    pub triangles: usize,
+    /// sorted runs, runs other than the last one are spilled to disk
+    pub runs: usize,
+    /// max triangles held in memory at once
+    pub peak_triangles: usize,
+}
+
+/// Builder for a streaming triangulation
+pub struct StreamBuilder {
+    chunk_size: usize,
+    batch_size: usize,
+    temp_dir: PathBuf,
+    bounds: Option<(Point, Point)>,
+    config: SweepConfig,
+}
+
+impl Default for StreamBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamBuilder {
+    /// Builder with chunks of 2^20 points, 16 MiB sorted in memory at once, runs
+    /// spilled to [`std::env::temp_dir`], eviction batches of at least 2^12
+    /// points, bounds from the points and the default [`SweepConfig`]
+    pub fn new() -> Self {
+        Self {
+            chunk_size: 1 << 20,
+            batch_size: 1 << 12,
+            temp_dir: std::env::temp_dir(),
+            bounds: None,
+            config: SweepConfig::default(),
+        }
+    }
+
+    /// Points sorted in memory at once, each chunk becomes one run on disk
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Min points swept between two evictions, the batch grows with the band so
+    /// eviction cost stays linear
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Directory for the sorted runs, defaults to [`std::env::temp_dir`]
+    pub fn temp_dir(mut self, temp_dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = temp_dir.into();
+        self
+    }
+
+    /// Rect wrapping the points, every point should be strictly inside. Defaults
+    /// to points' bounding box with a margin.
+    pub fn bounds(mut self, min: Point, max: Point) -> Self {
+        self.bounds = Some((min, max));
+        self
+    }
+
+    /// Advancing front backend and capacity, triangle capacity is not used
+    pub fn with_config(mut self, config: SweepConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sort and sweep `points`, finished triangles are emitted to `sink` in no
+    /// particular order
+    pub fn triangulate_into(
+        self,
+        points: impl IntoIterator<Item = Point>,
+        sink: &mut impl TriangleSink,
+    ) -> Result<StreamStats, StreamError> {
+        let runs = SortedRuns::sort(points, self.chunk_size, &self.temp_dir)?;
+        let Some((min, max)) = self.bounds.or(runs.bounds) else {
+            return Ok(StreamStats::default());
+        };
+        let (min, max) = match self.bounds {
+            Some(_) => (min, max),
+            None => {
+                let margin = ((max.x - min.x) + (max.y - min.y)) * 0.01 + 1.;
+                (
+                    Point::new(min.x - margin, min.y - margin),
+                    Point::new(max.x + margin, max.y + margin),
+                )
+            }
+        };
+
+        let run_count = runs.len();
+        let mut stats =
+            StreamSweep::new(min, max, &self.config).sweep(runs.merge()?, self.batch_size, sink)?;
+        stats.runs = run_count;
+        Ok(stats)
+    }
+}
+
+/// sweep order, y first then x
+fn cmp_point(a: &Point, b: &Point) -> Ordering {
+    a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+}
+
+/// Input cut into sorted runs, all but the last one are spilled to disk.
+/// Spilled files are removed on drop.
+struct SortedRuns {
+    files: Vec<(PathBuf, usize)>,
+    last: Vec<Point>,
+    bounds: Option<(Point, Point)>,
+}
+
+impl SortedRuns {
+    fn sort(
+        points: impl IntoIterator<Item = Point>,
+        chunk_size: usize,
+        temp_dir: &std::path::Path,
+    ) -> Result<Self, StreamError> {
+        let mut runs = Self {
+            files: vec![],
+            last: Vec::with_capacity(chunk_size.min(1 << 20)),
+            bounds: None,
+        };
+
+        for point in points {
+            if !point.x.is_finite() || !point.y.is_finite() {
+                return Err(StreamError::NotFinite(point));
+            }
+            // -0. and 0. should be the same point
+            let point = Point::new(point.x + 0., point.y + 0.);
+            runs.bounds = Some(match runs.bounds {
+                None => (point, point),
+                Some((min, max)) => (
+                    Point::new(min.x.min(point.x), min.y.min(point.y)),
+                    Point::new(max.x.max(point.x), max.y.max(point.y)),
+                ),
+            });
+
+            if runs.last.len() == chunk_size {
+                runs.spill(temp_dir)?;
+            }
+            runs.last.push(point);
+        }
+        runs.last.sort_unstable_by(cmp_point);
+        Ok(runs)
+    }
+
+    fn spill(&mut self, temp_dir: &std::path::Path) -> std::io::Result<()> {
+        self.last.sort_unstable_by(cmp_point);
+        let path = temp_dir.join(format!(
+            "p2t-{}-{}.run",
+            std::process::id(),
+            RUN_SEQ.fetch_add(1, AtomicOrdering::Relaxed)
+        ));
+        // registered before writing, so a failed write still gets cleaned up
+        self.files.push((path.clone(), self.last.len()));
+
+        let mut writer = BufWriter::new(File::create(&path)?);
+        for point in self.last.drain(..) {
+            writer.write_all(&point.x.to_le_bytes())?;
+            writer.write_all(&point.y.to_le_bytes())?;
+        }
+        writer.flush()
+    }
+
+    fn len(&self) -> usize {
+        self.files.len() + usize::from(!self.last.is_empty())
+    }
+
+    /// merge all runs, duplicated points are yielded once
+    fn merge(mut self) -> std::io::Result<Merge> {
+        let mut runs = vec![];
+        for (path, len) in self.files.iter() {
+            runs.push(Run::File {
+                reader: BufReader::new(File::open(path)?),
+                remaining: *len,
+            });
+        }
+        runs.push(Run::Memory(std::mem::take(&mut self.last).into_iter()));
+
+        let mut heap = BinaryHeap::with_capacity(runs.len());
+        for (index, run) in runs.iter_mut().enumerate() {
+            if let Some(point) = run.next()? {
+                heap.push(Reverse(RunHead { point, index }));
+            }
+        }
+
+        Ok(Merge {
+            _runs: self,
+            runs,
+            heap,
+            last: None,
+        })
+    }
+}
+
+impl Drop for SortedRuns {
+    fn drop(&mut self) {
+        for (path, _) in self.files.iter() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+enum Run {
+    File {
+        reader: BufReader<File>,
+        remaining: usize,
+    },
+    Memory(std::vec::IntoIter<Point>),
+}
+
+impl Run {
+    fn next(&mut self) -> std::io::Result<Option<Point>> {
+        match self {
+            Run::File { remaining: 0, .. } => Ok(None),
+            Run::File { reader, remaining } => {
+                *remaining -= 1;
+                let mut buf = [0u8; 16];
+                reader.read_exact(&mut buf)?;
+                let (x, y) = buf.split_at(8);
+                Ok(Some(Point::new(
+                    f64::from_le_bytes(x.try_into().unwrap()),
+                    f64::from_le_bytes(y.try_into().unwrap()),
+                )))
+            }
+            Run::Memory(iter) => Ok(iter.next()),
+        }
+    }
+}
+
+/// next point of a run, ordered by sweep order then run index
+struct RunHead {
+    point: Point,
+    index: usize,
+}
+
+impl PartialEq for RunHead {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RunHead {}
+
+impl PartialOrd for RunHead {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RunHead {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_point(&self.point, &other.point).then(self.index.cmp(&other.index))
+    }
+}
+
+/// k-way merge over sorted runs
+struct Merge {
+    /// keeps spilled files alive till merge done
+    _runs: SortedRuns,
+    runs: Vec<Run>,
+    heap: BinaryHeap<Reverse<RunHead>>,
+    last: Option<Point>,
+}
+
+impl Merge {
+    fn next(&mut self) -> std::io::Result<Option<Point>> {
+        while let Some(Reverse(RunHead { point, index })) = self.heap.pop() {
+            if let Some(next) = self.runs[index].next()? {
+                self.heap.push(Reverse(RunHead { point: next, index }));
+            }
+            if !self.last.is_some_and(|last| last.eq(&point)) {
+                self.last = Some(point);
+                return Ok(Some(point));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Sweep state, keeps the front and unfinished triangles only
+struct StreamSweep {
+    points: Points,
+    triangles: TriangleStore,
+    front: AdvancingFront,
+    min: Point,
+    max: Point,
+    /// rect corners by y order, the bottom two are swept at start
+    top_corners: [PointId; 2],
+    /// corners, head and tail, they are never dropped and keep their ids
+    pinned: usize,
+}
+
+impl StreamSweep {
+    fn new(min: Point, max: Point, config: &SweepConfig) -> Self {
+        let mut builder = PointsBuilder::with_capacity(4);
+        parse_polyline(
+            vec![min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)],
+            &mut builder,
+        );
+        let points = builder.build();
+        let corners = points
+            .iter_point_by_y(0)
+            .map(|(point_id, _, _)| point_id)
+            .collect::<Vec<_>>();
+
+        let mut triangles = TriangleStore::new();
+        let initial_triangle =
+            triangles.insert(InnerTriangle::new(corners[0], points.head, points.tail));
+        let mut front = AdvancingFront::with_backend(
+            triangles.get(initial_triangle).unwrap(),
+            initial_triangle,
+            &points,
+            config.front_backend,
+            config.front_capacity,
+        );
+
+        {
+            let mut context = Context::new(&points, &mut triangles, &mut front);
+            Self::sweep_corner(corners[1], &mut context);
+        }
+
+        Self {
+            pinned: points.len(),
+            points,
+            triangles,
+            front,
+            min,
+            max,
+            top_corners: [corners[2], corners[3]],
+        }
+    }
+
+    fn sweep_corner(point_id: PointId, context: &mut Context) {
+        let (_, point, edges) = context
+            .points
+            .iter()
+            .nth(point_id.as_usize())
+            .expect("corner exists");
+        Sweeper::sweep_point(point_id, *point, edges, context, &mut ());
+    }
+
+    fn sweep(
+        mut self,
+        mut merge: Merge,
+        batch_size: usize,
+        sink: &mut impl TriangleSink,
+    ) -> Result<StreamStats, StreamError> {
+        let mut stats = StreamStats::default();
+
+        loop {
+            // batch grows with the band, so each eviction pays for itself
+            let batch_len = batch_size.max(self.triangles.len() / 2);
+            let first = self.points.len();
+            while self.points.len() - first < batch_len {
+                let Some(point) = merge.next()? else {
+                    break;
+                };
+                if point.x <= self.min.x
+                    || point.x >= self.max.x
+                    || point.y <= self.min.y
+                    || point.y >= self.max.y
+                {
+                    return Err(StreamError::OutOfBounds(point));
+                }
+                self.points.push(point);
+            }
+            if self.points.len() == first {
+                break;
+            }
+            stats.points += self.points.len() - first;
+
+            let mut context = Context::new(&self.points, &mut self.triangles, &mut self.front);
+            for index in first..context.points.len() {
+                let point_id = PointId::from_usize(index);
+                let point = point_id.get(context.points);
+                Sweeper::sweep_point(
+                    point_id,
+                    point,
+                    crate::points::PointEdges::None,
+                    &mut context,
+                    &mut (),
+                );
+            }
+
+            stats.peak_triangles = stats.peak_triangles.max(self.triangles.len());
+            stats.triangles += self.evict(sink);
+        }
+
+        {
+            let mut context = Context::new(&self.points, &mut self.triangles, &mut self.front);
+            for point_id in self.top_corners {
+                Self::sweep_corner(point_id, &mut context);
+            }
+        }
+        stats.peak_triangles = stats.peak_triangles.max(self.triangles.len());
+
+        // everything left is finished, the ones outside rect all have head or tail
+        let (head, tail) = (self.points.head, self.points.tail);
+        for (_, triangle) in self.triangles.iter() {
+            if !triangle.interior && !triangle.points.iter().any(|p| *p == head || *p == tail) {
+                sink.emit(&self.to_triangle(triangle));
+                stats.triangles += 1;
+            }
+        }
+
+        Ok(stats)
+    }
+
+    fn to_triangle(&self, triangle: &InnerTriangle) -> Triangle {
+        Triangle {
+            points: triangle.points.map(|p| p.get(&self.points)),
+        }
+    }
+
+    /// Emit finished triangles, then drop the ones not needed anymore and renumber
+    /// the rest. Finished triangles next to unfinished ones are kept, as later flips
+    /// update their neighbor. Returns number of emitted triangles.
+    fn evict(&mut self, sink: &mut impl TriangleSink) -> usize {
+        let (xs, ys): (Vec<f64>, Vec<f64>) = self
+            .front
+            .iter()
+            .map(|node| (node.point().x, node.point().y))
+            .unzip();
+        let front_min = MinTable::new(ys);
+
+        let (head, tail) = (self.points.head, self.points.tail);
+        let finished = self
+            .triangles
+            .iter()
+            .map(|(_, triangle)| {
+                if triangle.points.iter().any(|p| *p == head || *p == tail) {
+                    return false;
+                }
+                let [a, b, c] = triangle.points.map(|p| p.get(&self.points));
+                let Some((center, radius)) = circumcircle(a, b, c) else {
+                    return false;
+                };
+                // front is above the circle on the circle's x span, every point
+                // inside has been swept and they can't flip it anymore
+                let lo = xs
+                    .partition_point(|x| *x <= center.x - radius)
+                    .saturating_sub(1);
+                let hi = xs
+                    .partition_point(|x| *x < center.x + radius)
+                    .min(xs.len() - 1);
+                front_min.min(lo, hi) > center.y + radius * (1. + 1e-9)
+            })
+            .collect::<Vec<_>>();
+
+        let mut emitted = 0;
+        for (index, (_, triangle)) in self.triangles.iter().enumerate() {
+            if finished[index] && !triangle.interior {
+                sink.emit(&self.to_triangle(triangle));
+                emitted += 1;
+            }
+        }
+
+        let keep = self
+            .triangles
+            .iter()
+            .enumerate()
+            .map(|(index, (_, triangle))| {
+                !finished[index]
+                    || triangle
+                        .neighbors
+                        .iter()
+                        .any(|n| !n.invalid() && !finished[n.as_usize()])
+            })
+            .collect::<Vec<_>>();
+        for (index, triangle) in self.triangles.iter_mut().enumerate() {
+            triangle.interior |= finished[index];
+        }
+        let triangle_remap = self.triangles.retain(&keep);
+
+        let mut keep = vec![false; self.points.len()];
+        keep[..self.pinned].iter_mut().for_each(|k| *k = true);
+        for (_, triangle) in self.triangles.iter() {
+            for p in triangle.points {
+                keep[p.as_usize()] = true;
+            }
+        }
+        for node in self.front.iter() {
+            keep[node.point_id().as_usize()] = true;
+        }
+        let point_remap = self.points.retain(&keep);
+        let map_point = |p: PointId| point_remap[p.as_usize()].unwrap();
+
+        for triangle in self.triangles.iter_mut() {
+            triangle.points = triangle.points.map(map_point);
+        }
+        self.front
+            .remap(map_point, |t: TriangleId| triangle_remap[t.as_usize()]);
+
+        emitted
+    }
+}
+
+/// Circumcenter and radius, `None` for collinear points
+fn circumcircle(a: Point, b: Point, c: Point) -> Option<(Point, f64)> {
+    let (bx, by) = (b.x - a.x, b.y - a.y);
+    let (cx, cy) = (c.x - a.x, c.y - a.y);
+    let d = 2. * (bx * cy - by * cx);
+    if d == 0. {
+        return None;
+    }
+    let b_len = bx * bx + by * by;
+    let c_len = cx * cx + cy * cy;
+    let ux = (cy * b_len - by * c_len) / d;
+    let uy = (bx * c_len - cx * b_len) / d;
+    let radius = (ux * ux + uy * uy).sqrt();
+    radius
+        .is_finite()
+        .then(|| (Point::new(a.x + ux, a.y + uy), radius))
+}
+
+/// Sparse table answering range min in O(1)
+struct MinTable {
+    levels: Vec<Vec<f64>>,
+}
+
+impl MinTable {
+    fn new(values: Vec<f64>) -> Self {
+        let mut levels = vec![values];
+        let mut width = 1;
+        while width * 2 <= levels[0].len() {
+            let last = levels.last().unwrap();
+            let level = (0..last.len() - width)
+                .map(|i| last[i].min(last[i + width]))
+                .collect();
+            levels.push(level);
+            width *= 2;
+        }
+        Self { levels }
+    }
+
+    /// min of values in `lo..=hi`
+    fn min(&self, lo: usize, hi: usize) -> f64 {
+        let level = (hi - lo + 1).ilog2() as usize;
+        let values = &self.levels[level];
+        values[lo].min(values[hi + 1 - (1 << level)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::SweeperBuilder;
+
+    fn sorted_triangles(triangles: impl Iterator<Item = Triangle>) -> Vec<[(f64, f64); 3]> {
+        let mut result = triangles
+            .map(|t| {
+                let mut points = t.points.map(|p| (p.x, p.y));
+                points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                points
+            })
+            .collect::<Vec<_>>();
+        result.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        result
+    }
+
+    #[test]
+    fn test_min_table() {
+        let table = MinTable::new(vec![5., 3., 8., 1., 9., 2., 7.]);
+        assert_eq!(table.min(0, 0), 5.);
+        assert_eq!(table.min(0, 2), 3.);
+        assert_eq!(table.min(2, 6), 1.);
+        assert_eq!(table.min(4, 6), 2.);
+    }
+
+    #[test]
+    fn test_stream_same_as_sweep() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let points = (0..3000)
+            .map(|_| Point::new(rng.gen_range(0.0..100.), rng.gen_range(0.0..1000.)))
+            .collect::<Vec<_>>();
+        let (min, max) = (Point::new(-1., -1.), Point::new(101., 1001.));
+
+        let mut streamed = vec![];
+        let stats = StreamBuilder::new()
+            .chunk_size(500)
+            .batch_size(100)
+            .bounds(min, max)
+            .triangulate_into(points.clone(), &mut |t: &Triangle| streamed.push(*t))
+            .unwrap();
+        assert_eq!(stats.points, 3000);
+        assert_eq!(stats.runs, 6);
+        assert_eq!(stats.triangles, streamed.len());
+        // only the band is kept in memory
+        assert!(stats.peak_triangles < streamed.len() / 2);
+
+        let sweeper = SweeperBuilder::new(vec![
+            min,
+            Point::new(max.x, min.y),
+            max,
+            Point::new(min.x, max.y),
+        ])
+        .add_steiner_points(points)
+        .build();
+        assert_eq!(
+            sorted_triangles(streamed.into_iter()),
+            sorted_triangles(sweeper.triangulate())
+        );
+    }
+
+    #[test]
+    fn test_stream_input() {
+        // duplicated points are merged
+        let points = vec![
+            Point::new(0., 0.),
+            Point::new(1., 0.5),
+            Point::new(-0., 0.),
+            Point::new(0.3, 1.),
+            Point::new(1., 0.5),
+        ];
+        let mut count = 0;
+        let stats = StreamBuilder::new()
+            .chunk_size(2)
+            .triangulate_into(points.clone(), &mut |_: &Triangle| count += 1)
+            .unwrap();
+        assert_eq!(stats.points, 3);
+        assert_eq!(stats.triangles, count);
+
+        let result = StreamBuilder::new()
+            .bounds(Point::new(0., 0.), Point::new(2., 2.))
+            .triangulate_into(points, &mut |_: &Triangle| {});
+        assert!(matches!(result, Err(StreamError::OutOfBounds(_))));
+
+        let result = StreamBuilder::new()
+            .triangulate_into([Point::new(f64::NAN, 0.)], &mut |_: &Triangle| {});
+        assert!(matches!(result, Err(StreamError::NotFinite(_))));
+    }
+}
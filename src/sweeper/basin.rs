@@ -0,0 +1,183 @@
+use crate::advancing_front::{NodeId, NodeRef};
+use crate::shape::Point;
+use crate::Context;
+
+use super::{Observer, Sweeper};
+
+#[derive(Debug)]
+struct Basin {
+    left: Point,
+    right: Point,
+    width: f64,
+    left_higher: bool,
+}
+
+impl Basin {
+    pub fn is_shallow(&self, point: Point) -> bool {
+        let height = if self.left_higher {
+            self.left.y - point.y
+        } else {
+            self.right.y - point.y
+        };
+
+        self.width > height
+    }
+
+    pub fn completed(&self, point: Point) -> bool {
+        if point.x >= self.right.x || point.x <= self.left.x {
+            return true;
+        }
+
+        self.is_shallow(point)
+    }
+}
+
+/// Basin related methods
+impl Sweeper {
+    pub(super) fn basin_angle_satisfy(node_id: NodeId, context: &Context) -> bool {
+        const TAN_3_4_PI: f64 = -1.;
+        let Some(next) = context.advancing_front.locate_next_node(node_id) else {
+            return false;
+        };
+        let Some(next_next) = next.next() else {
+            return false;
+        };
+
+        let ax = node_id.point().x - next_next.point().x;
+        let ay = node_id.point().y - next_next.point().y;
+        // the basin angle is (1/2pi, pi), so as long as tan value is less than 3/4 pi's, then its angle is less than 3/4 pi
+
+        // ay / ax < tan(3/4 * PI)
+        if ax > 0. {
+            ay < TAN_3_4_PI * ax
+        } else {
+            ay > TAN_3_4_PI * ax
+        }
+    }
+
+    /// basin is like a bowl, we first identify it's left, bottom, right node.
+    /// then fill it
+    pub(super) fn fill_basin(
+        node_point: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) -> Option<()> {
+        let next_node = context.advancing_front.locate_next_node(node_point)?;
+        let next_next_node = next_node.next()?;
+
+        // find the left
+        let left: NodeRef<'_> = if context
+            .predicates
+            .orient_2d(
+                node_point.point(),
+                next_node.point(),
+                next_next_node.point(),
+            )
+            .is_ccw()
+        {
+            next_next_node
+        } else {
+            next_node
+        };
+
+        // find the bottom
+        let mut bottom = left.clone();
+        while let Some(next_node) = bottom.next() {
+            if bottom.point().y >= next_node.point().y {
+                bottom = next_node;
+            } else {
+                break;
+            }
+        }
+
+        // no valid basin
+        if bottom.point_id().eq(&left.point_id()) {
+            return None;
+        }
+
+        // find the right
+        let mut right: NodeRef = bottom.clone();
+        while let Some(next_node) = right.next() {
+            if right.point().y < next_node.point().y {
+                right = next_node;
+            } else {
+                break;
+            }
+        }
+        if right.point_id() == bottom.point_id() {
+            // no valid basin
+            return None;
+        }
+
+        let width = right.point().x - left.point().x;
+        let left_higher: bool = left.point().y > right.point().y;
+
+        Self::fill_basin_req(
+            bottom.get_node_id(),
+            &Basin {
+                left: left.point(),
+                right: right.point(),
+                width,
+                left_higher,
+            },
+            context,
+            observer,
+        );
+
+        Some(())
+    }
+
+    fn fill_basin_req(
+        node: NodeId,
+        basin: &Basin,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) -> Option<()> {
+        if basin.completed(node.point()) {
+            return None;
+        }
+
+        let fill_one = Self::fill_one(node, context, observer).expect("already in basin");
+        let prev = fill_one.prev;
+        let next = fill_one.next;
+
+        if prev.point().eq(&basin.left) && next.point().eq(&basin.right) {
+            return Some(());
+        }
+
+        let new_node = if prev.point().eq(&basin.left) {
+            let next = context.advancing_front.get_node_with_id(next).unwrap();
+            let next_next = next.next().unwrap();
+            if context
+                .predicates
+                .orient_2d(node.point(), next.point(), next_next.point())
+                .is_cw()
+            {
+                return None;
+            }
+
+            next.get_node_id()
+        } else if next.point().eq(&basin.right) {
+            let prev = context.advancing_front.get_node_with_id(prev).unwrap();
+            let prev_prev = prev.prev()?;
+            if context
+                .predicates
+                .orient_2d(node.point(), prev.point(), prev_prev.point())
+                .is_ccw()
+            {
+                return None;
+            }
+
+            prev.get_node_id()
+        } else {
+            // continue with the neighbor node with lowest Y value
+            if prev.point().y < next.point().y {
+                prev
+            } else {
+                next
+            }
+        };
+
+        Self::fill_basin_req(new_node, basin, context, observer)
+    }
+}
@@ -0,0 +1,1695 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::metadata::Metadata;
+use crate::nesting::{self, RingNode};
+use crate::points::PointsBuilder;
+use crate::repair::{self, Merger, RepairOptions, RepairReport};
+use crate::tiling;
+use crate::utils::{is_collinear, point_in_ring, point_key, signed_area};
+use crate::validate::{validate, InputReport, MAX_SAFE_MAGNITUDE};
+use crate::{shape::*, Affine, PointId, SweepAxis, SweepConfig};
+
+use super::triangles::{deviation, PrecisionReport};
+use super::Sweeper;
+
+/// What [`SweeperBuilder::add_hole`] does with a degenerate hole, one with all
+/// points collinear, e.g. a slit going back and forth in a font outline
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DegenerateHole {
+    /// keep it as a constraint polyline through its points
+    #[default]
+    Collapse,
+    /// drop it with its points
+    Drop,
+}
+
+/// Where a polyline added by [`SweeperBuilder::add_constraint`] is enforced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConstraintScope {
+    /// only its pieces inside the polygon, ones over holes or outside are dropped
+    #[default]
+    Interior,
+    /// along its whole length, so triangles of holes it crosses respect it too,
+    /// e.g. a road crossing a lake
+    Everywhere,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum HoleError {
+    #[error("hole at {0:?} is degenerate, all its points are collinear")]
+    Degenerate(Point),
+}
+
+/// Why [`SweeperBuilder::from_rings`] found no single outer ring
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum RingsError {
+    #[error("no ring given")]
+    Empty,
+    #[error("rings {0:?} are each inside no other ring, expected one outer ring")]
+    MultipleOuter(Vec<usize>),
+}
+
+/// Holes dropped by [`SweeperBuilder::cull_holes_smaller_than`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CullReport {
+    /// holes dropped
+    pub holes_culled: usize,
+    /// total area of dropped holes
+    pub area_culled: f64,
+}
+
+/// Segment added more than once, see [`SweeperBuilder::duplicate_edges`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateEdge {
+    /// vertex indices of its ends, the smaller first
+    pub vertices: [usize; 2],
+    /// times added
+    pub count: u32,
+    /// whether it is a constrained edge of the mesh
+    pub kept: bool,
+}
+
+/// Sweeper Builder
+///
+/// # Regular inputs
+/// Regular grids, hex lattices, concentric squares and fans with collinear points,
+/// as boundary, holes or steiner points, triangulate into a valid constrained delaunay
+/// mesh. Cocircular ties are decided by exact predicates and broken by point ids, so
+/// the result is deterministic for the same input order.
+///
+/// # Example
+/// ```rust
+///    use poly2tri_rs::{SweeperBuilder, Point};
+///
+///    let builder = SweeperBuilder::new(vec![
+///        Point::new(-10., -10.),
+///        Point::new(810., -10.),
+///        Point::new(810., 810.),
+///        Point::new(-10., 810.),
+///    ]).add_steiner_points(vec![
+///        Point::new(50., 50.),
+///    ]).add_hole(vec![
+///        Point::new(400., 400.),
+///        Point::new(600., 400.),
+///        Point::new(600., 600.),
+///        Point::new(400., 600.),
+///    ]);
+///    let sweeper = builder.build();
+/// ```
+///
+/// # Typed states
+/// A builder from [`SweeperBuilder::new`] has its outer polyline already. Start with
+/// [`SweeperBuilder::empty`] to set up config first, holes and steiner points can
+/// only be added, and the sweeper built, after [`SweeperBuilder::outer`].
+/// ```compile_fail
+///    use poly2tri_rs::{SweeperBuilder, Point};
+///
+///    // no outer polyline yet
+///    let sweeper = SweeperBuilder::empty().build();
+/// ```
+#[derive(Clone)]
+pub struct SweeperBuilder<S = HasOuter> {
+    points_builder: PointsBuilder,
+    config: SweepConfig,
+    /// no holes or steiner points added
+    outer_only: bool,
+    /// point ids of outer polyline and holes, kept for [`Self::repair_input`]
+    rings: Vec<Vec<PointId>>,
+    /// point ids of collapsed holes, open polylines in order along their line
+    constraints: Vec<Vec<PointId>>,
+    /// tag of each ring, empty if no ring is tagged, see [`Self::tag_outer`]
+    ring_tags: Vec<Option<u32>>,
+    /// ring point ids by position, filled on first hole, so holes share points
+    /// with rings they touch
+    vertex_ids: HashMap<(u64, u64), PointId>,
+    degenerate_hole: DegenerateHole,
+    /// holes with area below are dropped, see [`Self::cull_holes_smaller_than`]
+    cull_area: f64,
+    cull_report: CullReport,
+    /// point weights by id, empty if no weighted point is added
+    weights: Vec<f64>,
+    /// seed points and material ids, see [`SweeperBuilder::add_region_seed`]
+    region_seeds: Vec<(Point, u32)>,
+    /// see [`SweeperBuilder::triangulate_holes`]
+    triangulate_holes: bool,
+    /// point id each input point was merged into, empty if none merged, see
+    /// [`SweeperBuilder::merge_duplicates`]
+    merged: Vec<u32>,
+    /// applied on build, see [`SweeperBuilder::with_transform`]
+    transform: Option<Affine>,
+    state: PhantomData<S>,
+}
+
+/// [`SweeperBuilder`] state before the outer polyline is set
+#[derive(Debug, Clone, Copy)]
+pub struct NeedsOuter;
+/// [`SweeperBuilder`] state with the outer polyline, ready to build
+#[derive(Debug, Clone, Copy)]
+pub struct HasOuter;
+impl Default for SweeperBuilder<NeedsOuter> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl SweeperBuilder<NeedsOuter> {
+    /// Create a builder without outer polyline, set it by [`Self::outer`]
+    pub fn empty() -> Self {
+        Self {
+            points_builder: PointsBuilder::with_capacity(0),
+            config: SweepConfig::default(),
+            outer_only: true,
+            rings: vec![],
+            constraints: vec![],
+            ring_tags: vec![],
+            vertex_ids: HashMap::new(),
+            degenerate_hole: DegenerateHole::default(),
+            cull_area: 0.,
+            cull_report: CullReport::default(),
+            weights: vec![],
+            region_seeds: vec![],
+            triangulate_holes: false,
+            merged: vec![],
+            transform: None,
+            state: PhantomData,
+        }
+    }
+
+    /// Set the [`SweepConfig`] used by sweeper
+    pub fn with_config(mut self, config: SweepConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set how later added degenerate holes are handled
+    pub fn degenerate_hole(mut self, policy: DegenerateHole) -> Self {
+        self.degenerate_hole = policy;
+        self
+    }
+
+    /// Drop later added holes with area below `area`
+    pub fn cull_holes_smaller_than(mut self, area: f64) -> Self {
+        self.cull_area = area;
+        self
+    }
+
+    /// Set the outer polyline, same as [`SweeperBuilder::new`]
+    pub fn outer(self, polyline: Vec<Point>) -> SweeperBuilder<HasOuter> {
+        SweeperBuilder::new(polyline)
+            .with_config(self.config)
+            .degenerate_hole(self.degenerate_hole)
+            .cull_holes_smaller_than(self.cull_area)
+    }
+}
+
+impl SweeperBuilder<HasOuter> {
+    /// Create a new Builder with polyline
+    /// There should be only one polyline, and multiple holes and steiner points supported
+    pub fn new(polyline: Vec<Point>) -> Self {
+        let mut points_builder = PointsBuilder::with_capacity(polyline.len());
+        let ring = parse_polyline(polyline, &mut points_builder);
+
+        Self {
+            points_builder,
+            config: SweepConfig::default(),
+            outer_only: true,
+            rings: vec![ring],
+            constraints: vec![],
+            ring_tags: vec![],
+            vertex_ids: HashMap::new(),
+            degenerate_hole: DegenerateHole::default(),
+            cull_area: 0.,
+            cull_report: CullReport::default(),
+            weights: vec![],
+            region_seeds: vec![],
+            triangulate_holes: false,
+            merged: vec![],
+            transform: None,
+            state: PhantomData,
+        }
+    }
+
+    /// Label the region holding `point` with `material`, flooded over triangles
+    /// up to constrained edges, i.e. the outer polyline and holes. Read labels by
+    /// [`Triangles::material`]. A region keeps the material of its first seed.
+    pub fn add_region_seed(mut self, point: Point, material: u32) -> Self {
+        self.region_seeds.push((point, material));
+        self
+    }
+
+    /// Also collect triangles inside each hole, read them by
+    /// [`Triangles::hole_triangles`], e.g. to render lakes with their own
+    /// material instead of cutting them out
+    pub fn triangulate_holes(mut self, enabled: bool) -> Self {
+        self.triangulate_holes = enabled;
+        self
+    }
+
+    /// Create a builder from rings in any order and winding, e.g. a ring list of
+    /// a GeoJSON polygon or a shapefile. The ring inside no other one is the outer
+    /// polyline, all others are added as holes. Rings nested deeper, e.g. an island
+    /// inside a hole, are added as holes too, the sweep fills areas inside an odd
+    /// number of rings. See [`nesting`] to inspect or edit the nesting first.
+    pub fn from_rings(rings: impl IntoIterator<Item = Vec<Point>>) -> Result<Self, RingsError> {
+        let mut rings = rings.into_iter().collect::<Vec<_>>();
+        let roots = nesting::hierarchy(&rings);
+        match roots[..] {
+            [] => Err(RingsError::Empty),
+            [ref root] => {
+                // holes keep their order
+                let outer = rings.remove(root.ring);
+                Ok(Self::new(outer).add_holes(rings))
+            }
+            _ => Err(RingsError::MultipleOuter(
+                roots.iter().map(|root| root.ring).collect(),
+            )),
+        }
+    }
+
+    /// Create a builder from the tree at `root` of [`nesting::hierarchy`], maybe
+    /// edited. Ring `root.ring` is the outer polyline, rings below it at any depth
+    /// are added as holes depth first, rings not in the tree are left out.
+    pub fn from_hierarchy(rings: &[Vec<Point>], root: &RingNode) -> Self {
+        let ids = root.rings();
+        Self::new(rings[ids[0]].clone()).add_holes(ids[1..].iter().map(|id| rings[*id].clone()))
+    }
+
+    /// Add a single sparse `Point`, there is no edge attached to it
+    /// NOTE: if the point locates outside of polyline, then it has no
+    /// effect on the final result
+    pub fn add_steiner_point(mut self, point: Point) -> Self {
+        self.points_builder.add_steiner_point(point);
+        self.outer_only = false;
+        self
+    }
+
+    /// Add multiple [`Point`], batch version for `Self::add_point`
+    pub fn add_steiner_points(mut self, points: impl IntoIterator<Item = Point>) -> Self {
+        self.points_builder.add_steiner_points(points);
+        self.outer_only = false;
+        self
+    }
+
+    /// Add steiner points with weights, the result is then a weighted delaunay,
+    /// i.e. regular, triangulation by [`PowerDelaunay`]. Points added other ways
+    /// weigh 0. Weights are dropped by [`Self::repair_input`].
+    pub fn add_weighted_steiner_points(
+        mut self,
+        points: impl IntoIterator<Item = (Point, f64)>,
+    ) -> Self {
+        for (point, weight) in points {
+            let point_id = self.points_builder.add_steiner_point(point);
+            self.weights.resize(point_id.as_usize(), 0.);
+            self.weights.push(weight);
+        }
+        self.outer_only = false;
+        self
+    }
+
+    /// Add a hole defined by polyline.
+    /// A hole vertex at the exact position of a vertex of the outer polyline or an
+    /// earlier hole shares its point, so holes may touch them at vertices or along
+    /// edges. A shared edge cancels out, it is the border of neither.
+    /// A degenerate hole is handled as set by [`Self::degenerate_hole`], a small
+    /// one as set by [`Self::cull_holes_smaller_than`].
+    pub fn add_hole(mut self, polyline: Vec<Point>) -> Self {
+        self.push_hole(polyline);
+        self
+    }
+
+    /// Same as [`Self::add_hole`], and tag it by `tag`, e.g. a draw priority or a
+    /// style id, read back by [`Triangles::ring_tag`]. A dropped hole has no tag.
+    pub fn add_tagged_hole(mut self, polyline: Vec<Point>, tag: u32) -> Self {
+        let ring = self.rings.len();
+        self.push_hole(polyline);
+        if self.rings.len() > ring {
+            self.set_ring_tag(ring, tag);
+        }
+        self
+    }
+
+    /// Tag the outer polyline by `tag`, see [`Self::add_tagged_hole`]
+    pub fn tag_outer(mut self, tag: u32) -> Self {
+        self.set_ring_tag(0, tag);
+        self
+    }
+
+    fn set_ring_tag(&mut self, ring: usize, tag: u32) {
+        if self.ring_tags.len() <= ring {
+            self.ring_tags.resize(ring + 1, None);
+        }
+        self.ring_tags[ring] = Some(tag);
+    }
+
+    fn push_hole(&mut self, polyline: Vec<Point>) {
+        if !is_collinear(&polyline) {
+            return self.add_ring_or_cull(polyline);
+        }
+        match self.degenerate_hole {
+            DegenerateHole::Collapse => self.add_collapsed_hole(polyline),
+            DegenerateHole::Drop => {}
+        }
+    }
+
+    /// Add an open polyline of constrained edges, e.g. a road or a river bank.
+    /// Where it crosses an edge of the outer polyline or of a hole added before,
+    /// both are split at the crossing. It doesn't change what is interior,
+    /// `scope` picks whether it is kept over holes and outside too. Its edges are
+    /// a [`PolylineId::Constraint`], numbered with collapsed holes.
+    pub fn add_constraint(mut self, polyline: Vec<Point>, scope: ConstraintScope) -> Self {
+        let mut ids = Vec::<PointId>::with_capacity(polyline.len());
+        for point in polyline {
+            let id = self.hole_point_id(point);
+            match ids.last() {
+                Some(last) if *last == id => continue,
+                Some(last) => {
+                    let crossings = self.split_rings_along(*last, id);
+                    ids.extend(crossings);
+                }
+                None => {}
+            }
+            ids.push(id);
+        }
+
+        // rings are final once split, their points are read once for all segments
+        let rings = match scope {
+            ConstraintScope::Interior => self.ring_points(),
+            ConstraintScope::Everywhere => vec![],
+        };
+        for pair in ids.windows(2) {
+            let (p1, p2) = (pair[0], pair[1]);
+            let (point1, point2) = (
+                self.points_builder.get_point(p1),
+                self.points_builder.get_point(p2),
+            );
+            let mid = Point::new((point1.x + point2.x) / 2., (point1.y + point2.y) / 2.);
+            if scope == ConstraintScope::Interior && !is_interior(&rings, mid) {
+                continue;
+            }
+            self.points_builder
+                .add_open_edge(Edge::new((p1, &point1), (p2, &point2)));
+        }
+        self.constraints.push(ids);
+        self.outer_only = false;
+        self
+    }
+
+    /// Split ring edges crossing the segment from `p` to `q`, returns the points
+    /// added at the crossings in order from `p`
+    fn split_rings_along(&mut self, p: PointId, q: PointId) -> Vec<PointId> {
+        let (a, b) = (
+            self.points_builder.get_point(p),
+            self.points_builder.get_point(q),
+        );
+        let mut crossings = vec![];
+        for (ring_index, ring) in self.rings.iter().enumerate() {
+            for index in 0..ring.len() {
+                let (u, v) = (ring[index], ring[(index + 1) % ring.len()]);
+                if [u, v].iter().any(|id| *id == p || *id == q) {
+                    continue;
+                }
+                let (c, d) = (
+                    self.points_builder.get_point(u),
+                    self.points_builder.get_point(v),
+                );
+                if let Some(t) = crossing(a, b, c, d) {
+                    crossings.push((t, ring_index, index));
+                }
+            }
+        }
+
+        let mut splits = crossings
+            .into_iter()
+            .map(|(t, ring, index)| {
+                let x = Point::new(a.x + t * (b.x - a.x), a.y + t * (b.y - a.y));
+                (t, ring, index, self.hole_point_id(x))
+            })
+            .collect::<Vec<_>>();
+        // later edges of a ring first, indices of earlier ones stay valid
+        splits.sort_by_key(|split| std::cmp::Reverse((split.1, split.2)));
+        for &(_, ring, index, x) in splits.iter() {
+            let len = self.rings[ring].len();
+            let (u, v) = (self.rings[ring][index], self.rings[ring][(index + 1) % len]);
+            let point = |id| (id, self.points_builder.get_point(id));
+            let ((u, pu), (v, pv), (x, px)) = (point(u), point(v), point(x));
+            self.points_builder
+                .remove_edge(Edge::new((u, &pu), (v, &pv)));
+            self.points_builder.add_edge(Edge::new((u, &pu), (x, &px)));
+            self.points_builder.add_edge(Edge::new((x, &px), (v, &pv)));
+            self.rings[ring].insert(index + 1, x);
+        }
+        splits.sort_by(|l, r| l.0.total_cmp(&r.0));
+        splits.into_iter().map(|split| split.3).collect()
+    }
+
+    /// Points of the outer polyline and holes, in ring order
+    fn ring_points(&self) -> Vec<Vec<Point>> {
+        self.rings
+            .iter()
+            .map(|ring| {
+                ring.iter()
+                    .map(|id| self.points_builder.get_point(*id))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Same as [`Self::add_hole`], but rejects a degenerate hole
+    pub fn try_add_hole(mut self, polyline: Vec<Point>) -> Result<Self, HoleError> {
+        if is_collinear(&polyline) {
+            return Err(HoleError::Degenerate(
+                polyline.first().copied().unwrap_or_default(),
+            ));
+        }
+        self.add_ring_or_cull(polyline);
+        Ok(self)
+    }
+
+    /// Set how later added degenerate holes are handled, default is
+    /// [`DegenerateHole::Collapse`]
+    pub fn degenerate_hole(mut self, policy: DegenerateHole) -> Self {
+        self.degenerate_hole = policy;
+        self
+    }
+
+    /// Drop later added holes with area below `area` before sweeping, e.g. specks
+    /// of a map feature too small to see when zoomed out. Degenerate holes have no
+    /// area, they are handled by [`Self::degenerate_hole`]. Default is 0, keep all.
+    /// Dropped holes are counted in [`Self::cull_report`].
+    pub fn cull_holes_smaller_than(mut self, area: f64) -> Self {
+        self.cull_area = area;
+        self
+    }
+
+    /// Holes dropped so far by [`Self::cull_holes_smaller_than`]
+    pub fn cull_report(&self) -> CullReport {
+        self.cull_report
+    }
+
+    /// Segments added more than once so far, e.g. by the outer polyline and a hole
+    /// touching it, ordered by vertex indices. Each is handled once by the sweep,
+    /// as an edge if added an odd number of times, so rings cancel where they
+    /// overlap. Collapsed holes keep their edges.
+    pub fn duplicate_edges(&self) -> Vec<DuplicateEdge> {
+        let mut duplicates = self
+            .points_builder
+            .repeated_edges()
+            .map(|(edge, count, kept)| {
+                let (p, q) = (edge.p.as_usize(), edge.q.as_usize());
+                DuplicateEdge {
+                    vertices: [p.min(q), p.max(q)],
+                    count,
+                    kept,
+                }
+            })
+            .collect::<Vec<_>>();
+        duplicates.sort_by_key(|d| d.vertices);
+        duplicates
+    }
+
+    fn add_ring_or_cull(&mut self, polyline: Vec<Point>) {
+        let area = signed_area(&polyline).abs();
+        if area < self.cull_area {
+            self.cull_report.holes_culled += 1;
+            self.cull_report.area_culled += area;
+            return;
+        }
+        self.add_ring(polyline);
+    }
+
+    fn add_ring(&mut self, polyline: Vec<Point>) {
+        let ring = polyline
+            .into_iter()
+            .map(|p| self.hole_point_id(p))
+            .collect::<Vec<_>>();
+        add_ring_edges(&ring, &mut self.points_builder);
+        self.rings.push(ring);
+        self.outer_only = false;
+    }
+
+    /// Add collinear points as a polyline in order along their line
+    fn add_collapsed_hole(&mut self, mut polyline: Vec<Point>) {
+        polyline.sort_by(|l, r| l.x.total_cmp(&r.x).then(l.y.total_cmp(&r.y)));
+        polyline.dedup_by(|l, r| l.eq(r));
+        let ids = polyline
+            .into_iter()
+            .map(|p| self.hole_point_id(p))
+            .collect::<Vec<_>>();
+        for pair in ids.windows(2) {
+            let (p1, p2) = (pair[0], pair[1]);
+            let edge = Edge::new(
+                (p1, &self.points_builder.get_point(p1)),
+                (p2, &self.points_builder.get_point(p2)),
+            );
+            // it may be an edge of a ring, keep it
+            self.points_builder.add_open_edge(edge);
+        }
+        self.constraints.push(ids);
+        self.outer_only = false;
+    }
+
+    /// Point id for a hole vertex, shared with ring vertices at the same position
+    fn hole_point_id(&mut self, point: Point) -> PointId {
+        self.index_ring_vertices();
+        *self
+            .vertex_ids
+            .entry(point_key(point))
+            .or_insert_with(|| self.points_builder.add_steiner_point(point))
+    }
+
+    /// Fill vertex ids with ring vertices, once before the first hole
+    fn index_ring_vertices(&mut self) {
+        if self.vertex_ids.is_empty() {
+            for point_id in self.rings.iter().flatten() {
+                let key = point_key(self.points_builder.get_point(*point_id));
+                self.vertex_ids.entry(key).or_insert(*point_id);
+            }
+        }
+    }
+
+    /// Add holes, same as [`Self::add_hole`] for each. Storage for points, rings
+    /// and deduplication is reserved once for all of them, so thousands of small
+    /// holes, e.g. perforations of a panel, are added in linear time.
+    pub fn add_holes(mut self, holes: impl IntoIterator<Item = Vec<Point>>) -> Self {
+        let holes = holes.into_iter().collect::<Vec<_>>();
+        let point_count = holes.iter().map(Vec::len).sum::<usize>();
+        self.index_ring_vertices();
+        self.vertex_ids.reserve(point_count);
+        self.points_builder.reserve(point_count);
+        self.rings.reserve(holes.len());
+        self.extend(holes);
+        self
+    }
+
+    /// Set the [`SweepConfig`] used by sweeper
+    pub fn with_config(mut self, config: SweepConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sweep points mapped by `transform`, e.g. a projection into a space where
+    /// the sweep is better conditioned, and output them mapped back by its
+    /// inverse. Points are mapped in place on build, no copy is kept. A mirroring
+    /// transform, one with negative determinant, flips the winding of output
+    /// triangles. Weights are scaled by the area factor, exact for similarity
+    /// transforms only. Panics if `transform` has no inverse.
+    ///
+    /// # Example
+    /// ```rust
+    ///    use poly2tri_rs::{Affine, Point, SweeperBuilder};
+    ///
+    ///    let triangles = SweeperBuilder::new(vec![
+    ///        Point::new(0., 0.),
+    ///        Point::new(1e6, 0.),
+    ///        Point::new(1e6, 1.),
+    ///        Point::new(0., 1.),
+    ///    ])
+    ///    .with_transform(Affine::scale(1e-6, 1.))
+    ///    .build()
+    ///    .triangulate();
+    ///    for triangle in triangles {
+    ///        assert!(triangle.points.iter().all(|p| p.x == 0. || p.x == 1e6));
+    ///    }
+    /// ```
+    pub fn with_transform(mut self, transform: Affine) -> Self {
+        assert!(
+            transform.inverse().is_some(),
+            "transform {transform:?} has no inverse"
+        );
+        self.transform = Some(transform);
+        self
+    }
+
+    /// Clean up messy input: merge repeated and close points, drop closing points
+    /// and degenerated rings, orient rings and split edges where they intersect
+    /// or touch. Call it after all holes and steiner points are added.
+    ///
+    /// # Example
+    /// ```rust
+    ///    use poly2tri_rs::{Point, RepairOptions, SweeperBuilder};
+    ///
+    ///    // cw, with a repeated point and closed
+    ///    let (builder, report) = SweeperBuilder::new(vec![
+    ///        Point::new(0., 0.),
+    ///        Point::new(0., 2.),
+    ///        Point::new(0., 2.),
+    ///        Point::new(2., 2.),
+    ///        Point::new(2., 0.),
+    ///        Point::new(0., 0.),
+    ///    ])
+    ///    .repair_input(RepairOptions::default());
+    ///    assert_eq!(report.points_merged, 2);
+    ///    assert_eq!(report.rings_reoriented, 1);
+    ///    assert_eq!(builder.build().triangulate().count(), 2);
+    /// ```
+    pub fn repair_input(mut self, options: RepairOptions) -> (Self, RepairReport) {
+        let (rings, steiner_points) = self.input_points();
+        let repaired = repair::repair(rings, steiner_points, &options);
+        let report = repaired.report.clone();
+        self.set_repaired(repaired);
+        (self, report)
+    }
+
+    /// Merge points within `epsilon` of each other into the first one, `0.`
+    /// merges exact duplicates only. Rings and constraints are remapped to kept
+    /// points, repeats along them dropped, and rings left with less than 3
+    /// points dropped. Call it after all holes and steiner points are added, map
+    /// input points to vertices by [`Triangles::merged_vertices`].
+    pub fn merge_duplicates(mut self, epsilon: f64) -> Self {
+        let mut merger = Merger::new(epsilon);
+        let mut kept = HashMap::<(u64, u64), PointId>::new();
+        let mut points_builder = PointsBuilder::with_capacity(self.points_builder.len());
+        let remap = (0..self.points_builder.len())
+            .map(|index| {
+                let point = merger.snap(self.points_builder.get_point(PointId::from_usize(index)));
+                *kept
+                    .entry(point_key(point))
+                    .or_insert_with(|| points_builder.add_steiner_point(point))
+            })
+            .collect::<Vec<_>>();
+        if points_builder.len() == remap.len() {
+            return self;
+        }
+        let old = std::mem::replace(&mut self.points_builder, points_builder);
+
+        let mut rings = Vec::with_capacity(self.rings.len());
+        let mut ring_tags = vec![];
+        for (index, ring) in self.rings.iter().enumerate() {
+            let mut ring = ring
+                .iter()
+                .map(|id| remap[id.as_usize()])
+                .collect::<Vec<_>>();
+            ring.dedup();
+            while ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+            if ring.len() < 3 {
+                continue;
+            }
+            add_ring_edges(&ring, &mut self.points_builder);
+            rings.push(ring);
+            if let Some(tag) = self.ring_tags.get(index) {
+                ring_tags.push(*tag);
+            }
+        }
+
+        let mut constraints = Vec::with_capacity(self.constraints.len());
+        for ids in self.constraints.iter() {
+            // edges of the polyline outside the polygon may be left out
+            for pair in ids.windows(2) {
+                let point = |id: PointId| (id, old.get_point(id));
+                let ((p, pp), (q, pq)) = (point(pair[0]), point(pair[1]));
+                let (p, q) = (remap[p.as_usize()], remap[q.as_usize()]);
+                if p != q && old.has_edge(Edge::new((pair[0], &pp), (pair[1], &pq))) {
+                    let (pp, pq) = (
+                        self.points_builder.get_point(p),
+                        self.points_builder.get_point(q),
+                    );
+                    self.points_builder
+                        .add_open_edge(Edge::new((p, &pp), (q, &pq)));
+                }
+            }
+            let mut ids = ids
+                .iter()
+                .map(|id| remap[id.as_usize()])
+                .collect::<Vec<_>>();
+            ids.dedup();
+            if ids.len() >= 2 {
+                constraints.push(ids);
+            }
+        }
+
+        // new ids are given in order of first use, the first point merged into
+        // an id created it
+        if !self.weights.is_empty() {
+            let mut weights = Vec::with_capacity(self.points_builder.len());
+            for (weight, id) in self.weights.iter().zip(remap.iter()) {
+                if id.as_usize() == weights.len() {
+                    weights.push(*weight);
+                }
+            }
+            self.weights = weights;
+        }
+        self.merged = match self.merged.is_empty() {
+            true => remap.iter().map(|id| id.as_usize() as u32).collect(),
+            false => self
+                .merged
+                .iter()
+                .map(|id| remap[*id as usize].as_usize() as u32)
+                .collect(),
+        };
+        self.rings = rings;
+        self.ring_tags = ring_tags;
+        self.constraints = constraints;
+        self.vertex_ids.clear();
+        self.outer_only = false;
+        self
+    }
+
+    /// Clip outer polyline and holes to the rect `min`..`max` before sweeping, e.g.
+    /// the visible tile of a continent sized polygon. Parts of the rect border
+    /// inside the polygon become constrained edges, steiner points outside the
+    /// rect are dropped. Rings are clipped like tiles of [`crate::tiling`], a
+    /// polygon falling apart into several parts is fine. Call it after all holes
+    /// and steiner points are added, point weights are cleared.
+    ///
+    /// # Example
+    /// ```rust
+    ///    use poly2tri_rs::{Point, SweeperBuilder};
+    ///
+    ///    // a U shape, the window cuts off its bottom, leaving two arms
+    ///    let triangles = SweeperBuilder::new(vec![
+    ///        Point::new(0., 0.),
+    ///        Point::new(30., 0.),
+    ///        Point::new(30., 30.),
+    ///        Point::new(20., 30.),
+    ///        Point::new(20., 10.),
+    ///        Point::new(10., 10.),
+    ///        Point::new(10., 30.),
+    ///        Point::new(0., 30.),
+    ///    ])
+    ///    .clip_to(Point::new(-5., 20.), Point::new(35., 25.))
+    ///    .build()
+    ///    .triangulate();
+    ///    assert_eq!(triangles.count(), 4);
+    /// ```
+    pub fn clip_to(mut self, min: Point, max: Point) -> Self {
+        let (mut rings, steiner_points) = self.input_points();
+        let polygon = tiling::Polygon {
+            outer: if rings.is_empty() {
+                vec![]
+            } else {
+                rings.remove(0)
+            },
+            holes: rings,
+        };
+        // parts after the first are rings like holes, the sweep fills by even-odd
+        let rings = tiling::clip_polygon(polygon, min, max)
+            .into_iter()
+            .flat_map(|polygon| std::iter::once(polygon.outer).chain(polygon.holes))
+            .collect();
+        let steiner_points = steiner_points
+            .into_iter()
+            .filter(|p| p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y)
+            .collect();
+
+        let options = RepairOptions::default().orient(false);
+        self.set_repaired(repair::repair(rings, steiner_points, &options));
+        self
+    }
+
+    /// Points of rings, outer first, and steiner points
+    fn input_points(&self) -> (Vec<Vec<Point>>, Vec<Point>) {
+        let mut in_ring = vec![false; self.points_builder.len()];
+        let rings = self
+            .rings
+            .iter()
+            .map(|ring| {
+                ring.iter()
+                    .map(|point_id| {
+                        in_ring[point_id.as_usize()] = true;
+                        self.points_builder.get_point(*point_id)
+                    })
+                    .collect()
+            })
+            .collect();
+        let steiner_points = (0..in_ring.len())
+            .filter(|idx| !in_ring[*idx])
+            .map(|idx| self.points_builder.get_point(PointId::from_usize(idx)))
+            .collect();
+        (rings, steiner_points)
+    }
+
+    fn set_repaired(&mut self, repaired: repair::Repaired) {
+        // monotone fast path needs one ring with ids in polyline order
+        self.outer_only = repaired.rings.len() == 1
+            && repaired.points_builder.len() == repaired.rings[0].len()
+            && repaired.rings[0]
+                .iter()
+                .enumerate()
+                .all(|(idx, point_id)| point_id.as_usize() == idx);
+        self.points_builder = repaired.points_builder;
+        self.rings = repaired.rings;
+        // repair takes points of collapsed holes as steiner points, and rings may
+        // split or merge
+        self.constraints.clear();
+        self.ring_tags.clear();
+        self.vertex_ids.clear();
+        self.weights.clear();
+        self.merged.clear();
+    }
+
+    /// Input points by vertex index
+    #[cfg(feature = "compare")]
+    pub(crate) fn vertices(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.points_builder.len())
+            .map(|idx| self.points_builder.get_point(PointId::from_usize(idx)))
+    }
+
+    /// Point ids of outer polyline and holes
+    #[cfg(feature = "compare")]
+    pub(crate) fn rings(&self) -> &[Vec<PointId>] {
+        &self.rings
+    }
+
+    /// build the sweeper
+    /// NOTE: NaN or infinite coordinates break point ordering and corrupt the
+    /// sweep silently, use [`Self::try_build`] for untrusted input
+    pub fn build(mut self) -> Sweeper {
+        if self.sweeps_along_x() {
+            // exact, swaps x and y with one sign flip
+            let turn = Affine::new(0., -1., 1., 0., 0., 0.);
+            self.transform = Some(self.transform.unwrap_or_default().then(&turn));
+        }
+        if self.config.shear_columns {
+            let transform = self.transform.unwrap_or_default();
+            if let Some(k) = self.points_builder.column_shear(|p| transform.apply(p)) {
+                self.transform = Some(transform.then(&Affine::new(1., k, 0., 1., 0., 0.)));
+            }
+        }
+        let mut precision = PrecisionReport::default();
+        let inverse = self.transform.map(|transform| {
+            let inverse = transform.inverse().unwrap();
+            self.points_builder.transform(|p| {
+                let q = transform.apply(p);
+                precision.transform = precision.transform.max(deviation(inverse.apply(q), p));
+                q
+            });
+            for weight in self.weights.iter_mut() {
+                *weight *= transform.determinant().abs();
+            }
+            for (seed, _) in self.region_seeds.iter_mut() {
+                *seed = transform.apply(*seed);
+            }
+            inverse
+        });
+        if self.config.x_tolerance > 0. {
+            let moved = self.points_builder.snap_x(self.config.x_tolerance);
+            // a move along x in sweep space, mapped back to input space
+            precision.snap = match &inverse {
+                Some(inverse) => moved * inverse.a.abs().max(inverse.c.abs()),
+                None => moved,
+            };
+        }
+        let scale = self.rescale_factor();
+        if scale != 1. {
+            self.points_builder.scale(scale);
+            // weights are squared distances
+            for weight in self.weights.iter_mut() {
+                *weight *= scale * scale;
+            }
+            for (seed, _) in self.region_seeds.iter_mut() {
+                *seed = Point::new(seed.x * scale, seed.y * scale);
+            }
+        }
+        let points = self.points_builder.build_with(self.config.ghost_points);
+        Sweeper {
+            points,
+            config: self.config,
+            outer_only: self.outer_only,
+            rings: self.rings,
+            constraints: self.constraints,
+            ring_tags: self.ring_tags,
+            weights: self.weights,
+            region_seeds: self.region_seeds,
+            triangulate_holes: self.triangulate_holes,
+            merged: self.merged,
+            scale,
+            inverse,
+            precision,
+        }
+    }
+
+    /// Whether the sweep turns input to advance along x, see [`SweepConfig::sweep_axis`]
+    fn sweeps_along_x(&self) -> bool {
+        match self.config.sweep_axis {
+            SweepAxis::Y => false,
+            SweepAxis::X => true,
+            SweepAxis::Auto => {
+                let transform = self.transform.unwrap_or_default();
+                let (width, height) = self.points_builder.extent(|p| transform.apply(p));
+                width > height * 2.
+            }
+        }
+    }
+
+    /// Power of two bringing coordinates to about 1 when they may overflow, exact
+    /// both ways unless small coordinates underflow. 1 if not needed or disabled
+    fn rescale_factor(&self) -> f64 {
+        let magnitude = self.points_builder.max_magnitude();
+        if !self.config.auto_rescale || magnitude <= MAX_SAFE_MAGNITUDE || !magnitude.is_finite() {
+            return 1.;
+        }
+        2f64.powi(-(magnitude.log2().floor() as i32))
+    }
+
+    /// Metadata of the mesh this builder produces, for headers of serialized results
+    pub fn metadata(&self) -> Metadata {
+        Metadata::new(
+            &self.config,
+            &self.points_builder,
+            &self.rings,
+            &self.weights,
+        )
+    }
+
+    /// Same as [`Self::build`], but checks input first and reports all problems
+    /// found: an outer polyline of less than 3 points, NaN or infinite
+    /// coordinates, ones large enough to overflow when
+    /// [`SweepConfig::auto_rescale`] is off, duplicate points, intersecting edges
+    /// and holes outside the outer polyline
+    pub fn try_build(self) -> Result<Sweeper, InputReport> {
+        let report = validate(&self.points_builder, &self.rings, self.config.auto_rescale);
+        if !report.problems.is_empty() {
+            return Err(report);
+        }
+        Ok(self.build())
+    }
+}
+
+/// Add holes, same as [`SweeperBuilder::add_holes`], e.g. rings piped from a parser
+impl Extend<Vec<Point>> for SweeperBuilder<HasOuter> {
+    fn extend<I: IntoIterator<Item = Vec<Point>>>(&mut self, holes: I) {
+        for polyline in holes {
+            self.push_hole(polyline);
+        }
+    }
+}
+
+/// Add holes from borrowed rings
+impl<'a> Extend<&'a [Point]> for SweeperBuilder<HasOuter> {
+    fn extend<I: IntoIterator<Item = &'a [Point]>>(&mut self, holes: I) {
+        self.extend(holes.into_iter().map(|polyline| polyline.to_vec()));
+    }
+}
+
+/// Add polyline's points and edges, returns point ids in polyline order
+pub(crate) fn parse_polyline(polyline: Vec<Point>, points: &mut PointsBuilder) -> Vec<PointId> {
+    let ring = polyline
+        .into_iter()
+        .map(|p| points.add_steiner_point(p))
+        .collect::<Vec<_>>();
+    add_ring_edges(&ring, points);
+    ring
+}
+
+/// Whether `point` is inside the polygon of `rings` by even odd rule, or on a
+/// ring edge
+fn is_interior(rings: &[Vec<Point>], point: Point) -> bool {
+    let mut inside = false;
+    for ring in rings {
+        match point_in_ring(ring, point) {
+            Some(within) => inside ^= within,
+            None => return true,
+        }
+    }
+    inside
+}
+
+/// Parameter along `a -> b` where it crosses `c -> d`, both strictly inside the
+/// segments
+fn crossing(a: Point, b: Point, c: Point, d: Point) -> Option<f64> {
+    let det = |u: Point, v: Point| u.x * v.y - u.y * v.x;
+    let sub = |u: Point, v: Point| Point::new(u.x - v.x, u.y - v.y);
+    let (r, s) = (sub(b, a), sub(d, c));
+    let denom = det(r, s);
+    if denom == 0. {
+        return None;
+    }
+    let t = det(sub(c, a), s) / denom;
+    let u = det(sub(c, a), r) / denom;
+    (t > 0. && t < 1. && u > 0. && u < 1.).then_some(t)
+}
+
+/// Add edges between consecutive points of the closed `ring`
+fn add_ring_edges(ring: &[PointId], points: &mut PointsBuilder) {
+    let len = ring.len();
+    for idx in 0..len {
+        let (p1, p2) = (ring[idx], ring[(idx + 1) % len]);
+        let (point1, point2) = (points.get_point(p1), points.get_point(p2));
+        // repeated points are left to `SweeperBuilder::repair_input`
+        if point1.eq(&point2) {
+            continue;
+        }
+        points.add_edge(Edge::new((p1, &point1), (p2, &point2)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::origin::PolylineId;
+    use crate::sweeper::tests::{assert_valid_mesh, sorted_indices, try_load_from_file};
+    use crate::{datasets, InputError};
+
+    #[test]
+    fn test_degenerate_hole() {
+        let outer = vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ];
+        // slit going back and forth, unordered along its line
+        let slit = vec![
+            Point::new(2., 3.),
+            Point::new(8., 6.),
+            Point::new(4., 4.),
+            Point::new(8., 6.),
+        ];
+
+        let builder = SweeperBuilder::new(outer.clone()).add_hole(slit.clone());
+        assert_valid_mesh("collapse", builder.clone(), 100.);
+        let triangles = builder.build().triangulate().collect::<Vec<_>>();
+        for (p, q) in [((2., 3.), (4., 4.)), ((4., 4.), (8., 6.))] {
+            let (p, q) = (Point::new(p.0, p.1), Point::new(q.0, q.1));
+            // a constrained edge inside has triangles on both sides
+            let count = triangles
+                .iter()
+                .filter(|t| t.points.iter().filter(|v| v.eq(&p) || v.eq(&q)).count() == 2)
+                .count();
+            assert_eq!(count, 2);
+        }
+
+        let builder = SweeperBuilder::new(outer.clone())
+            .degenerate_hole(DegenerateHole::Drop)
+            .add_hole(slit.clone());
+        assert_valid_mesh("drop", builder.clone(), 100.);
+        assert_eq!(builder.build().triangulate().count(), 2);
+
+        let result = SweeperBuilder::new(outer).try_add_hole(slit);
+        assert!(matches!(result, Err(HoleError::Degenerate(_))));
+    }
+
+    #[test]
+    fn test_add_holes() {
+        let outer = vec![
+            Point::new(0., 0.),
+            Point::new(100., 0.),
+            Point::new(100., 100.),
+            Point::new(0., 100.),
+        ];
+        // 10 x 10 diamonds touching their neighbors at vertices
+        let holes = (0..100)
+            .map(|i| {
+                let (x, y) = ((i % 10) as f64 * 8. + 14., (i / 10) as f64 * 8. + 14.);
+                vec![
+                    Point::new(x, y - 4.),
+                    Point::new(x + 4., y),
+                    Point::new(x, y + 4.),
+                    Point::new(x - 4., y),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let one_by_one = holes
+            .iter()
+            .fold(SweeperBuilder::new(outer.clone()), |builder, hole| {
+                builder.add_hole(hole.clone())
+            });
+        let bulk = SweeperBuilder::new(outer).add_holes(holes);
+        assert_valid_mesh("add_holes", bulk.clone(), 10000. - 100. * 32.);
+        assert_eq!(
+            crate::testkit::snapshot(bulk.build().triangulate()),
+            crate::testkit::snapshot(one_by_one.build().triangulate())
+        );
+    }
+
+    #[test]
+    fn test_extend_holes() {
+        let outer = vec![
+            Point::new(0., 0.),
+            Point::new(30., 0.),
+            Point::new(30., 10.),
+            Point::new(0., 10.),
+        ];
+        // rings as parsed from text, one per line
+        let text = "2 2, 2 8, 8 8, 8 2\n12 2, 12 8, 18 8, 18 2";
+        let mut builder = SweeperBuilder::new(outer.clone());
+        builder.extend(text.lines().map(|line| {
+            line.split(", ")
+                .map(|xy| {
+                    let (x, y) = xy.split_once(' ').unwrap();
+                    Point::new(x.parse().unwrap(), y.parse().unwrap())
+                })
+                .collect::<Vec<_>>()
+        }));
+        let borrowed = [
+            Point::new(22., 2.),
+            Point::new(22., 8.),
+            Point::new(28., 8.),
+            Point::new(28., 2.),
+        ];
+        builder.extend([&borrowed[..]]);
+        assert_valid_mesh("extend", builder.clone(), 300. - 3. * 36.);
+        assert_eq!(builder.build().triangulate().count(), 20);
+    }
+
+    #[test]
+    fn test_cull_holes() {
+        let square = |x: f64, y: f64, size: f64| {
+            vec![
+                Point::new(x, y),
+                Point::new(x, y + size),
+                Point::new(x + size, y + size),
+                Point::new(x + size, y),
+            ]
+        };
+        let builder = SweeperBuilder::empty()
+            .cull_holes_smaller_than(1.)
+            .outer(square(0., 0., 100.))
+            .add_holes([
+                square(10., 10., 20.),
+                square(50., 50., 0.5),
+                vec![
+                    Point::new(70., 70.),
+                    Point::new(71., 70.),
+                    Point::new(70., 71.),
+                ],
+            ]);
+        assert_eq!(
+            builder.cull_report(),
+            CullReport {
+                holes_culled: 2,
+                area_culled: 0.75,
+            }
+        );
+        assert_valid_mesh("cull", builder.clone(), 10000. - 400.);
+        // culled holes leave no points behind
+        assert_eq!(builder.build().triangulate().count(), 8);
+    }
+
+    #[test]
+    fn test_from_rings() {
+        let square = |min: f64, max: f64| {
+            vec![
+                Point::new(min, min),
+                Point::new(max, min),
+                Point::new(max, max),
+                Point::new(min, max),
+            ]
+        };
+        let mut hole = square(20., 80.);
+        hole.reverse();
+        // touches the outer ring at its first vertex
+        let corner = vec![Point::new(0., 0.), Point::new(5., 10.), Point::new(10., 5.)];
+        let rings = vec![hole, corner, square(40., 60.), square(0., 100.)];
+
+        let builder = SweeperBuilder::from_rings(rings).unwrap();
+        assert_eq!(builder.rings.len(), 4);
+        assert_eq!(builder.rings[0].len(), 4);
+        // outer, hole, island inside the hole, corner hole
+        assert_valid_mesh("from_rings", builder, 10000. - 3600. + 400. - 37.5);
+
+        assert_eq!(
+            SweeperBuilder::from_rings([square(0., 1.), square(2., 3.)]).err(),
+            Some(RingsError::MultipleOuter(vec![0, 1]))
+        );
+        assert_eq!(
+            SweeperBuilder::from_rings([]).err(),
+            Some(RingsError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let report = SweeperBuilder::default()
+            .outer(vec![])
+            .try_build()
+            .err()
+            .unwrap();
+        assert_eq!(report.problems, [InputError::Empty(0)]);
+        let line = SweeperBuilder::new(vec![Point::new(0., 0.), Point::new(1., 1.)]);
+        assert_eq!(
+            line.try_build().err().unwrap().problems,
+            [InputError::Empty(2)]
+        );
+
+        let sweeper = SweeperBuilder::default().outer(vec![]).build();
+        let (head, tail) = (
+            sweeper.points.head.get(&sweeper.points),
+            sweeper.points.tail.get(&sweeper.points),
+        );
+        assert!(head.x.is_finite() && tail.x.is_finite());
+        assert_eq!(sweeper.triangulate().count(), 0);
+
+        let triangles = SweeperBuilder::default()
+            .with_config(SweepConfig::default().monotone_fast_path(false))
+            .outer(vec![
+                Point::new(0., 0.),
+                Point::new(1., 0.),
+                Point::new(0., 1.),
+            ])
+            .try_build()
+            .unwrap()
+            .triangulate();
+        assert_eq!(triangles.count(), 1);
+    }
+
+    #[test]
+    fn test_non_finite() {
+        let builder = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., f64::INFINITY),
+            Point::new(0., 10.),
+        ])
+        .add_steiner_points([Point::new(5., 5.), Point::new(f64::NAN, 5.)])
+        .add_hole(vec![
+            Point::new(2., 2.),
+            Point::new(3., f64::NEG_INFINITY),
+            Point::new(3., 3.),
+        ]);
+        let report = builder.try_build().err().unwrap();
+        assert_eq!(report.problems, [InputError::NonFinite(vec![2, 5, 7])]);
+
+        let sweeper = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+        ])
+        .try_build()
+        .unwrap();
+        assert_eq!(sweeper.triangulate().count(), 1);
+    }
+
+    #[test]
+    fn test_typed_builder() {
+        let outer = vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ];
+        let slit = vec![Point::new(2., 3.), Point::new(8., 6.)];
+        let builder = SweeperBuilder::empty()
+            .with_config(SweepConfig::default().monotone_fast_path(false))
+            .degenerate_hole(DegenerateHole::Drop)
+            .outer(outer)
+            .add_hole(slit);
+        let sweeper = builder.build();
+        assert!(!sweeper.config().monotone_fast_path);
+        // slit is dropped
+        assert_eq!(sweeper.triangulate().count(), 2);
+    }
+
+    #[test]
+    fn test_repair_input() {
+        let points = try_load_from_file("test_data/bird.dat").unwrap();
+        let mut messy = points.clone();
+        messy.reverse();
+        messy.insert(10, messy[10]);
+        messy.push(messy[0]);
+
+        let (builder, report) = SweeperBuilder::new(messy)
+            .add_steiner_point(points[3])
+            .repair_input(RepairOptions::default());
+        assert_eq!(
+            report,
+            RepairReport {
+                points_merged: 3,
+                rings_reoriented: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(builder.build().triangulate().count(), 273);
+
+        // hole touching outer at (5, 0) splits the outer edge
+        let (builder, report) = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_hole(vec![
+            Point::new(5., 0.),
+            Point::new(6., 2.),
+            Point::new(4., 2.),
+        ])
+        .repair_input(RepairOptions::default());
+        assert_eq!(report.edges_split, 1);
+        let area = builder
+            .build()
+            .triangulate()
+            .map(|t| {
+                let [a, b, c] = t.points;
+                ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() / 2.
+            })
+            .sum::<f64>();
+        assert_eq!(area, 98.);
+    }
+
+    #[test]
+    fn test_merge_duplicates() {
+        let triangles = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 1e-9),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+            Point::new(0., 0.),
+        ])
+        .add_hole(vec![
+            Point::new(4., 4.),
+            Point::new(6., 4.),
+            Point::new(6., 4. + 1e-9),
+            Point::new(5., 6.),
+        ])
+        .add_steiner_points([
+            Point::new(5., 8.),
+            Point::new(5., 8. + 1e-9),
+            Point::new(0., 0.),
+        ])
+        .merge_duplicates(1e-6)
+        .build()
+        .triangulate();
+        assert!(triangles.validate().is_ok());
+        assert_eq!(
+            triangles.merged_vertices(),
+            [0, 1, 1, 2, 3, 0, 4, 5, 5, 6, 7, 7, 0]
+        );
+        assert_eq!(triangles.vertex_count(), 8);
+        // 7 ring points, a hole and a steiner point
+        assert_eq!(triangles.triangle_count(), 9);
+        let area = triangles
+            .map(|t| {
+                let [a, b, c] = t.points;
+                ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() / 2.
+            })
+            .sum::<f64>();
+        assert!((area - 98.).abs() < 1e-6);
+
+        // nothing to merge
+        let triangles = datasets::uniform(20, 1)
+            .merge_duplicates(0.)
+            .build()
+            .triangulate();
+        assert!(triangles.merged_vertices().is_empty());
+    }
+
+    #[test]
+    fn test_clip_to() {
+        let square = |min: f64, max: f64| {
+            vec![
+                Point::new(min, min),
+                Point::new(max, min),
+                Point::new(max, max),
+                Point::new(min, max),
+            ]
+        };
+        let builder = SweeperBuilder::new(square(0., 100.))
+            .add_hole(square(40., 60.))
+            .add_steiner_points([Point::new(70., 10.), Point::new(10., 10.)]);
+
+        // the hole corner cut by the window becomes a notch of the outer ring
+        let clipped = builder
+            .clone()
+            .clip_to(Point::new(50., -10.), Point::new(150., 50.));
+        assert_eq!(clipped.rings.len(), 1);
+        assert_eq!(clipped.rings[0].len(), 6);
+        assert_eq!(clipped.points_builder.len(), 7);
+        assert_valid_mesh("clip_to", clipped, 2500. - 100.);
+
+        // window inside the hole
+        let clipped = builder
+            .clone()
+            .clip_to(Point::new(45., 45.), Point::new(55., 55.));
+        assert_eq!(clipped.build().triangulate().count(), 0);
+
+        // window covering all keeps the input
+        let clipped = builder.clip_to(Point::new(-1., -1.), Point::new(101., 101.));
+        assert_eq!(clipped.rings.len(), 2);
+        assert_valid_mesh("clip_to_all", clipped, 10000. - 400.);
+    }
+
+    #[test]
+    fn test_with_transform() {
+        let builder = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_hole(vec![
+            Point::new(2., 2.),
+            Point::new(2., 4.),
+            Point::new(4., 4.),
+            Point::new(4., 2.),
+        ])
+        .add_steiner_points([Point::new(7., 3.), Point::new(6., 8.)]);
+
+        // mirrored, so edges change which end is lower
+        let transform = Affine::rotate(0.7)
+            .then(&Affine::scale(-3., 0.5))
+            .then(&Affine::translate(100., 5.));
+        let transformed = builder.clone().with_transform(transform);
+        assert_valid_mesh("with_transform", transformed.clone(), 100. - 4.);
+
+        let mut positions = vec![0.; 20];
+        let triangles = transformed.build().triangulate();
+        assert_eq!(triangles.write_positions_into(&mut positions), 20);
+        let expected = builder.build().triangulate();
+        for index in 0..10 {
+            let (p, q) = (triangles.vertex(index), expected.vertex(index));
+            assert!((p.x - q.x).abs() < 1e-12 && (p.y - q.y).abs() < 1e-12);
+            assert_eq!(positions[index * 2], p.x as f32);
+        }
+    }
+
+    #[test]
+    fn test_sweep_axis() {
+        let mut rng = rand::thread_rng();
+        let strip = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(1000., 0.),
+            Point::new(1000., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_hole(vec![
+            Point::new(500., 2.),
+            Point::new(500., 8.),
+            Point::new(520., 5.),
+        ])
+        .add_steiner_points(
+            (0..500).map(|_| Point::new(rng.gen_range(1.0..999.), rng.gen_range(0.5..1.5))),
+        );
+        let with_axis = |axis| {
+            strip
+                .clone()
+                .with_config(SweepConfig::default().sweep_axis(axis))
+        };
+        assert!(!with_axis(SweepAxis::Y).sweeps_along_x());
+        assert!(with_axis(SweepAxis::Auto).sweeps_along_x());
+        // a turn makes it tall
+        let turned = with_axis(SweepAxis::Auto).with_transform(Affine::rotate(1.5));
+        assert!(!turned.sweeps_along_x());
+
+        let triangles = |builder: SweeperBuilder| {
+            let triangles = builder.build().triangulate();
+            (
+                sorted_indices(&triangles),
+                triangles.vertex(300),
+                triangles.precision_report(),
+            )
+        };
+        let (along_y, vertex, _) = triangles(with_axis(SweepAxis::Y));
+        let (along_x, turned_vertex, precision) = triangles(with_axis(SweepAxis::X));
+        // same mesh, the turn is exact
+        assert_eq!(along_x, along_y);
+        assert!(turned_vertex.eq(&vertex));
+        assert_eq!(precision.transform, 0.);
+    }
+
+    #[test]
+    fn test_shear_columns() {
+        // a grid of columns, with a hole in the middle
+        let square = |min: f64, max: f64| {
+            vec![
+                Point::new(min, min),
+                Point::new(max, min),
+                Point::new(max, max),
+                Point::new(min, max),
+            ]
+        };
+        let builder = SweeperBuilder::new(square(0., 10.))
+            .add_hole(square(4., 6.))
+            .add_steiner_points(
+                (1..10)
+                    .flat_map(|x| (1..10).map(move |y| Point::new(x as f64, y as f64)))
+                    .filter(|p| !(4. ..=6.).contains(&p.x) || !(4. ..=6.).contains(&p.y)),
+            );
+        let config = SweepConfig::default().shear_columns(true);
+        assert!(builder.points_builder.column_shear(|p| p).is_some());
+
+        let plain = builder.clone().build().triangulate();
+        let sheared = builder.with_config(config).build().triangulate();
+        assert_eq!(sheared.triangle_count(), plain.triangle_count());
+        assert!(sheared.validate().is_ok());
+        // every vertex comes back, up to rounding
+        let precision = sheared.precision_report();
+        assert!(precision.transform < 1e-12);
+        for index in 0..plain.vertex_count() {
+            let (p, q) = (sheared.vertex(index), plain.vertex(index));
+            assert!((p.x - q.x).abs() <= precision.transform && p.y == q.y);
+        }
+
+        // nothing to split
+        let triangle = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(1., 0.),
+            Point::new(0., 1.),
+        ]);
+        assert!(triangle.points_builder.column_shear(|p| p).is_none());
+    }
+
+    #[test]
+    fn test_duplicate_edges() {
+        let square = vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ];
+        assert!(SweeperBuilder::new(square.clone())
+            .duplicate_edges()
+            .is_empty());
+
+        // a notch on the bottom edge, and a collapsed hole on the left one
+        let builder = SweeperBuilder::new(square)
+            .add_hole(vec![
+                Point::new(0., 0.),
+                Point::new(10., 0.),
+                Point::new(5., 3.),
+            ])
+            .add_hole(vec![Point::new(0., 10.), Point::new(0., 0.)])
+            .add_hole(vec![
+                Point::new(0., 0.),
+                Point::new(10., 0.),
+                Point::new(5., -3.),
+            ]);
+        assert_eq!(
+            builder.duplicate_edges(),
+            [
+                DuplicateEdge {
+                    vertices: [0, 1],
+                    count: 3,
+                    kept: true,
+                },
+                DuplicateEdge {
+                    vertices: [0, 3],
+                    count: 2,
+                    kept: true,
+                },
+            ]
+        );
+        // the second notch is outside, it flips the bottom edge back
+        let triangles = builder.build().triangulate();
+        let area = (0..triangles.triangle_count())
+            .map(|index| {
+                let [a, b, c] = triangles.triangle(index).unwrap().points;
+                ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)) / 2.
+            })
+            .sum::<f64>();
+        assert_eq!(area, 100. - 15. + 15.);
+    }
+
+    #[test]
+    fn test_ring_tags() {
+        let square = |min: f64, max: f64| {
+            vec![
+                Point::new(min, min),
+                Point::new(max, min),
+                Point::new(max, max),
+                Point::new(min, max),
+            ]
+        };
+        let triangles = SweeperBuilder::new(square(0., 10.))
+            .tag_outer(7)
+            .add_hole(square(1., 2.))
+            // culled, no ring to tag
+            .cull_holes_smaller_than(0.5)
+            .add_tagged_hole(square(3., 3.5), 8)
+            .add_tagged_hole(square(4., 6.), 9)
+            .build()
+            .triangulate();
+
+        assert_eq!(
+            (0..4)
+                .map(|ring| triangles.ring_tag(ring))
+                .collect::<Vec<_>>(),
+            [Some(7), None, Some(9), None]
+        );
+        let mut tagged = [0; 3];
+        for index in 0..triangles.triangle_count() {
+            for edge in 0..3 {
+                let tag = triangles.edge_tag(index, edge);
+                let ring = triangles.edge_origin(index, edge).map(|origin| origin.ring);
+                assert_eq!(tag, ring.and_then(|ring| triangles.ring_tag(ring)));
+                match tag {
+                    Some(7) => tagged[0] += 1,
+                    Some(9) => tagged[1] += 1,
+                    None => tagged[2] += 1,
+                    tag => panic!("{tag:?}"),
+                }
+            }
+        }
+        assert_eq!((tagged[0], tagged[1]), (4, 4));
+    }
+
+    #[test]
+    fn test_constraint_over_hole() {
+        let square = |min: f64, max: f64| {
+            vec![
+                Point::new(min, min),
+                Point::new(max, min),
+                Point::new(max, max),
+                Point::new(min, max),
+            ]
+        };
+        // a road crossing a lake
+        let road = vec![Point::new(1., 5.), Point::new(9., 5.)];
+        for scope in [ConstraintScope::Interior, ConstraintScope::Everywhere] {
+            let triangles = SweeperBuilder::new(square(0., 10.))
+                .add_hole(square(3., 7.))
+                .add_constraint(road.clone(), scope)
+                .build()
+                .triangulate();
+            // road ends, and points splitting the lake shore
+            assert_eq!(triangles.vertex_count(), 12);
+            assert_eq!(triangles.rings[1].len(), 6);
+
+            let area = (0..triangles.triangle_count())
+                .map(|index| {
+                    let t = triangles.triangle(index).unwrap();
+                    let [a, b, c] = t.points;
+                    ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2.
+                })
+                .sum::<f64>();
+            assert!((area - 84.).abs() < 1e-9);
+
+            let mut on_road = 0;
+            for index in 0..triangles.triangle_count() {
+                for edge in 0..3 {
+                    if triangles.edge_polyline(index, edge) == Some(PolylineId::Constraint(0)) {
+                        on_road += 1;
+                    }
+                }
+            }
+            // a triangle on each side of the pieces on land
+            assert_eq!(on_road, 4);
+
+            // the piece over the lake is in the full mesh only when enforced there
+            let over_lake = triangles.triangles.iter().any(|(_, t)| {
+                (0..3).any(|i| {
+                    let (p, q) = (t.points[(i + 1) % 3], t.points[(i + 2) % 3]);
+                    let (p, q) = (p.get(&triangles.points), q.get(&triangles.points));
+                    t.is_constrained(i) && p.y == 5. && q.y == 5. && (p.x - q.x).abs() == 4.
+                })
+            });
+            assert_eq!(over_lake, scope == ConstraintScope::Everywhere);
+        }
+    }
+}
@@ -0,0 +1,1027 @@
+use crate::advancing_front::NodeId;
+use crate::shape::{Edge, Point};
+use crate::triangles::TriangleId;
+use crate::utils::{in_scan_area, Orientation};
+use crate::{Context, PointId};
+
+use super::{EdgeError, Observer, Sweeper};
+
+/// The constrained edge of a running edge event, see [`Observer::edge_step`]
+#[derive(Debug)]
+pub struct ConstrainedEdge {
+    constrained_edge: Edge,
+    p: Point,
+    q: Point,
+    /// Whether the constrained edge is "right" edge, p.x larger than q.x
+    right: bool,
+}
+
+impl ConstrainedEdge {
+    /// Point ids of the edge, p is the lower end
+    pub fn edge(&self) -> Edge {
+        self.constrained_edge
+    }
+
+    /// Whether p is right of q, fills go to the right of q then
+    pub fn is_right(&self) -> bool {
+        self.right
+    }
+
+    fn p_id(&self) -> PointId {
+        self.constrained_edge.p
+    }
+
+    fn q_id(&self) -> PointId {
+        self.constrained_edge.q
+    }
+
+    fn with_q(&self, q: PointId, context: &Context) -> Self {
+        let q_point = q.get(context.points);
+        Self {
+            constrained_edge: Edge {
+                p: self.constrained_edge.p,
+                q,
+            },
+            p: self.p,
+            q: q_point,
+            right: self.p.x > q_point.x,
+        }
+    }
+}
+
+/// EdgeEvent related methods
+impl Sweeper {
+    /// Process the edge event for `edge`, `node_id` is the node for `edge.q`.
+    /// Returns the node's latest [`NodeId`]
+    pub(super) fn edge_event(
+        edge: Edge,
+        node_id: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) -> NodeId {
+        let p = edge.p.get(context.points);
+        let q = node_id.point();
+
+        let constrain_edge = ConstrainedEdge {
+            constrained_edge: edge,
+            p,
+            q,
+            right: p.x > q.x,
+        };
+
+        {
+            // check and fill
+            let node = context
+                .advancing_front
+                .get_node_with_id_and_cache(node_id)
+                .unwrap();
+
+            let triangle_id = node.triangle.unwrap();
+            let node_id = node.get_node_id();
+            if Self::try_mark_edge_for_triangle(edge.p, edge.q, triangle_id, context) {
+                // the edge is already an edge of the triangle, return
+                return node_id;
+            }
+
+            // for now we will do all needed filling
+            Self::fill_edge_event(&constrain_edge, node_id, context, observer);
+        }
+
+        // node's triangle may changed, get the latest
+        // filling may delete nodes on both sides, the id lookup falls back to search
+        // when index hint is stale
+        let (node_id, triangle) = {
+            let node = context
+                .advancing_front
+                .get_node_with_id_and_cache(node_id)
+                .unwrap();
+            (node.get_node_id(), node.triangle.unwrap())
+        };
+
+        // this triangle crosses constraint so let's flippin start!
+        let mut triangle_ids = std::mem::take(&mut context.triangle_id_queue);
+        if let Err(error) = Self::edge_event_process(
+            edge.p,
+            edge.q,
+            &constrain_edge,
+            triangle,
+            edge.q,
+            &mut triangle_ids,
+            context,
+            observer,
+        ) {
+            // flips done so far still need legalize, the mesh is otherwise fine
+            context.edge_errors.push(error);
+        }
+
+        for triangle_id in triangle_ids.drain(..) {
+            Self::legalize(triangle_id, context, observer);
+        }
+        context.triangle_id_queue = triangle_ids;
+
+        // legalize only updates node's triangle, the node's index is not affected
+        node_id
+    }
+
+    /// try mark edge for triangle if the constrained edge already is a edge
+    /// returns `true` if yes, otherwise `false`
+    fn try_mark_edge_for_triangle(
+        p: PointId,
+        q: PointId,
+        t_id: TriangleId,
+        context: &mut Context,
+    ) -> bool {
+        let triangle = context.triangles.get_mut_unchecked(t_id);
+        let Some(index) = triangle.edge_index(p, q) else {
+            return false;
+        };
+
+        Self::mark_constrained_edge(t_id, index, context);
+        true
+    }
+
+    /// mark edge `index` of the triangle constrained, on both sides of the edge
+    fn mark_constrained_edge(t_id: TriangleId, index: usize, context: &mut Context) {
+        let triangle = context.triangles.get_mut_unchecked(t_id);
+        triangle.set_constrained(index, true);
+        let neighbor_t_id = triangle.neighbors[index];
+        if !neighbor_t_id.invalid() {
+            let ot = context.triangles.get_mut_unchecked(neighbor_t_id);
+            let index = ot.neighbor_index(t_id);
+            ot.set_constrained(index, true);
+        }
+    }
+
+    fn fill_edge_event(
+        edge: &ConstrainedEdge,
+        node_id: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) {
+        if edge.right {
+            Self::fill_right_above_edge_event(edge, node_id, context, observer);
+        } else {
+            Self::fill_left_above_edge_event(edge, node_id, context, observer);
+        }
+    }
+
+    fn fill_right_above_edge_event(
+        edge: &ConstrainedEdge,
+        node_id: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) {
+        observer.edge_step(edge, context);
+        let mut node_id = node_id;
+        while let Some(next_node) = context.advancing_front.locate_next_node(node_id) {
+            if next_node.point().x >= edge.p.x {
+                break;
+            }
+
+            // check if next node is below the edge
+            if context
+                .predicates
+                .orient_2d(edge.q, next_node.point(), edge.p)
+                .is_ccw()
+            {
+                Self::fill_right_below_edge_event(edge, node_id, context, observer);
+            } else {
+                // try next node
+                node_id = next_node.get_node_id();
+            }
+        }
+    }
+
+    fn fill_right_below_edge_event(
+        edge: &ConstrainedEdge,
+        node_id: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) {
+        observer.edge_step(edge, context);
+        if node_id.point().x >= edge.p.x {
+            return;
+        }
+
+        let node = context.advancing_front.get_node_with_id(node_id).unwrap();
+
+        let next_node = node.next().unwrap();
+        let next_next_node = next_node.next().unwrap();
+
+        if context
+            .predicates
+            .orient_2d(node.point(), next_node.point(), next_next_node.point())
+            .is_ccw()
+        {
+            // concave
+            Self::fill_right_concave_edge_event(edge, node_id, context, observer);
+        } else {
+            // convex
+            Self::fill_right_convex_edge_event(edge, node_id, context, observer);
+
+            // retry this one
+            Self::fill_right_below_edge_event(edge, node_id, context, observer);
+        }
+    }
+
+    /// recursively fill concave nodes
+    fn fill_right_concave_edge_event(
+        edge: &ConstrainedEdge,
+        node_id: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) {
+        observer.edge_step(edge, context);
+        let next_id = {
+            let next_node = context.advancing_front.locate_next_node(node_id).unwrap();
+            let next_id = next_node.get_node_id();
+            match Self::fill_one(next_id, context, observer) {
+                None => {
+                    // nothing changed
+                    next_id
+                }
+                Some(fill_one) => fill_one.next,
+            }
+        };
+
+        if next_id.point_id() != edge.p_id() {
+            // next above or below edge?
+            if context
+                .predicates
+                .orient_2d(edge.q, next_id.point(), edge.p)
+                .is_ccw()
+            {
+                let next_next_node = context.advancing_front.locate_next_node(next_id).unwrap();
+
+                //  below
+                if context
+                    .predicates
+                    .orient_2d(node_id.point(), next_id.point(), next_next_node.point())
+                    .is_ccw()
+                {
+                    // next is concave
+                    Self::fill_right_concave_edge_event(edge, node_id, context, observer);
+                } else {
+                    // next is convex
+                }
+            }
+        }
+    }
+
+    fn fill_right_convex_edge_event(
+        edge: &ConstrainedEdge,
+        node_id: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) {
+        observer.edge_step(edge, context);
+        let next_node = context.advancing_front.locate_next_node(node_id).unwrap();
+        let next_next_node = next_node.next().unwrap();
+        let next_next_next_node = next_next_node.next().unwrap();
+        // next concave or convex?
+        if context
+            .predicates
+            .orient_2d(
+                next_node.point(),
+                next_next_node.point(),
+                next_next_next_node.point(),
+            )
+            .is_ccw()
+        {
+            // concave, fill next next, which is the concave one
+            Self::fill_right_concave_edge_event(edge, next_node.get_node_id(), context, observer);
+        } else {
+            // convex
+            // next above or below edge?
+            if context
+                .predicates
+                .orient_2d(edge.q, next_next_node.point(), edge.p)
+                .is_ccw()
+            {
+                // Below
+                Self::fill_right_convex_edge_event(
+                    edge,
+                    next_node.get_node_id(),
+                    context,
+                    observer,
+                );
+            } else {
+                // Above
+            }
+        }
+    }
+
+    fn fill_left_above_edge_event(
+        edge: &ConstrainedEdge,
+        node_id: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) {
+        observer.edge_step(edge, context);
+        let mut node_id = node_id;
+        while let Some(prev_node) = context.advancing_front.locate_prev_node(node_id) {
+            // check if next node is below the edge
+            if prev_node.point().x <= edge.p.x {
+                break;
+            }
+
+            if context
+                .predicates
+                .orient_2d(edge.q, prev_node.point(), edge.p)
+                .is_cw()
+            {
+                Self::fill_left_below_edge_event(edge, node_id, context, observer);
+            } else {
+                node_id = prev_node.get_node_id();
+            }
+        }
+    }
+
+    fn fill_left_below_edge_event(
+        edge: &ConstrainedEdge,
+        node_id: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) {
+        observer.edge_step(edge, context);
+        if node_id.point().x > edge.p.x {
+            let prev_node = context.advancing_front.locate_prev_node(node_id).unwrap();
+            let prev_prev_node = prev_node.prev().unwrap();
+            if context
+                .predicates
+                .orient_2d(node_id.point(), prev_node.point(), prev_prev_node.point())
+                .is_cw()
+            {
+                Self::fill_left_concave_edge_event(edge, node_id, context, observer);
+            } else {
+                // convex
+                Self::fill_left_convex_edge_event(edge, node_id, context, observer);
+
+                // retry this one
+                Self::fill_left_below_edge_event(edge, node_id, context, observer);
+            }
+        }
+    }
+
+    fn fill_left_convex_edge_event(
+        edge: &ConstrainedEdge,
+        node_id: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) {
+        observer.edge_step(edge, context);
+        // next concave or convex?
+        let prev_node = context.advancing_front.locate_prev_node(node_id).unwrap();
+        let prev_prev_node = prev_node.prev().unwrap();
+        let prev_prev_prev_node = prev_prev_node.prev().unwrap();
+
+        if context
+            .predicates
+            .orient_2d(
+                prev_node.point(),
+                prev_prev_node.point(),
+                prev_prev_prev_node.point(),
+            )
+            .is_cw()
+        {
+            // concave
+            Self::fill_left_concave_edge_event(edge, prev_node.get_node_id(), context, observer);
+        } else {
+            // convex
+            // next above or below edge?
+            if context
+                .predicates
+                .orient_2d(edge.q, prev_prev_node.point(), edge.p)
+                .is_cw()
+            {
+                // below
+                Self::fill_left_convex_edge_event(edge, prev_node.get_node_id(), context, observer);
+            } else {
+                // above
+            }
+        }
+    }
+
+    fn fill_left_concave_edge_event(
+        edge: &ConstrainedEdge,
+        node_id: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) {
+        observer.edge_step(edge, context);
+        let prev_node = context.advancing_front.locate_prev_node(node_id).unwrap();
+
+        let prev_node_id = prev_node.get_node_id();
+
+        let prev_node_id = match Self::fill_one(prev_node_id, context, observer) {
+            Some(fill_one) => fill_one.prev,
+            None => prev_node_id,
+        };
+
+        if prev_node_id.point_id() != edge.p_id() {
+            // next above or below edge?
+            if context
+                .predicates
+                .orient_2d(edge.q, prev_node_id.point(), edge.p)
+                .is_cw()
+            {
+                let prev_node = context
+                    .advancing_front
+                    .get_node_with_id(prev_node_id)
+                    .unwrap();
+                // below
+                let prev_prev_node = prev_node.prev().unwrap();
+                if context
+                    .predicates
+                    .orient_2d(node_id.point(), prev_node.point(), prev_prev_node.point())
+                    .is_cw()
+                {
+                    // next is concave
+                    Self::fill_left_concave_edge_event(edge, node_id, context, observer);
+                } else {
+                    // next is convex
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn edge_event_process(
+        ep: PointId,
+        eq: PointId,
+        constrain_edge: &ConstrainedEdge,
+        triangle_id: TriangleId,
+        p: PointId,
+        triangle_ids: &mut Vec<TriangleId>,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) -> Result<(), EdgeError> {
+        if triangle_id.invalid() {
+            // stepped across the mesh border
+            return Err(EdgeError::MissingNeighbor(
+                constrain_edge.constrained_edge,
+                p,
+            ));
+        }
+
+        if Self::try_mark_edge_for_triangle(ep, eq, triangle_id, context) {
+            return Ok(());
+        }
+
+        let triangle = context.triangles.get_mut_unchecked(triangle_id);
+        let p1 = triangle.point_ccw(p);
+        let o1 = context.predicates.orient_2d(
+            eq.get(context.points),
+            p1.get(context.points),
+            ep.get(context.points),
+        );
+
+        if o1.is_collinear() {
+            if let Some(edge_index) = triangle.edge_index(eq, p1) {
+                let neighbor_across_t = triangle.neighbor_across(p);
+                Self::mark_constrained_edge(triangle_id, edge_index, context);
+
+                return Self::edge_event_process(
+                    ep,
+                    p1,
+                    &constrain_edge.with_q(p1, context),
+                    neighbor_across_t,
+                    p1,
+                    triangle_ids,
+                    context,
+                    observer,
+                );
+            } else {
+                panic!("EdgeEvent - collinear points not supported")
+            }
+        }
+
+        let p2 = triangle.point_cw(p);
+        let o2 = context.predicates.orient_2d(
+            eq.get(context.points),
+            p2.get(context.points),
+            ep.get(context.points),
+        );
+        if o2.is_collinear() {
+            if let Some(edge_index) = triangle.edge_index(eq, p2) {
+                let neighbor_across_t = triangle.neighbor_across(p);
+                Self::mark_constrained_edge(triangle_id, edge_index, context);
+
+                return Self::edge_event_process(
+                    ep,
+                    p2,
+                    &constrain_edge.with_q(p2, context),
+                    neighbor_across_t,
+                    p2,
+                    triangle_ids,
+                    context,
+                    observer,
+                );
+            } else {
+                panic!("collinear points not supported");
+            }
+        }
+
+        if o1 == o2 {
+            // need to decide if we are rotating cw or ccw to get to a triangle
+            // that will cross edge
+            let triangle_id = if o1.is_cw() {
+                triangle.neighbor_ccw(p)
+            } else {
+                triangle.neighbor_cw(p)
+            };
+
+            Self::edge_event_process(
+                ep,
+                eq,
+                constrain_edge,
+                triangle_id,
+                p,
+                triangle_ids,
+                context,
+                observer,
+            )
+        } else {
+            Self::flip_edge_event(
+                ep,
+                eq,
+                constrain_edge,
+                triangle_id,
+                p,
+                triangle_ids,
+                context,
+                observer,
+            )
+        }
+    }
+}
+
+/// flip edge related methods
+impl Sweeper {
+    #[allow(clippy::too_many_arguments)]
+    fn flip_edge_event(
+        ep: PointId,
+        eq: PointId,
+        edge: &ConstrainedEdge,
+        triangle_id: TriangleId,
+        p: PointId,
+        legalize_queue: &mut Vec<TriangleId>,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) -> Result<(), EdgeError> {
+        // walks along ep -> eq, one flip per iteration instead of one recursion
+        // per crossed triangle, stack depth doesn't grow with edge length
+        let mut triangle_id = triangle_id;
+        loop {
+            let t = triangle_id.get(context.triangles);
+            let ot_id = t.neighbor_across(p);
+            let Some(ot) = ot_id.try_get(context.triangles) else {
+                return Err(EdgeError::MissingNeighbor(edge.constrained_edge, p));
+            };
+            let op = ot.opposite_point(t, p);
+
+            if in_scan_area(
+                p.get(context.points),
+                t.point_ccw(p).get(context.points),
+                t.point_cw(p).get(context.points),
+                op.get(context.points),
+            ) {
+                // lets rotate shared edge one vertex cw
+                if Self::rotate_triangle_pair(triangle_id, p, ot_id, op, context.triangles) {
+                    Self::map_triangle_to_nodes(triangle_id, context);
+                    Self::map_triangle_to_nodes(ot_id, context);
+                }
+                observer.edge_step(edge, context);
+                // legalize later, both triangles are queued whichever branch below is
+                // taken, so unlike upstream no extra legalize is needed here
+                legalize_queue.extend([triangle_id, ot_id]);
+
+                if p == eq && op == ep {
+                    // the flip made edge ep -> eq. It is the constrained edge, or a
+                    // temporary edge of flip scan, which is left unconstrained so the
+                    // queued legalize is free to flip it
+                    if eq == edge.q_id() && ep == edge.p_id() {
+                        context
+                            .triangles
+                            .get_mut_unchecked(triangle_id)
+                            .set_constrained_for_edge(ep, eq);
+
+                        context
+                            .triangles
+                            .get_mut_unchecked(ot_id)
+                            .set_constrained_for_edge(ep, eq);
+                    }
+                } else {
+                    let o = context.predicates.orient_2d(
+                        eq.get(context.points),
+                        op.get(context.points),
+                        ep.get(context.points),
+                    );
+
+                    triangle_id = Self::next_flip_triangle(o, triangle_id, ot_id, legalize_queue);
+                    continue;
+                }
+            } else {
+                let new_p = Self::next_flip_point(ep, eq, ot_id, op, context);
+                Self::flip_scan_edge_event(
+                    ep,
+                    eq,
+                    edge,
+                    triangle_id,
+                    ot_id,
+                    new_p,
+                    legalize_queue,
+                    context,
+                    observer,
+                )?;
+                return Self::edge_event_process(
+                    ep,
+                    eq,
+                    edge,
+                    triangle_id,
+                    p,
+                    legalize_queue,
+                    context,
+                    observer,
+                );
+            }
+            return Ok(());
+        }
+    }
+
+    fn next_flip_triangle(
+        o: Orientation,
+        t: TriangleId,
+        ot: TriangleId,
+        triangle_ids: &mut Vec<TriangleId>,
+    ) -> TriangleId {
+        if o.is_ccw() {
+            // ot is not crossing edge after flip
+            triangle_ids.push(ot);
+            t
+        } else {
+            // t is not crossing edge after flip
+            triangle_ids.push(t);
+            ot
+        }
+    }
+
+    fn next_flip_point(
+        ep: PointId,
+        eq: PointId,
+        ot: TriangleId,
+        op: PointId,
+        context: &mut Context,
+    ) -> PointId {
+        let o2d = context.predicates.orient_2d(
+            eq.get(context.points),
+            op.get(context.points),
+            ep.get(context.points),
+        );
+
+        let ot = context.triangles.get_unchecked(ot);
+        match o2d {
+            Orientation::CW => {
+                // right
+                ot.point_ccw(op)
+            }
+            Orientation::CCW => {
+                // left
+                ot.point_cw(op)
+            }
+            Orientation::Collinear => {
+                panic!("Opposing point on constrained edge");
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn flip_scan_edge_event(
+        ep: PointId,
+        eq: PointId,
+        edge: &ConstrainedEdge,
+        flip_triangle_id: TriangleId,
+        t_id: TriangleId,
+        p: PointId,
+        triangle_ids: &mut Vec<TriangleId>,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) -> Result<(), EdgeError> {
+        let flip_triangle = flip_triangle_id.get(context.triangles);
+        let p1 = flip_triangle.point_ccw(eq);
+        let p2 = flip_triangle.point_cw(eq);
+
+        // scan across triangles for a point visible from eq, the flip triangle
+        // doesn't change while scanning
+        let (mut t_id, mut p) = (t_id, p);
+        loop {
+            let t = t_id.get(context.triangles);
+            let ot = t.neighbor_across(p);
+            let Some(ot_triangle) = ot.try_get(context.triangles) else {
+                return Err(EdgeError::MissingNeighbor(edge.constrained_edge, p));
+            };
+            let op = ot_triangle.opposite_point(t, p);
+
+            if in_scan_area(
+                eq.get(context.points),
+                p1.get(context.points),
+                p2.get(context.points),
+                op.get(context.points),
+            ) {
+                // flip with new edge op -> eq
+                Self::flip_edge_event(eq, op, edge, ot, op, triangle_ids, context, observer)?;
+
+                // original comment:
+                // TODO: Actually I just figured out that it should be possible to
+                //       improve this by getting the next ot and op before the the above
+                //       flip and continue the flipScanEdgeEvent here
+                //
+                // the flip changes the flip triangle, so the next ot and op would
+                // be scanned from a new flip triangle anyway. The caller restarts
+                // from the flip triangle right after, which is only a few orient
+                // tests, so return here
+                return Ok(());
+            }
+
+            p = Self::next_flip_point(ep, eq, ot, op, context);
+            t_id = ot;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::advancing_front::AdvancingFront;
+    use crate::points::PointsBuilder;
+    use crate::shape::InnerTriangle;
+    use crate::triangles::TriangleStore;
+    use crate::{loader, ObserverMask, SweeperBuilder};
+
+    #[test]
+    fn test_edge_off_mesh() {
+        let mut points = PointsBuilder::default();
+        let p0 = points.add_steiner_point(Point::new(0., 0.));
+        let p1 = points.add_steiner_point(Point::new(2., 0.));
+        let p2 = points.add_steiner_point(Point::new(1., 2.));
+        let p3 = points.add_steiner_point(Point::new(1., -2.));
+        let points = points.build();
+
+        // lone triangle, edge p3 -> p2 leaves it across the border p0 p1
+        let mut triangles = TriangleStore::new();
+        let t0 = triangles.insert(InnerTriangle::new(p2, p0, p1));
+        let mut front = AdvancingFront::new(triangles.get(t0).unwrap(), t0, &points);
+        let mut context = Context::new(&points, &mut triangles, &mut front);
+
+        let edge = Edge { p: p3, q: p2 };
+        let constrain_edge = ConstrainedEdge {
+            constrained_edge: edge,
+            p: p3.get(&points),
+            q: p2.get(&points),
+            right: false,
+        };
+        let mut triangle_ids = vec![];
+        let result = Sweeper::edge_event_process(
+            p3,
+            p2,
+            &constrain_edge,
+            t0,
+            p2,
+            &mut triangle_ids,
+            &mut context,
+            &mut (),
+        );
+        let Err(EdgeError::MissingNeighbor(error_edge, point)) = result else {
+            panic!("expected error, got {result:?}");
+        };
+        assert_eq!((error_edge.p, error_edge.q, point), (p3, p2, p2));
+        assert!(context.triangles.validate(&points).is_ok());
+    }
+    #[test]
+    fn test_edge_step() {
+        #[derive(Default)]
+        struct Steps {
+            mask: ObserverMask,
+            pending: Vec<Edge>,
+            steps: usize,
+            edges_with_steps: usize,
+        }
+
+        impl Observer for Steps {
+            fn mask(&self) -> ObserverMask {
+                self.mask
+            }
+
+            fn edge_step(&mut self, edge: &ConstrainedEdge, _context: &Context) {
+                self.pending.push(edge.edge());
+            }
+
+            fn edge_event(&mut self, edge: Edge, _context: &Context) {
+                // steps of an edge come right before its event, split parts keep p
+                for step in self.pending.iter() {
+                    assert_eq!(step.p, edge.p);
+                }
+                self.steps += self.pending.len();
+                self.edges_with_steps += !self.pending.is_empty() as usize;
+                self.pending.clear();
+            }
+        }
+
+        let builder = loader::load_file("test_data/bird.dat").unwrap();
+        let mut steps = Steps::default();
+        builder
+            .clone()
+            .build()
+            .triangulate_with_observer(&mut steps);
+        assert!(steps.pending.is_empty());
+        assert!(steps.edges_with_steps > 0);
+        assert!(steps.steps >= steps.edges_with_steps);
+
+        let mut masked = Steps {
+            mask: ObserverMask::EDGE_EVENT,
+            ..Default::default()
+        };
+        builder.build().triangulate_with_observer(&mut masked);
+        assert_eq!(masked.steps, 0);
+    }
+
+    /// triangulate, check the result is delaunay, keeps every edge of `rings` and
+    /// covers `area`
+    fn assert_constrained_delaunay(builder: SweeperBuilder, rings: &[Vec<Point>], area: f64) {
+        struct Check(Option<bool>);
+        impl Observer for Check {
+            fn finalized(&mut self, context: &Context) {
+                self.0 = Some(Sweeper::verify_triangles(context));
+            }
+        }
+
+        let mut check = Check(None);
+        let triangles = builder.build().triangulate_with_observer(&mut check);
+        assert_eq!(check.0, Some(true));
+        assert!(triangles.edge_errors().is_empty());
+        let result = triangles.collect::<Vec<_>>();
+
+        let has_edge = |a: &Point, b: &Point| {
+            result.iter().any(|t| {
+                (0..3).any(|i| {
+                    let (p, q) = (&t.points[i], &t.points[(i + 1) % 3]);
+                    (p.eq(a) && q.eq(b)) || (p.eq(b) && q.eq(a))
+                })
+            })
+        };
+        for ring in rings {
+            for (i, a) in ring.iter().enumerate() {
+                let b = &ring[(i + 1) % ring.len()];
+                assert!(has_edge(a, b), "missing edge {a:?} {b:?}");
+            }
+        }
+
+        let result_area = result
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.points;
+                ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() / 2.
+            })
+            .sum::<f64>();
+        assert!((result_area - area).abs() <= area * 1e-9);
+    }
+
+    #[test]
+    fn test_long_constraints() {
+        use rand::SeedableRng;
+
+        let outer = vec![
+            Point::new(0., 0.),
+            Point::new(100., 0.),
+            Point::new(100., 100.),
+            Point::new(0., 100.),
+        ];
+        for seed in 0..20 {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            // long thin sliver, its edges cross many triangles of the point cloud
+            let (y0, y1) = (rng.gen_range(5.0..95.), rng.gen_range(5.0..95.));
+            let hole = vec![
+                Point::new(3., y0),
+                Point::new(97., y1),
+                Point::new(97., y1 + 0.5),
+                Point::new(3., y0 + 0.5),
+            ];
+            let steiner_points = (0..300)
+                .map(|_| Point::new(rng.gen_range(1.0..99.), rng.gen_range(1.0..99.)))
+                .filter(|p| {
+                    // away from the sliver
+                    let y = y0 + (y1 - y0) * (p.x - 3.) / 94.;
+                    p.y < y - 0.1 || p.y > y + 0.6
+                })
+                .collect::<Vec<_>>();
+
+            let builder = SweeperBuilder::new(outer.clone())
+                .add_steiner_points(steiner_points)
+                .add_hole(hole.clone());
+            assert_constrained_delaunay(builder, &[outer.clone(), hole], 10000. - 94. * 0.5);
+        }
+
+        // thin diagonal sliver crossing a grid, cocircular everywhere
+        let hole = vec![
+            Point::new(2.3, 1.7),
+            Point::new(37.6, 36.2),
+            Point::new(37.2, 36.6),
+            Point::new(1.9, 2.1),
+        ];
+        let grid = (1..40)
+            .flat_map(|x| (1..40).map(move |y| Point::new(x as f64 + 0.5, y as f64 + 0.5)))
+            .filter(|p| (p.x - p.y).abs() > 1.5)
+            .collect::<Vec<_>>();
+        let outer = vec![
+            Point::new(0., 0.),
+            Point::new(41., 0.),
+            Point::new(41., 41.),
+            Point::new(0., 41.),
+        ];
+        let hole_area = hole
+            .iter()
+            .zip(hole.iter().cycle().skip(1))
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<f64>()
+            .abs()
+            / 2.;
+        let builder = SweeperBuilder::new(outer.clone())
+            .add_steiner_points(grid)
+            .add_hole(hole.clone());
+        assert_constrained_delaunay(builder, &[outer, hole], 41. * 41. - hole_area);
+    }
+
+    #[test]
+    fn test_spiral_constraints() {
+        use rand::SeedableRng;
+
+        fn distance_to_segment(p: &Point, a: &Point, b: &Point) -> f64 {
+            let (dx, dy) = (b.x - a.x, b.y - a.y);
+            let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / (dx * dx + dy * dy)).clamp(0., 1.);
+            ((p.x - a.x - t * dx).powi(2) + (p.y - a.y - t * dy).powi(2)).sqrt()
+        }
+
+        fn contains(ring: &[Point], p: &Point) -> bool {
+            let mut inside = false;
+            for (a, b) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+                if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x)
+                {
+                    inside = !inside;
+                }
+            }
+            inside
+        }
+
+        let outer = vec![
+            Point::new(0., 0.),
+            Point::new(100., 0.),
+            Point::new(100., 100.),
+            Point::new(0., 100.),
+        ];
+        for (seed, per_turn) in [(0, 9), (1, 12), (2, 16), (3, 24)] {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            // band along an archimedean spiral, 8 apart per turn, 4 turns. Few
+            // vertices per turn make long edges winding through the cloud
+            let start = rng.gen_range(0.0..std::f64::consts::TAU);
+            let arm = |offset: f64| {
+                (0..=4 * per_turn)
+                    .map(|i| {
+                        let angle = std::f64::consts::TAU * i as f64 / per_turn as f64;
+                        let r = 4. + 8. * angle / std::f64::consts::TAU + offset;
+                        Point::new(
+                            50. + r * (angle + start).cos(),
+                            50. + r * (angle + start).sin(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let mut hole = arm(1.);
+            hole.extend(arm(-1.).into_iter().rev());
+
+            let steiner_points = (0..2000)
+                .map(|_| Point::new(rng.gen_range(1.0..99.), rng.gen_range(1.0..99.)))
+                .filter(|p| {
+                    !contains(&hole, p)
+                        && hole
+                            .iter()
+                            .zip(hole.iter().cycle().skip(1))
+                            .all(|(a, b)| distance_to_segment(p, a, b) > 0.05)
+                })
+                .collect::<Vec<_>>();
+            let hole_area = hole
+                .iter()
+                .zip(hole.iter().cycle().skip(1))
+                .map(|(a, b)| a.x * b.y - b.x * a.y)
+                .sum::<f64>()
+                .abs()
+                / 2.;
+
+            let builder = SweeperBuilder::new(outer.clone())
+                .add_steiner_points(steiner_points)
+                .add_hole(hole.clone());
+            assert_constrained_delaunay(builder, &[outer.clone(), hole], 10000. - hole_area);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::advancing_front::{NodeId, NodeRef};
+use crate::shape::InnerTriangle;
+use crate::Context;
+
+use super::{Observer, Sweeper};
+
+/// Fill related methods
+impl Sweeper {
+    /// fill the node with one triangle.
+    /// Note: The moment it filled, advancing_front is modified.
+    /// if the node is covered by another triangle, then it is deleted from advancing_front.
+    /// all following advancing front lookup is affected.
+    /// Returns the new next's point info.
+    pub(super) fn fill_one(
+        node: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) -> Option<FillOne> {
+        let node = context.advancing_front.get_node_with_id(node).unwrap();
+        let prev_node = node.prev()?;
+        let next_node = node.next()?;
+        let prev_id = prev_node.get_node_id();
+
+        let new_triangle = context.triangles.insert(InnerTriangle::new(
+            prev_node.point_id(),
+            node.point_id(),
+            next_node.point_id(),
+        ));
+
+        context
+            .triangles
+            .mark_neighbor(new_triangle, prev_node.triangle.unwrap());
+        context
+            .triangles
+            .mark_neighbor(new_triangle, node.triangle.unwrap());
+
+        // update prev_node's triangle to newly created and delete the node.
+        // node is covered by new triangle.
+        // safety: prev_node and node is valid till this point, advanceing_front can not changed
+        //       under the hood, so the index is still valid
+        unsafe {
+            context
+                .advancing_front
+                .update_and_delete(prev_id, new_triangle, node.get_node_id())
+        };
+
+        // After fill and legalize, next's node won't change. So we save a version here
+        // why: Most of time, after fill, external code needs to query the new
+        //      next/prev and check whether more work needs to do. The checking logic
+        //      only requires point info.
+        // The ids are taken after the delete, so their index hints stay valid.
+        let fill_one_result = {
+            let prev_node = context.advancing_front.get_node_with_id(prev_id).unwrap();
+            let next_node = prev_node.next().unwrap();
+            FillOne {
+                prev: prev_node.get_node_id(),
+                next: next_node.get_node_id(),
+            }
+        };
+
+        // legalize works on existing triangles, no new triangle will be created
+        // that ganrentees next point won't change
+        Self::legalize(new_triangle, context, observer);
+
+        Some(fill_one_result)
+    }
+
+    /// Fill holes and basins around the node, returns the node's latest [`NodeId`]
+    pub(super) fn fill_advancing_front(
+        node_id: NodeId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) -> NodeId {
+        {
+            // fill right holes
+            let mut node_id = node_id;
+            while let Some(next_node) = context.advancing_front.locate_next_node(node_id) {
+                if next_node.next().is_some() {
+                    // if HoleAngle exceeds 90 degrees then break
+                    if Self::large_hole_dont_fill(&next_node) {
+                        break;
+                    }
+                    let next_node_id = next_node.get_node_id();
+
+                    node_id = match Self::fill_one(next_node_id, context, observer) {
+                        Some(fill_one) => fill_one.next,
+                        None => next_node_id,
+                    };
+                } else {
+                    break;
+                }
+            }
+        }
+
+        // fill left holes, each fill deletes the prev node, so node_id is
+        // refreshed from fill result to keep its index hint valid
+        let mut node_id = node_id;
+        while let Some(prev_node) = context.advancing_front.locate_prev_node(node_id) {
+            if prev_node.prev().is_some() {
+                // if HoleAngle exceeds 90 degrees then break
+                if Self::large_hole_dont_fill(&prev_node) {
+                    break;
+                }
+
+                let prev_node_id = prev_node.get_node_id();
+                node_id = match Self::fill_one(prev_node_id, context, observer) {
+                    Some(fill_one) => fill_one.next,
+                    None => prev_node_id,
+                };
+            } else {
+                break;
+            }
+        }
+
+        // fill right basins, basin is on right side, node's index is not affected
+        if Self::basin_angle_satisfy(node_id, context) {
+            Self::fill_basin(node_id, context, observer);
+        }
+
+        node_id
+    }
+
+    fn large_hole_dont_fill(node: &NodeRef) -> bool {
+        let next_node = node.next().unwrap();
+        let prev_node = node.prev().unwrap();
+
+        let angle = crate::utils::Angle::new(node.point(), next_node.point(), prev_node.point());
+        if angle.exceeds_90_degree() {
+            return false;
+        }
+        if angle.is_negative() {
+            return true;
+        }
+
+        true
+    }
+}
+
+pub(super) struct FillOne {
+    pub(super) prev: NodeId,
+    pub(super) next: NodeId,
+}
@@ -0,0 +1,62 @@
+use crate::triangles::TriangleId;
+use crate::Context;
+
+use super::Sweeper;
+
+/// Finalization, after all points are swept
+impl Sweeper {
+    /// Mark triangles inside the polygon as interior. A triangle is inside when an
+    /// odd number of constrained edges separate it from the outside, i.e. the area
+    /// beyond the mesh border. Unlike a flood from one inner triangle, it handles
+    /// interiors split into pieces, e.g. by a hole touching the outer polyline at
+    /// two vertices.
+    pub(crate) fn finalize_polygon(context: &mut Context) {
+        let mut depth = vec![u32::MAX; context.triangles.len()];
+        // triangles at current depth, and ones behind a constrained edge
+        let mut current = Vec::<TriangleId>::new();
+        let mut next = Vec::<TriangleId>::new();
+        for (t, tri) in context.triangles.iter() {
+            for i in 0..3 {
+                if tri.neighbors[i].invalid() {
+                    if tri.is_constrained(i) {
+                        next.push(t);
+                    } else {
+                        current.push(t);
+                    }
+                }
+            }
+        }
+
+        let mut level = 0;
+        while !current.is_empty() || !next.is_empty() {
+            while let Some(t) = current.pop() {
+                if depth[t.as_usize()] != u32::MAX {
+                    continue;
+                }
+                depth[t.as_usize()] = level;
+
+                let tri = context.triangles.get_unchecked(t);
+                for i in 0..3 {
+                    let neighbor = tri.neighbors[i];
+                    if neighbor.invalid() || depth[neighbor.as_usize()] != u32::MAX {
+                        continue;
+                    }
+                    if tri.is_constrained(i) {
+                        next.push(neighbor);
+                    } else {
+                        current.push(neighbor);
+                    }
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+            level += 1;
+        }
+
+        for (index, tri) in context.triangles.iter_mut().enumerate() {
+            if depth[index] != u32::MAX && depth[index] % 2 == 1 {
+                tri.interior = true;
+                context.result.push(TriangleId::from_index(index));
+            }
+        }
+    }
+}
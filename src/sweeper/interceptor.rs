@@ -0,0 +1,187 @@
+use crate::shape::Point;
+use crate::utils::orient;
+use crate::{Context, PointId, Triangles};
+
+use super::{Observer, Sweeper};
+
+/// What the sweep does with the next point, see [`Interceptor::before_point`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Decision {
+    #[default]
+    Continue,
+    /// leave the point out of the mesh, for points on no edge only, others are
+    /// swept as usual
+    SkipPoint,
+    /// stop the sweep, nothing is returned
+    AbortSweep,
+    /// sweep the point, then fill every dip of the advancing front, not only
+    /// the ones next to the point
+    ForceFill,
+}
+
+/// [`Observer`] whose callbacks steer the sweep, e.g. ignore points inside an
+/// exclusion zone without filtering the input. Run by
+/// [`Sweeper::triangulate_with_interceptor`].
+#[allow(unused_variables)]
+pub trait Interceptor: Observer {
+    /// Called before the point event of `point_id`. `point` is in sweep
+    /// coordinates, same as `context.points`.
+    fn before_point(&mut self, point_id: PointId, point: Point, context: &Context) -> Decision {
+        Decision::Continue
+    }
+}
+
+impl Sweeper {
+    /// Run triangulate, asking `interceptor` before each point event. Observer
+    /// events go to it too, [`Observer::mask`] is not read. `None` if it
+    /// aborted the sweep. The monotone fast path is not taken.
+    pub fn triangulate_with_interceptor(
+        self,
+        interceptor: &mut impl Interceptor,
+    ) -> Option<Triangles> {
+        let mut aborted = false;
+        let triangles = self.triangulate_with_stages(|context| {
+            aborted = !Self::sweep_intercepted(context, interceptor);
+            if !aborted {
+                interceptor.sweep_done(context);
+                Self::finalize_polygon(context);
+                interceptor.finalized(context);
+            }
+        });
+        (!aborted).then_some(triangles)
+    }
+
+    /// Sweep points in y order by decisions of `interceptor`, false if aborted
+    fn sweep_intercepted(context: &mut Context, interceptor: &mut impl Interceptor) -> bool {
+        let points = context.points;
+        // endpoints of any edge can't be skipped, edge events need them
+        let mut on_edge = vec![false; points.len()];
+        for (point_id, _, edges) in points.iter() {
+            for p in points.edges(edges) {
+                on_edge[p.as_usize()] = true;
+                on_edge[point_id.as_usize()] = true;
+            }
+        }
+
+        for (point_id, point, edges) in points.iter_point_by_y(1) {
+            let decision = interceptor.before_point(point_id, point, context);
+            match decision {
+                Decision::AbortSweep => return false,
+                Decision::SkipPoint if !on_edge[point_id.as_usize()] => continue,
+                _ => {}
+            }
+            Self::sweep_point(point_id, point, edges, context, interceptor);
+            if decision == Decision::ForceFill {
+                Self::fill_front(context, interceptor);
+            }
+        }
+        true
+    }
+
+    /// Fill each front node lying below the line through its neighbors, left to
+    /// right, stepping back after a fill as the prev node may form a new dip
+    fn fill_front(context: &mut Context, observer: &mut impl Observer) {
+        let mut index = 1;
+        while let Some(node) = context.advancing_front.nth(index) {
+            let (Some(prev), Some(next)) = (node.prev(), node.next()) else {
+                break;
+            };
+            if orient(prev.point(), node.point(), next.point()) > 0. {
+                let node_id = node.get_node_id();
+                Self::fill_one(node_id, context, observer);
+                index = (index - 1).max(1);
+            } else {
+                index += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets;
+
+    /// skips points in a disk, aborts at `abort_at` points, counts point events
+    struct Zone {
+        center: Point,
+        radius: f64,
+        decision: Decision,
+        abort_at: usize,
+        asked: usize,
+        point_events: usize,
+    }
+
+    impl Zone {
+        fn new(radius: f64, decision: Decision) -> Self {
+            Self {
+                center: Point::new(500., 500.),
+                radius,
+                decision,
+                abort_at: usize::MAX,
+                asked: 0,
+                point_events: 0,
+            }
+        }
+    }
+
+    impl Observer for Zone {
+        fn point_event(&mut self, _point_id: PointId, _context: &Context) {
+            self.point_events += 1;
+        }
+    }
+
+    impl Interceptor for Zone {
+        fn before_point(
+            &mut self,
+            _point_id: PointId,
+            point: Point,
+            _context: &Context,
+        ) -> Decision {
+            self.asked += 1;
+            if self.asked >= self.abort_at {
+                return Decision::AbortSweep;
+            }
+            let (dx, dy) = (point.x - self.center.x, point.y - self.center.y);
+            match dx * dx + dy * dy < self.radius * self.radius {
+                true => self.decision,
+                false => Decision::Continue,
+            }
+        }
+    }
+
+    #[test]
+    fn test_interceptor() {
+        let builder = datasets::uniform(200, 3);
+        let mut zone = Zone::new(200., Decision::SkipPoint);
+        let triangles = builder
+            .clone()
+            .build()
+            .triangulate_with_interceptor(&mut zone)
+            .unwrap();
+        assert!(triangles.validate().is_ok());
+        // the lowest point is in the initial triangle and never asked
+        assert_eq!(zone.asked, 203);
+        let skipped = zone.asked - zone.point_events;
+        assert!(skipped > 0);
+        assert_eq!(triangles.count(), 2 * (200 - skipped) + 2);
+
+        // filled early or not, legalization ends in a triangulation of the same points
+        let mut zone = Zone::new(1000., Decision::ForceFill);
+        let triangles = builder
+            .clone()
+            .build()
+            .triangulate_with_interceptor(&mut zone)
+            .unwrap();
+        assert!(triangles.validate().is_ok());
+        assert_eq!(triangles.count(), 402);
+
+        let mut zone = Zone::new(0., Decision::Continue);
+        zone.abort_at = 10;
+        assert!(builder
+            .build()
+            .triangulate_with_interceptor(&mut zone)
+            .is_none());
+        assert_eq!(zone.point_events, 9);
+    }
+}
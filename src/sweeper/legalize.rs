@@ -0,0 +1,465 @@
+use crate::legality::is_illegal;
+use crate::points::Points;
+use crate::shape::InnerTriangle;
+use crate::triangles::{TriangleId, TriangleStore};
+use crate::utils::in_circle_tolerant;
+use crate::{Context, DelaunayFlag, Legality, LegalizeOrder, LegalizeStrategy, PointId};
+
+use super::{Observer, Sweeper};
+
+/// Legalize related methods
+impl Sweeper {
+    /// helper function to check wether triangle is legal
+    pub(super) fn is_legalize(triangle_id: TriangleId, context: &Context) -> [TriangleId; 3] {
+        let mut result = [TriangleId::INVALID; 3];
+        for (point_idx, illegal_neighbor) in result.iter_mut().enumerate() {
+            let triangle = context.triangles.get_unchecked(triangle_id);
+            let opposite_triangle_id = triangle.neighbors[point_idx];
+            let Some(opposite_triangle) = context.triangles.get(opposite_triangle_id) else {
+                continue;
+            };
+
+            let p = triangle.points[point_idx];
+            let op = opposite_triangle.opposite_point(triangle, p);
+            let oi = opposite_triangle.point_index(op).unwrap();
+
+            // only delaunay is verified, a custom legality may not settle locally
+            if opposite_triangle.is_constrained(oi) || context.legality.is_some() {
+                continue;
+            }
+
+            // cocircular points, e.g. a rectangle, may go either way due to rounding,
+            // so only report when it is inside beyond rounding error
+            let inside = unsafe {
+                in_circle_tolerant(
+                    context.points.get_point_uncheck(p),
+                    context.points.get_point_uncheck(triangle.point_ccw(p)),
+                    context.points.get_point_uncheck(triangle.point_cw(p)),
+                    context.points.get_point_uncheck(op),
+                )
+            };
+
+            if inside {
+                *illegal_neighbor = opposite_triangle_id;
+            }
+        }
+
+        result
+    }
+
+    /// legalize the triangle
+    pub(super) fn legalize(
+        triangle_id: TriangleId,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) {
+        observer.will_legalize(triangle_id, context);
+
+        // keeps record of all touched triangles, after legalize finished
+        // need to remap all to the advancing front
+        let mut legalized_triangles = std::mem::take(&mut context.legalize_remap_tids);
+
+        // record the task and who triggered it
+        let mut task_queue = std::mem::take(&mut context.legalize_task_queue);
+        task_queue.push_back(triangle_id);
+        legalized_triangles.push(triangle_id);
+
+        let LegalizeStrategy {
+            order,
+            delaunay_flag,
+        } = context.legalize;
+        let tick = context.tick_legalize();
+        let is_flagged = |triangle: &InnerTriangle, edge_index: usize| {
+            if delaunay_flag == DelaunayFlag::Tick {
+                triangle.is_delaunay_at(edge_index, tick)
+            } else {
+                triangle.is_delaunay(edge_index)
+            }
+        };
+        while let Some(triangle_id) = match order {
+            LegalizeOrder::Lifo => task_queue.pop_back(),
+            LegalizeOrder::Fifo => task_queue.pop_front(),
+        } {
+            for point_idx in 0..3 {
+                let triangle = triangle_id.get(context.triangles);
+                // skip legalize for constrained_edge
+                if triangle.is_constrained(point_idx) || is_flagged(triangle, point_idx) {
+                    continue;
+                }
+
+                let opposite_triangle_id = triangle.neighbors[point_idx];
+                if opposite_triangle_id.invalid() {
+                    continue;
+                };
+                let opposite_triangle = opposite_triangle_id.get(context.triangles);
+
+                let p = triangle.points[point_idx];
+                let op = opposite_triangle.opposite_point(triangle, p);
+
+                let (ccw, cw) = (triangle.point_ccw(p), triangle.point_cw(p));
+                let illegal = is_illegal(
+                    context.legality,
+                    p.get(context.points),
+                    ccw.get(context.points),
+                    cw.get(context.points),
+                    op.get(context.points),
+                    [p, ccw, cw, op],
+                );
+                if illegal {
+                    observer.triangle_rotated(triangle_id, opposite_triangle_id, context);
+                    // rotate shared edge one vertex cw to legalize it
+                    let need_remap = Self::rotate_triangle_pair(
+                        triangle_id,
+                        p,
+                        opposite_triangle_id,
+                        op,
+                        context.triangles,
+                    );
+                    observer.flipped(
+                        triangle_id,
+                        opposite_triangle_id,
+                        [ccw, cw],
+                        [p, op],
+                        context,
+                    );
+
+                    // set the delaunay flag for the edge we just fixed
+                    if delaunay_flag != DelaunayFlag::Off {
+                        let triangles = &mut *context.triangles;
+                        Self::mark_delaunay(triangle_id, opposite_triangle_id, tick, triangles);
+                    }
+
+                    task_queue.push_back(triangle_id);
+                    task_queue.push_back(opposite_triangle_id);
+
+                    if need_remap {
+                        legalized_triangles.push(triangle_id);
+                        legalized_triangles.push(opposite_triangle_id);
+                    }
+                    break;
+                } else if delaunay_flag == DelaunayFlag::Checked {
+                    // not flagged by default, it turns out slower, it means the
+                    // recalculation is not many
+                    let triangles = &mut *context.triangles;
+                    Self::mark_delaunay(triangle_id, opposite_triangle_id, tick, triangles);
+                }
+            }
+
+            observer.legalize_step(triangle_id, context);
+        }
+
+        for triangle_id in legalized_triangles.drain(..) {
+            Self::map_triangle_to_nodes(triangle_id, context);
+        }
+
+        {
+            // give back the task queue
+            context.legalize_task_queue = task_queue;
+            context.legalize_remap_tids = legalized_triangles;
+        }
+
+        observer.legalized(triangle_id, context);
+    }
+
+    /// set the delaunay flag at `tick` on both sides of the edge shared by two
+    /// triangles
+    fn mark_delaunay(
+        t_id: TriangleId,
+        ot_id: TriangleId,
+        tick: u32,
+        triangles: &mut TriangleStore,
+    ) {
+        let (t, ot) = unsafe { triangles.get_mut_two(t_id, ot_id) };
+        let (t_idx, ot_idx) = t.common_edge_index(ot).unwrap();
+        t.set_delaunay_at(t_idx, tick);
+        ot.set_delaunay_at(ot_idx, tick);
+    }
+
+    /// legalize all triangles in store, without advancing front. Used by triangles
+    /// built outside of sweep, e.g. monotone fast path
+    pub(crate) fn legalize_all(triangles: &mut TriangleStore, points: &Points) {
+        let task_queue = triangles.iter().map(|(id, _)| id).collect::<Vec<_>>();
+        Self::flip_illegal(
+            triangles,
+            points,
+            None,
+            task_queue,
+            usize::MAX,
+            |_| true,
+            |_, _| {},
+        );
+    }
+
+    /// Flip illegal edges under `legality` of triangles in `task_queue` and the ones
+    /// flipped with them, skips constrained and delaunay marked edges, and edges to
+    /// triangles not accepted by `flippable`. Stops after `max_flips`, returns flips
+    /// made.
+    pub(super) fn flip_illegal(
+        triangles: &mut TriangleStore,
+        points: &Points,
+        legality: Option<&dyn Legality>,
+        mut task_queue: Vec<TriangleId>,
+        max_flips: usize,
+        flippable: impl Fn(&InnerTriangle) -> bool,
+        mut on_flip: impl FnMut(TriangleId, TriangleId),
+    ) -> usize {
+        let mut flips = 0;
+        while let Some(triangle_id) = task_queue.pop() {
+            if flips >= max_flips {
+                break;
+            }
+            for point_idx in 0..3 {
+                let triangle = triangle_id.get(triangles);
+                if triangle.is_constrained(point_idx) || triangle.is_delaunay(point_idx) {
+                    continue;
+                }
+
+                let opposite_triangle_id = triangle.neighbors[point_idx];
+                if opposite_triangle_id.invalid() {
+                    continue;
+                };
+                let opposite_triangle = opposite_triangle_id.get(triangles);
+                if !flippable(opposite_triangle) {
+                    continue;
+                }
+
+                let p = triangle.points[point_idx];
+                let op = opposite_triangle.opposite_point(triangle, p);
+
+                let (ccw, cw) = (triangle.point_ccw(p), triangle.point_cw(p));
+                let illegal = is_illegal(
+                    legality,
+                    p.get(points),
+                    ccw.get(points),
+                    cw.get(points),
+                    op.get(points),
+                    [p, ccw, cw, op],
+                );
+                if illegal {
+                    Self::rotate_triangle_pair(triangle_id, p, opposite_triangle_id, op, triangles);
+                    flips += 1;
+                    on_flip(triangle_id, opposite_triangle_id);
+
+                    // same as legalize, the fixed edge is not checked again, or cocircular
+                    // points keep flipping each other
+                    Self::mark_delaunay(triangle_id, opposite_triangle_id, 0, triangles);
+
+                    task_queue.push(triangle_id);
+                    task_queue.push(opposite_triangle_id);
+                    break;
+                }
+            }
+        }
+        flips
+    }
+
+    /// Rotate the triangle pair, returns two flag indicate (t, ot) whether candidate for af remap
+    pub(super) fn rotate_triangle_pair(
+        t_id: TriangleId,
+        p: PointId,
+        ot_id: TriangleId,
+        op: PointId,
+        triangles: &mut TriangleStore,
+    ) -> bool {
+        let (t, ot) = unsafe { triangles.get_mut_two(t_id, ot_id) };
+
+        let n1 = t.neighbor_ccw(p);
+        let n2 = t.neighbor_cw(p);
+        let n3 = ot.neighbor_ccw(op);
+        let n4 = ot.neighbor_cw(op);
+
+        let ea1 = t.edge_attr_ccw(p);
+        let ea2 = t.edge_attr_cw(p);
+        let ea3 = ot.edge_attr_ccw(op);
+        let ea4 = ot.edge_attr_cw(op);
+
+        // rotate shared edge one vertex cw to legalize it
+        t.rotate_cw(p, op);
+        ot.rotate_cw(op, p);
+
+        t.set_edge_attr_cw(p, ea2);
+        t.set_edge_attr_ccw(op, ea3);
+        ot.set_edge_attr_ccw(p, ea1);
+        ot.set_edge_attr_cw(op, ea4);
+
+        t.clear_neighbors();
+        ot.clear_neighbors();
+
+        TriangleStore::mark_neighbor_for_two_mut(t_id, ot_id, t, ot);
+
+        let (t, ot, t_n1, t_n2, t_n3, t_n4) =
+            unsafe { triangles.get_mut_six(t_id, ot_id, n1, n2, n3, n4) };
+
+        if let Some(t_n2) = t_n2 {
+            TriangleStore::mark_neighbor_for_two_mut(t_id, n2, t, t_n2);
+        }
+        if let Some(t_n3) = t_n3 {
+            TriangleStore::mark_neighbor_for_two_mut(t_id, n3, t, t_n3);
+        }
+        if let Some(t_n1) = t_n1 {
+            TriangleStore::mark_neighbor_for_two_mut(ot_id, n1, ot, t_n1);
+        }
+        if let Some(t_n4) = t_n4 {
+            TriangleStore::mark_neighbor_for_two_mut(ot_id, n4, ot, t_n4);
+        }
+
+        n1.invalid() || n2.invalid() || n3.invalid() || n4.invalid()
+    }
+
+    /// update advancing front node's triangle
+    pub(super) fn map_triangle_to_nodes(triangle_id: TriangleId, context: &mut Context) {
+        let triangle = triangle_id.get(context.triangles);
+        for i in 0..3 {
+            if triangle.neighbors[i].invalid() {
+                let point = unsafe {
+                    context
+                        .points
+                        .get_point_uncheck(triangle.point_cw(triangle.points[i]))
+                };
+                context.advancing_front.update_triangle(point, triangle_id);
+            }
+        }
+    }
+}
+
+impl Sweeper {
+    pub fn verify_triangles(context: &Context) -> bool {
+        // no collect, it runs after each event in debug builds
+        context.triangles.iter().all(|(t_id, _)| {
+            Self::is_legalize(t_id, context)
+                .iter()
+                .all(|neighbor| neighbor.invalid())
+        })
+    }
+
+    /// verify all triangles stored in context are legal
+    #[allow(unused)]
+    pub fn illegal_triangles(context: &Context) -> Vec<(TriangleId, TriangleId)> {
+        let triangle_ids = context
+            .triangles
+            .iter()
+            .map(|(t_id, _)| t_id)
+            .collect::<Vec<_>>();
+
+        let mut result = Vec::<(TriangleId, TriangleId)>::new();
+
+        for t_id in triangle_ids {
+            for illegal_neighbor in &Self::is_legalize(t_id, context) {
+                if !illegal_neighbor.invalid() {
+                    result.push((t_id, *illegal_neighbor));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::EdgeAttr;
+    use crate::sweeper::tests::{sorted_indices, try_load_from_file};
+    use crate::{stages, Point, Predicates, SweepConfig, SweeperBuilder};
+
+    /// whether triangles are consistent once finalized
+    struct Check(Option<bool>);
+
+    impl Observer for Check {
+        fn finalized(&mut self, context: &Context) {
+            self.0 = Some(Sweeper::verify_triangles(context));
+        }
+    }
+
+    #[test]
+    fn test_legalize_strategies() {
+        for file_path in ["test_data/bird.dat", "test_data/nazca_heron.dat"] {
+            let points = try_load_from_file(file_path).unwrap();
+            let expected = SweeperBuilder::new(points.clone())
+                .build()
+                .triangulate()
+                .count();
+            for order in [LegalizeOrder::Lifo, LegalizeOrder::Fifo] {
+                for flag in [
+                    DelaunayFlag::Flipped,
+                    DelaunayFlag::Checked,
+                    DelaunayFlag::Off,
+                ] {
+                    let strategy = LegalizeStrategy::default().order(order).delaunay_flag(flag);
+                    let mut check = Check(None);
+                    let count = SweeperBuilder::new(points.clone())
+                        .with_config(SweepConfig::default().legalize(strategy))
+                        .build()
+                        .triangulate_with_observer(&mut check)
+                        .count();
+                    assert_eq!(check.0, Some(true), "{file_path} {strategy:?}");
+                    assert_eq!(count, expected, "{file_path} {strategy:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_adaptive_predicates() {
+        // bottom side sampled along a slope, each point a few ulps off it
+        let mut points = (0..=200)
+            .map(|i| {
+                let x = i as f64 * 5.;
+                let y = x * 0.3;
+                let ulps = [0, 1, -2, 3, -1][i % 5];
+                Point::new(x, y + ulps as f64 * (y.next_up() - y))
+            })
+            .collect::<Vec<_>>();
+        points.extend([Point::new(1000., 600.), Point::new(0., 600.)]);
+
+        let mut check = Check(None);
+        let triangles = SweeperBuilder::new(points.clone())
+            .with_config(SweepConfig::default().predicates(Predicates::Adaptive))
+            .build()
+            .triangulate_with_observer(&mut check);
+        assert_eq!(check.0, Some(true));
+        assert!(triangles.validate().is_ok());
+        assert_eq!(triangles.count(), points.len() - 2);
+    }
+    #[test]
+    fn test_tick_wrap() {
+        let points = try_load_from_file("test_data/bird.dat").unwrap();
+        let strategy = LegalizeStrategy::default().delaunay_flag(DelaunayFlag::Tick);
+        let builder =
+            SweeperBuilder::new(points).with_config(SweepConfig::default().legalize(strategy));
+        let expected = sorted_indices(&builder.clone().build().triangulate());
+
+        let period = 1 << EdgeAttr::TICK_BITS;
+        let triangles = builder.build().triangulate_with_stages(|context| {
+            // the stored tick bits wrap during the sweep
+            context.legalize_tick = period - 50;
+            stages::sweep_points(context, &mut ());
+            assert!(context.legalize_tick > period);
+            assert!(Sweeper::verify_triangles(context));
+
+            // a flag of exactly one period ago is stale
+            let (t_id, edge_index) = context
+                .triangles
+                .iter()
+                .find_map(|(t_id, t)| {
+                    (0..3)
+                        .find(|i| !t.is_constrained(*i) && !t.neighbors[*i].invalid())
+                        .map(|i| (t_id, i))
+                })
+                .unwrap();
+            let tick = context.tick_legalize();
+            context
+                .triangles
+                .get_mut_unchecked(t_id)
+                .set_delaunay_at(edge_index, tick);
+            for _ in 0..period {
+                context.tick_legalize();
+            }
+            let triangle = t_id.get(context.triangles);
+            assert!(!triangle.is_delaunay_at(edge_index, context.legalize_tick));
+
+            stages::finalize(context);
+        });
+        assert_eq!(sorted_indices(&triangles), expected);
+    }
+}
@@ -0,0 +1,1072 @@
+use std::sync::OnceLock;
+
+use crate::advancing_front::AdvancingFront;
+use crate::chunked::ChunkedTriangulation;
+use crate::lod::LodMeshes;
+use crate::observer_mask::Masked;
+use crate::points::Points;
+use crate::quadratic::QuadraticMesh;
+use crate::region;
+use crate::triangles::{TriangleId, TriangleStore};
+use crate::{
+    shape::*, Affine, Context, Legality, ObserverMask, PointId, PowerDelaunay, SweepConfig,
+    TriangleSink, TriangleVec,
+};
+
+mod basin;
+mod builder;
+mod edge_event;
+mod fill;
+mod finalize;
+mod interceptor;
+mod legalize;
+mod point_event;
+pub mod stages;
+mod triangles;
+
+pub(crate) use builder::parse_polyline;
+pub use builder::{
+    ConstraintScope, CullReport, DegenerateHole, DuplicateEdge, HasOuter, HoleError, NeedsOuter,
+    RingsError, SweeperBuilder,
+};
+pub use edge_event::ConstrainedEdge;
+pub use interceptor::{Decision, Interceptor};
+pub use triangles::{EdgeError, PrecisionReport, Triangles};
+
+/// Observer for sweeper, used to monitor how sweeper works, quite useful
+/// for visual debugging when things goes wrong. Check example's draw.
+#[allow(unused_variables)]
+pub trait Observer {
+    /// Events to dispatch, read once when the sweep starts. Skipping per event
+    /// calls helps observers which only want milestones of huge sweeps.
+    fn mask(&self) -> ObserverMask {
+        ObserverMask::ALL
+    }
+
+    /// A point_event processed
+    fn point_event(&mut self, point_id: PointId, context: &Context) {}
+
+    /// An edge event processed
+    fn edge_event(&mut self, edge: Edge, context: &Context) {}
+
+    /// A sub step of an edge event, a front fill below the edge or a flip of
+    /// a triangle pair crossing it. `edge` is the one driving the step, the
+    /// part of the input edge left once it got split at a collinear point.
+    #[inline]
+    fn edge_step(&mut self, edge: &ConstrainedEdge, context: &Context) {}
+
+    /// Sweep process done
+    fn sweep_done(&mut self, context: &Context) {}
+
+    /// The result finalized, holes, fake points etc cleaned.
+    fn finalized(&mut self, context: &Context) {}
+
+    /// About to legalize for triangle
+    #[inline]
+    fn will_legalize(&mut self, triangle_id: TriangleId, context: &Context) {}
+
+    /// A single step inside one legalization process
+    #[inline]
+    fn legalize_step(&mut self, triangle_id: TriangleId, context: &Context) {}
+
+    /// A rotate happened
+    #[inline]
+    fn triangle_rotated(
+        &mut self,
+        triangle_id: TriangleId,
+        opposite_triangle_id: TriangleId,
+        context: &Context,
+    ) {
+    }
+
+    /// A legalization flip done, the diagonal of the pair `triangle_id` and
+    /// `opposite_triangle_id` went from `before` to `after`. Called with the
+    /// flipped pair in context, after [`Self::triangle_rotated`].
+    #[inline]
+    fn flipped(
+        &mut self,
+        triangle_id: TriangleId,
+        opposite_triangle_id: TriangleId,
+        before: [PointId; 2],
+        after: [PointId; 2],
+        context: &Context,
+    ) {
+    }
+
+    /// The triangle legalized
+    #[inline]
+    fn legalized(&mut self, triangel_id: TriangleId, context: &Context) {}
+}
+
+/// Default dummy observer, blank impl, so all calls should be optimized out by compiler.
+impl Observer for () {
+    fn mask(&self) -> ObserverMask {
+        ObserverMask::NONE
+    }
+}
+
+/// Main interface, user should grab a new Sweeper by [`SweeperBuilder::build`]
+///
+/// Triangulation only reads the sweeper and state it owns, there are no globals
+/// or interior mutability. Clones triangulated on different threads give the
+/// same result.
+#[derive(Clone)]
+pub struct Sweeper {
+    points: Points,
+    config: SweepConfig,
+    /// only the outer polygon, no holes or steiner points
+    outer_only: bool,
+    /// point ids of outer polyline and holes
+    rings: Vec<Vec<PointId>>,
+    /// point ids of collapsed holes
+    constraints: Vec<Vec<PointId>>,
+    /// tag of each ring, empty if none is tagged
+    ring_tags: Vec<Option<u32>>,
+    /// point weights by id, empty if not weighted
+    weights: Vec<f64>,
+    /// seed points and material ids, scaled
+    region_seeds: Vec<(Point, u32)>,
+    /// collect triangles of holes too
+    triangulate_holes: bool,
+    /// point id of each input point, empty if none merged
+    merged: Vec<u32>,
+    /// factor points are scaled by, see [`SweepConfig::auto_rescale`]
+    scale: f64,
+    /// inverse of [`SweeperBuilder::with_transform`]
+    inverse: Option<Affine>,
+    /// deviation introduced on build
+    precision: PrecisionReport,
+}
+
+// builders, sweepers and results move and share across threads, e.g. between
+// worker threads of a server
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SweeperBuilder>();
+    assert_send_sync::<Sweeper>();
+    assert_send_sync::<Triangles>();
+};
+
+impl Sweeper {
+    /// Config used by this sweeper
+    pub fn config(&self) -> &SweepConfig {
+        &self.config
+    }
+
+    /// Points as they will be swept, after transforms and snapping. Ghost
+    /// points [`Points::head`] and [`Points::tail`] come last, the sweep order
+    /// is [`Points::iter_point_by_y`].
+    pub fn points(&self) -> &Points {
+        &self.points
+    }
+
+    /// Constrained edges to sweep, `p` is the lower point. Rings and
+    /// constraints alike, each once.
+    pub fn edges(&self) -> impl Iterator<Item = Edge> + '_ {
+        self.points
+            .iter()
+            .flat_map(|(q, _, edges)| self.points.edges(edges).map(move |p| Edge { p, q }))
+    }
+
+    /// Run a quick trial sweep over about `sample` points, measure how the advancing
+    /// front grows and churns, then pick backend and buffer capacities for the full run.
+    /// Config is kept unchanged when there are too few points to tell.
+    pub fn auto_tune(mut self, sample: usize) -> Self {
+        if let Some(config) = crate::tune::tune(&self.points, sample) {
+            self.config = config;
+        }
+        self
+    }
+
+    /// Run trianglate with dummy observer
+    /// A monotone polygon without holes and steiner points takes the linear monotone
+    /// algorithm, unless disabled by [`SweepConfig::monotone_fast_path`]
+    pub fn triangulate(self) -> Triangles {
+        if self.config.monotone_fast_path && self.outer_only {
+            if let Some((triangles, result)) =
+                crate::monotone::triangulate(&self.points, self.config.predicates)
+            {
+                let end = result.len();
+                let materials =
+                    region::label_regions(&self.points, &triangles, &result, &self.region_seeds);
+                return Triangles {
+                    points: self.points,
+                    triangles,
+                    result,
+                    rings: self.rings,
+                    constraints: self.constraints,
+                    ring_tags: self.ring_tags,
+                    origins: OnceLock::new(),
+                    walk: OnceLock::new(),
+                    grid: OnceLock::new(),
+                    edge_errors: vec![],
+                    materials,
+                    holes: vec![],
+                    merged: self.merged,
+                    scale: self.scale,
+                    inverse: self.inverse,
+                    precision: self.precision,
+
+                    next: 0,
+                    end,
+                };
+            }
+        }
+        self.triangulate_with_observer(&mut ())
+    }
+
+    /// Run triangulate, then emit result triangles to `sink` one by one instead of
+    /// collecting them
+    pub fn triangulate_into(self, sink: &mut impl TriangleSink) {
+        for triangle in self.triangulate() {
+            sink.emit(&triangle);
+        }
+    }
+
+    /// Run triangulate, then collect the result into an owned [`TriangleVec`] with
+    /// random access
+    pub fn collect_mesh(self) -> TriangleVec {
+        TriangleVec::new(&self.triangulate())
+    }
+
+    /// Run triangulate, then take the result as a vertex buffer and CCW vertex
+    /// indices of each triangle, see [`TriangleVec::into_parts`]
+    pub fn triangulate_indexed(self) -> (Vec<Point>, Vec<[u32; 3]>) {
+        self.collect_mesh().into_parts()
+    }
+
+    /// Run triangulate, then decimate the result into coarser levels, see
+    /// [`LodMeshes::new`] for `keep`
+    pub fn triangulate_lod(self, keep: &[f64]) -> LodMeshes {
+        LodMeshes::new(&self.triangulate(), keep)
+    }
+
+    /// Run triangulate, then add midside nodes into 6-node elements, see
+    /// [`QuadraticMesh`]
+    pub fn triangulate_quadratic(self) -> QuadraticMesh {
+        QuadraticMesh::new(&self.triangulate())
+    }
+
+    /// Run triangulate with observer
+    pub fn triangulate_with_observer(mut self, observer: &mut impl Observer) -> Triangles {
+        if self.weights.is_empty() {
+            return self.run(observer, None);
+        }
+        let power = PowerDelaunay::new(self.take_weights());
+        self.run(observer, Some(&power))
+    }
+
+    /// Run triangulate, flipping edges by `legality` instead of the delaunay in
+    /// circle test. The monotone fast path is not taken.
+    pub fn triangulate_with_legality(self, legality: &dyn Legality) -> Triangles {
+        self.run(&mut (), Some(legality))
+    }
+
+    /// Run triangulate in steps of `chunk_size` point events, see
+    /// [`ChunkedTriangulation`]. The monotone fast path is not taken.
+    pub fn triangulate_chunked(self, chunk_size: usize) -> ChunkedTriangulation {
+        ChunkedTriangulation::new(self, chunk_size)
+    }
+
+    /// Run a custom sweep, `f` drives the [`stages`] on a context holding the
+    /// initial triangle. Triangles in `context.result` are the interior of the
+    /// result, [`stages::finalize`] fills it. `f` isn't called when there is no
+    /// point. The monotone fast path is not taken.
+    pub fn triangulate_with_stages(mut self, f: impl FnOnce(&mut Context)) -> Triangles {
+        if self.points.get_id_by_y(0).is_none() {
+            return self.into_triangles(TriangleStore::new(), vec![], vec![]);
+        }
+        let power = (!self.weights.is_empty()).then(|| PowerDelaunay::new(self.take_weights()));
+        let (mut triangles, mut advancing_front) = self.start();
+
+        let mut context = Context::new(&self.points, &mut triangles, &mut advancing_front);
+        context.legality = power.as_ref().map(|power| power as &dyn Legality);
+        context.legalize = self.config.legalize;
+        context.predicates = self.config.predicates;
+        f(&mut context);
+
+        let result = context.result;
+        let edge_errors = context.edge_errors;
+        self.into_triangles(triangles, result, edge_errors)
+    }
+
+    fn run(self, observer: &mut impl Observer, legality: Option<&dyn Legality>) -> Triangles {
+        if self.points.get_id_by_y(0).is_none() {
+            // no point at all, e.g. a clip window missing the polygon
+            return self.into_triangles(TriangleStore::new(), vec![], vec![]);
+        }
+        let observer = &mut Masked::new(observer);
+        let (mut triangles, mut advancing_front) = self.start();
+
+        let mut context = Context::new(&self.points, &mut triangles, &mut advancing_front);
+        context.legality = legality;
+        context.legalize = self.config.legalize;
+        context.predicates = self.config.predicates;
+
+        Self::sweep_points(&mut context, observer);
+        observer.sweep_done(&context);
+
+        Self::finalize_polygon(&mut context);
+        observer.finalized(&context);
+
+        // take result out of context
+        let result = context.result;
+        let edge_errors = context.edge_errors;
+        self.into_triangles(triangles, result, edge_errors)
+    }
+
+    /// Take point weights, for [`PowerDelaunay`]
+    pub(crate) fn take_weights(&mut self) -> Vec<f64> {
+        std::mem::take(&mut self.weights)
+    }
+
+    /// Triangle store and advancing front with the initial triangle, before any
+    /// point event
+    pub(crate) fn start(&self) -> (TriangleStore, AdvancingFront) {
+        let mut triangles = TriangleStore::with_capacity(
+            self.config
+                .triangle_capacity
+                .unwrap_or(self.points.len() * 3),
+        );
+
+        let initial_triangle = triangles.insert(InnerTriangle::new(
+            self.points.get_id_by_y(0).unwrap(),
+            self.points.head,
+            self.points.tail,
+        ));
+
+        // create the advancing front with initial triangle
+        let advancing_front = AdvancingFront::with_backend(
+            triangles.get(initial_triangle).unwrap(),
+            initial_triangle,
+            &self.points,
+            self.config.front_backend,
+            self.config.front_capacity,
+        );
+        (triangles, advancing_front)
+    }
+
+    /// Result of a finished sweep, `result` are the interior triangles
+    pub(crate) fn into_triangles(
+        self,
+        triangles: TriangleStore,
+        result: Vec<TriangleId>,
+        edge_errors: Vec<EdgeError>,
+    ) -> Triangles {
+        let end = result.len();
+        let materials =
+            region::label_regions(&self.points, &triangles, &result, &self.region_seeds);
+        let holes = match self.triangulate_holes {
+            true => region::hole_triangles(&self.points, &triangles, &self.rings),
+            false => vec![],
+        };
+
+        Triangles {
+            points: self.points,
+            triangles,
+            result,
+            rings: self.rings,
+            constraints: self.constraints,
+            ring_tags: self.ring_tags,
+            origins: OnceLock::new(),
+            walk: OnceLock::new(),
+            grid: OnceLock::new(),
+            edge_errors,
+            materials,
+            holes,
+            merged: self.merged,
+            scale: self.scale,
+            inverse: self.inverse,
+            precision: self.precision,
+
+            next: 0,
+            end,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use rand::Rng;
+
+    use super::*;
+    use crate::{DelaunayFlag, FrontBackend, GhostPoints, InputError, LegalizeStrategy, Triangle};
+
+    #[derive(Default)]
+    struct CacheHitOb {
+        hit_count: u64,
+        mis_count: u64,
+        rotate_count: u64,
+        legalize_step_count: u64,
+        legalize_count: u64,
+        flip_count: u64,
+    }
+
+    impl CacheHitOb {
+        pub fn hit_rate(&self) -> f64 {
+            self.hit_count as f64 / (self.hit_count + self.mis_count) as f64
+        }
+    }
+
+    impl Observer for CacheHitOb {
+        fn legalized(&mut self, _triangel_id: TriangleId, _context: &Context) {
+            self.legalize_count += 1;
+        }
+
+        fn legalize_step(&mut self, _triangle_id: TriangleId, _context: &Context) {
+            self.legalize_step_count += 1;
+        }
+
+        fn triangle_rotated(
+            &mut self,
+            _triangle_id: TriangleId,
+            _opposite_triangle_id: TriangleId,
+            _context: &Context,
+        ) {
+            self.rotate_count += 1;
+        }
+
+        fn flipped(
+            &mut self,
+            triangle_id: TriangleId,
+            opposite_triangle_id: TriangleId,
+            before: [PointId; 2],
+            after: [PointId; 2],
+            context: &Context,
+        ) {
+            self.flip_count += 1;
+            let [t, ot] = [triangle_id, opposite_triangle_id].map(|id| id.get(context.triangles));
+            // the new diagonal is shared, the old one is gone
+            for triangle in [t, ot] {
+                assert!(after.iter().all(|p| triangle.points.contains(p)));
+                assert!(!before.iter().all(|p| triangle.points.contains(p)));
+            }
+            assert!(t.neighbors.contains(&opposite_triangle_id));
+        }
+
+        fn finalized(&mut self, context: &Context) {
+            let (hit, miss) = context.advancing_front.cache_stats();
+            println!(
+                "af cache hit: {}/{} rate: {:.2}%",
+                hit,
+                hit + miss,
+                hit as f64 / (hit + miss) as f64 * 100.
+            );
+
+            println!(
+                "points: {} triangle: {}",
+                context.points.len(),
+                context.triangles.len()
+            );
+            println!(
+                "legalize: {} steps: {} rotate: {}",
+                self.legalize_count, self.legalize_step_count, self.rotate_count
+            );
+            self.hit_count = hit;
+            self.mis_count = miss;
+        }
+    }
+
+    #[test]
+    fn test_bird() {
+        let file_path = "test_data/bird.dat";
+        let points = try_load_from_file(file_path).unwrap();
+
+        let mut cache_hit = CacheHitOb::default();
+        let sweeper = SweeperBuilder::new(points)
+            .with_config(vec_config())
+            .build();
+        let triangles = sweeper
+            .triangulate_with_observer(&mut cache_hit)
+            .collect::<Vec<_>>();
+        assert_eq!(triangles.len(), 273);
+        assert!(cache_hit.hit_rate() > 0.74);
+        assert!(cache_hit.rotate_count <= 1043);
+        assert_eq!(cache_hit.flip_count, cache_hit.rotate_count);
+    }
+
+    #[test]
+    fn test_nazca_heron() {
+        let file_path = "test_data/nazca_heron.dat";
+        let points = try_load_from_file(file_path).unwrap();
+
+        let sweeper = SweeperBuilder::new(points)
+            .with_config(vec_config())
+            .build();
+        let mut cache_hit = CacheHitOb::default();
+        let triangles = sweeper
+            .triangulate_with_observer(&mut cache_hit)
+            .collect::<Vec<_>>();
+        assert_eq!(triangles.len(), 1034);
+        assert!(cache_hit.hit_rate() > 0.78);
+        assert!(cache_hit.rotate_count <= 6700);
+    }
+
+    /// cache hit rate asserts are tuned for the vec backend
+    fn vec_config() -> SweepConfig {
+        SweepConfig::default().front_backend(FrontBackend::Vec)
+    }
+
+    #[test]
+    fn test_triangulate_into() {
+        let points = try_load_from_file("test_data/bird.dat").unwrap();
+        let sweeper = SweeperBuilder::new(points).build();
+
+        let mut emitted = vec![];
+        sweeper
+            .clone()
+            .triangulate_into(&mut |t: &Triangle| emitted.push(*t));
+        let collected = sweeper.triangulate().collect::<Vec<_>>();
+        assert_eq!(emitted.len(), 273);
+        for (a, b) in emitted.iter().zip(collected.iter()) {
+            for i in 0..3 {
+                assert!(a.points[i].eq(&b.points[i]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cocircular_grid() {
+        // every 4 neighbor points of a grid are cocircular
+        let n = 20;
+        let steiner_points = (1..n - 1)
+            .flat_map(|y| (1..n - 1).map(move |x| Point::new(x as f64, y as f64)))
+            .collect::<Vec<_>>();
+        let max = (n - 1) as f64;
+        let sweeper = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(max, 0.),
+            Point::new(max, max),
+            Point::new(0., max),
+        ])
+        .add_steiner_points(steiner_points)
+        .build();
+
+        // 2 * points - hull points - 2
+        let triangles = sweeper.clone().triangulate();
+        assert_eq!(triangles.count(), 2 * (n - 2) * (n - 2) + 2);
+
+        struct Check<'a>(&'a mut Option<bool>);
+        impl Observer for Check<'_> {
+            fn finalized(&mut self, context: &Context) {
+                *self.0 = Some(Sweeper::verify_triangles(context));
+            }
+        }
+        let mut verified = None;
+        sweeper.triangulate_with_observer(&mut Check(&mut verified));
+        assert_eq!(verified, Some(true));
+    }
+
+    /// triangulate with every backend, with and without monotone fast path and with
+    /// tick delaunay flags, check the result is delaunay and covers `area`
+    pub(super) fn assert_valid_mesh(name: &str, builder: SweeperBuilder, area: f64) {
+        struct Check(Option<bool>);
+        impl Observer for Check {
+            fn finalized(&mut self, context: &Context) {
+                self.0 = Some(Sweeper::verify_triangles(context));
+            }
+        }
+
+        for backend in [
+            FrontBackend::Vec,
+            FrontBackend::BTree,
+            FrontBackend::Chunked,
+        ] {
+            for (monotone, flag) in [
+                (true, DelaunayFlag::Flipped),
+                (false, DelaunayFlag::Flipped),
+                (false, DelaunayFlag::Tick),
+            ] {
+                let config = SweepConfig::default()
+                    .front_backend(backend)
+                    .monotone_fast_path(monotone)
+                    .legalize(LegalizeStrategy::default().delaunay_flag(flag));
+                let mut check = Check(None);
+                let result = builder
+                    .clone()
+                    .with_config(config)
+                    .build()
+                    .triangulate_with_observer(&mut check)
+                    .map(|t| {
+                        let [a, b, c] = t.points;
+                        ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() / 2.
+                    })
+                    .sum::<f64>();
+                assert_eq!(check.0, Some(true), "{name} {backend:?}");
+                assert!(
+                    (result - area).abs() <= area * 1e-9,
+                    "{name} {backend:?}: area {result}, expected {area}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_regular_inputs() {
+        for n in [3, 12] {
+            for scale in [1., 0.3, 1e-3, 1e3] {
+                let size = n as f64 * scale;
+                let center = size / 2.;
+                let at = |x: f64, y: f64| Point::new(x * scale, y * scale);
+
+                // square with every grid point on its border
+                let side = |side: usize, i: f64| {
+                    let n = n as f64;
+                    [(i, 0.), (n, i), (n - i, n), (0., n - i)][side]
+                };
+                let border = (0..4)
+                    .flat_map(|s| (0..n).map(move |i| side(s, i as f64)))
+                    .map(|(x, y)| at(x, y))
+                    .collect::<Vec<_>>();
+                let square = || SweeperBuilder::new(border.clone());
+
+                let grid = (1..n)
+                    .flat_map(|y| (1..n).map(move |x| (x as f64, y as f64)))
+                    .map(|(x, y)| at(x, y))
+                    .collect::<Vec<_>>();
+                let name = format!("n {n} scale {scale}");
+                assert_valid_mesh(&format!("border {name}"), square(), size * size);
+                assert_valid_mesh(
+                    &format!("grid {name}"),
+                    square().add_steiner_points(grid.clone()),
+                    size * size,
+                );
+
+                // rows offset by half, every 6 neighbors are cocircular
+                let hex = (1..n)
+                    .flat_map(|y| (1..n).map(move |x| (x, y)))
+                    .map(|(x, y)| {
+                        let offset = if y % 2 == 1 { 0.5 } else { 0. };
+                        at(x as f64 + offset, y as f64 * 3f64.sqrt() / 2.)
+                    })
+                    .filter(|p| p.x < size && p.y < size)
+                    .collect::<Vec<_>>();
+                assert_valid_mesh(
+                    &format!("hex {name}"),
+                    square().add_steiner_points(hex),
+                    size * size,
+                );
+
+                // concentric squares, as steiner points and as a hole
+                let ring = |r: f64| {
+                    [(-r, -r), (r, -r), (r, r), (-r, r)]
+                        .map(|(x, y)| Point::new(center + x, center + y))
+                };
+                let rings = (1..n)
+                    .flat_map(|k| ring(k as f64 * scale * 0.45))
+                    .collect::<Vec<_>>();
+                assert_valid_mesh(
+                    &format!("concentric {name}"),
+                    square().add_steiner_points(rings.clone()),
+                    size * size,
+                );
+                let hole = size / 8.;
+                assert_valid_mesh(
+                    &format!("concentric hole {name}"),
+                    square().add_hole(ring(hole).to_vec()).add_steiner_points(
+                        rings
+                            .into_iter()
+                            .filter(|p| (p.x - center).abs() > hole || (p.y - center).abs() > hole),
+                    ),
+                    size * size - 4. * hole * hole,
+                );
+
+                // fans, points on rays from the center, exactly on axes and diagonals
+                // and rounded on other angles
+                let mut fan = vec![Point::new(center, center)];
+                for k in 0..16 {
+                    let angle = k as f64 * std::f64::consts::PI / 8.;
+                    for j in 1..n {
+                        let d = j as f64 * scale * 0.45;
+                        let (dx, dy) = if k % 2 == 0 {
+                            [(1., 0.), (1., 1.), (0., 1.), (-1., 1.)][(k / 2) % 4]
+                        } else {
+                            (angle.cos(), angle.sin())
+                        };
+                        let sign = if k >= 8 && k % 2 == 0 { -1. } else { 1. };
+                        fan.push(Point::new(center + sign * dx * d, center + sign * dy * d));
+                    }
+                }
+                assert_valid_mesh(
+                    &format!("fan {name}"),
+                    square().add_steiner_points(fan),
+                    size * size,
+                );
+
+                // all but the apex on one line
+                let mut comb = (0..=n).map(|i| at(i as f64, 0.)).collect::<Vec<_>>();
+                comb.push(Point::new(center, size));
+                assert_valid_mesh(
+                    &format!("comb {name}"),
+                    SweeperBuilder::new(comb),
+                    size * size / 2.,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_touching_holes() {
+        let points = |points: &[(f64, f64)]| {
+            points
+                .iter()
+                .map(|(x, y)| Point::new(*x, *y))
+                .collect::<Vec<_>>()
+        };
+        let outer = points(&[
+            (0., 0.),
+            (4., 0.),
+            (5., 0.),
+            (6., 0.),
+            (10., 0.),
+            (10., 10.),
+            (5., 10.),
+            (0., 10.),
+        ]);
+
+        // one shared vertex
+        assert_valid_mesh(
+            "vertex",
+            SweeperBuilder::new(outer.clone()).add_hole(points(&[(5., 0.), (7., 3.), (3., 3.)])),
+            94.,
+        );
+        // two shared vertices, interior is split into two pieces, top vertex has
+        // 3 edges below it
+        assert_valid_mesh(
+            "split",
+            SweeperBuilder::new(outer.clone()).add_hole(points(&[
+                (5., 0.),
+                (7., 5.),
+                (5., 10.),
+                (3., 5.),
+            ])),
+            80.,
+        );
+        // shared edge, the hole is a notch
+        assert_valid_mesh(
+            "edge",
+            SweeperBuilder::new(outer.clone()).add_hole(points(&[
+                (4., 0.),
+                (4., 2.),
+                (5., 2.),
+                (5., 0.),
+            ])),
+            98.,
+        );
+        // holes sharing a vertex and an edge with each other
+        assert_valid_mesh(
+            "holes",
+            SweeperBuilder::new(outer.clone()).add_holes([
+                points(&[(2., 2.), (2., 4.), (4., 4.), (4., 2.)]),
+                points(&[(4., 2.), (4., 4.), (6., 4.), (6., 2.)]),
+                points(&[(6., 4.), (6., 6.), (8., 6.), (8., 4.)]),
+            ]),
+            88.,
+        );
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        let builder = |scale: f64| {
+            let at = |x: f64, y: f64| Point::new(x * scale, y * scale);
+            SweeperBuilder::new(vec![at(-1., -1.), at(1., -1.), at(1., 1.), at(-1., 1.)])
+                .add_hole(vec![
+                    at(-0.2, -0.2),
+                    at(-0.2, 0.2),
+                    at(0.2, 0.2),
+                    at(0.2, -0.2),
+                ])
+                .add_steiner_points([at(0.5, 0.7), at(-0.6, 0.3), at(0.1, -0.8)])
+        };
+        let expected = builder(1.).build().triangulate().collect::<Vec<_>>();
+
+        let scale = 1e300;
+        let triangles = builder(scale).build().triangulate().collect::<Vec<_>>();
+        assert_eq!(triangles.len(), expected.len());
+        for (t, e) in triangles.iter().zip(expected.iter()) {
+            for (p, q) in t.points.iter().zip(e.points) {
+                assert!(p.eq(&Point::new(q.x * scale, q.y * scale)));
+            }
+        }
+
+        let config = SweepConfig::default().auto_rescale(false);
+        let report = builder(scale)
+            .with_config(config.clone())
+            .try_build()
+            .err()
+            .unwrap();
+        assert!(matches!(report.problems[..], [InputError::Overflow { .. }]));
+        assert!(builder(1e50).with_config(config).try_build().is_ok());
+    }
+
+    #[test]
+    fn test_x_tolerance() {
+        // vertical stack with x jittered by a few ulps
+        let stack = (1..20)
+            .map(|i| Point::new(5. + (i % 3) as f64 * 1e-15, i as f64 * 0.5))
+            .collect::<Vec<_>>();
+        let square = vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ];
+        // (total area, distinct x of stacked points)
+        let stats = |config: SweepConfig| {
+            let triangles = SweeperBuilder::new(square.clone())
+                .add_steiner_points(stack.clone())
+                .with_config(config)
+                .build()
+                .triangulate()
+                .collect::<Vec<_>>();
+            let mut stack_x = triangles
+                .iter()
+                .flat_map(|t| t.points)
+                .filter(|p| (p.x - 5.).abs() < 0.1)
+                .map(|p| p.x)
+                .collect::<Vec<_>>();
+            stack_x.sort_by(f64::total_cmp);
+            stack_x.dedup();
+            let area = triangles
+                .iter()
+                .map(|t| {
+                    let [a, b, c] = t.points;
+                    ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() / 2.
+                })
+                .sum::<f64>();
+            (area, stack_x.len())
+        };
+
+        let (area, stack_x) = stats(SweepConfig::default());
+        assert!((area - 100.).abs() < 1e-9);
+        assert_eq!(stack_x, 3);
+        // snapped into an exactly vertical stack
+        let (area, stack_x) = stats(SweepConfig::default().x_tolerance(1e-9));
+        assert!((area - 100.).abs() < 1e-9);
+        assert_eq!(stack_x, 1);
+    }
+
+    /// indices of result triangles, each rotated to start at its smallest, sorted
+    pub(super) fn sorted_indices(triangles: &Triangles) -> Vec<[u32; 3]> {
+        let mut indices = vec![0; triangles.index_count()];
+        triangles.write_indices_into(&mut indices);
+        let mut sorted = indices
+            .chunks_exact(3)
+            .map(|t| {
+                let min = (0..3).min_by_key(|i| t[*i]).unwrap();
+                [t[min], t[(min + 1) % 3], t[(min + 2) % 3]]
+            })
+            .collect::<Vec<_>>();
+        sorted.sort();
+        sorted
+    }
+
+    #[test]
+    fn test_ghost_points() {
+        let mut rng = rand::thread_rng();
+        let tall = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(2., 0.),
+            Point::new(2., 1000.),
+            Point::new(0., 1000.),
+        ])
+        .add_steiner_points(
+            (0..500).map(|_| Point::new(rng.gen_range(0.1..1.9), rng.gen_range(1.0..999.))),
+        );
+        let with_ghosts = |ghost_points| {
+            tall.clone()
+                .with_config(SweepConfig::default().ghost_points(ghost_points))
+                .build()
+        };
+
+        let square = with_ghosts(GhostPoints::Square);
+        let (head, tail) = (
+            square.points.head.get(&square.points),
+            square.points.tail.get(&square.points),
+        );
+        assert!(head.eq(&Point::new(-300., -300.)) && tail.eq(&Point::new(302., -300.)));
+
+        let extents = with_ghosts(GhostPoints::Extents).triangulate();
+        let square = square.triangulate();
+        assert_eq!(sorted_indices(&square), sorted_indices(&extents));
+    }
+
+    #[test]
+    fn test_front_backends_agree() {
+        for file_path in ["test_data/bird.dat", "test_data/nazca_heron.dat"] {
+            let points = try_load_from_file(file_path).unwrap();
+            let results = [
+                FrontBackend::Vec,
+                FrontBackend::BTree,
+                FrontBackend::Chunked,
+            ]
+            .map(|backend| {
+                SweeperBuilder::new(points.clone())
+                    .with_config(SweepConfig::default().front_backend(backend))
+                    .build()
+                    .triangulate()
+                    .collect::<Vec<_>>()
+            });
+            for other in &results[1..] {
+                assert_eq!(other.len(), results[0].len());
+                for (a, b) in results[0].iter().zip(other.iter()) {
+                    for (pa, pb) in a.points.iter().zip(b.points.iter()) {
+                        assert!(pa.eq(pb));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_concurrent_clones() {
+        let points = try_load_from_file("test_data/nazca_heron.dat").unwrap();
+        let sweeper = SweeperBuilder::new(points)
+            .add_steiner_points((0..50).map(|i| Point::new(i as f64 * 3., i as f64 * 2.)))
+            .build();
+        let expected = crate::testkit::snapshot(sweeper.clone().triangulate());
+
+        let results = std::thread::scope(|scope| {
+            let handles = (0..8)
+                .map(|_| {
+                    let sweeper = sweeper.clone();
+                    scope.spawn(move || crate::testkit::snapshot(sweeper.triangulate()))
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert!(results.iter().all(|result| *result == expected));
+
+        // a shared result is read from many threads, lazy edge origins included
+        let triangles = sweeper.triangulate();
+        let origins = std::thread::scope(|scope| {
+            let handles = (0..4)
+                .map(|_| scope.spawn(|| triangles.edge_origin(0, 0)))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert!(origins.iter().all(|origin| *origin == origins[0]));
+    }
+
+    #[test]
+    fn test_auto_tune() {
+        let points = try_load_from_file("test_data/nazca_heron.dat").unwrap();
+        let sweeper = SweeperBuilder::new(points).build().auto_tune(256);
+        assert!(sweeper.config().front_capacity.is_some());
+        assert_eq!(sweeper.triangulate().count(), 1034);
+    }
+
+    #[test]
+    fn test_points_and_edges() {
+        let sweeper = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_hole(vec![
+            Point::new(4., 4.),
+            Point::new(4., 6.),
+            Point::new(6., 6.),
+        ])
+        .add_steiner_point(Point::new(2., 8.))
+        .build();
+        let points = sweeper.points();
+        assert_eq!(points.len(), 10);
+        assert_eq!((points.head.as_usize(), points.tail.as_usize()), (8, 9));
+
+        let mut order = vec![usize::MAX; points.len()];
+        for (i, (point_id, _, _)) in points.iter_point_by_y(0).enumerate() {
+            order[point_id.as_usize()] = i;
+        }
+        assert!(order[..8].iter().all(|i| *i < 8));
+        let edges = sweeper.edges().collect::<Vec<_>>();
+        assert_eq!(edges.len(), 7);
+        assert!(edges
+            .iter()
+            .all(|edge| order[edge.p.as_usize()] < order[edge.q.as_usize()]));
+    }
+
+    #[test]
+    fn test_rand() {
+        let test_path = "test_data/latest_test_data";
+        let points = match try_load_from_file(test_path) {
+            None => {
+                let mut points = Vec::<Point>::new();
+                for _ in 0..100 {
+                    let x: f64 = rand::thread_rng().gen_range(0.0..800.);
+                    let y: f64 = rand::thread_rng().gen_range(0.0..800.);
+                    points.push(Point::new(x, y));
+                }
+                save_to_file(&points, test_path);
+                points
+            }
+            Some(points) => points,
+        };
+
+        let sweeper = SweeperBuilder::new(vec![
+            Point::new(-10., -10.),
+            Point::new(810., -10.),
+            Point::new(810., 810.),
+            Point::new(-10., 810.),
+        ])
+        .add_steiner_points(points)
+        .add_hole(vec![
+            Point::new(400., 400.),
+            Point::new(600., 400.),
+            Point::new(600., 600.),
+            Point::new(400., 600.),
+        ])
+        .build();
+        let _ = sweeper.triangulate();
+
+        delete_file(test_path);
+    }
+
+    pub(super) fn try_load_from_file(path: &str) -> Option<Vec<Point>> {
+        let mut f = std::fs::File::options().read(true).open(path).ok()?;
+        let mut value = "".to_string();
+        f.read_to_string(&mut value).unwrap();
+        let mut points = vec![];
+        for line in value.lines() {
+            let mut iter = line.split_whitespace();
+            let x = iter.next().unwrap();
+            let y = iter.next().unwrap();
+
+            let x = x.parse::<f64>().unwrap();
+            let y = y.parse::<f64>().unwrap();
+            points.push(Point::new(x, y));
+        }
+
+        Some(points)
+    }
+
+    fn save_to_file(points: &[Point], path: &str) {
+        use std::fmt::Write;
+
+        let mut f = std::fs::File::options()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .unwrap();
+
+        let mut value = "".to_string();
+        for p in points {
+            writeln!(value, "{} {}", p.x, p.y).unwrap();
+        }
+
+        f.write_all(value.as_bytes()).unwrap();
+    }
+
+    fn delete_file(path: &str) {
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,113 @@
+use crate::advancing_front::NodeId;
+#[cfg(feature = "paranoid")]
+use crate::paranoid;
+use crate::points::PointEdges;
+use crate::shape::{Edge, InnerTriangle, Point};
+use crate::{Context, PointId};
+
+use super::{Observer, Sweeper};
+
+/// Point event related methods
+impl Sweeper {
+    pub(super) fn sweep_points(context: &mut Context, observer: &mut impl Observer) {
+        for (point_id, point, edges) in context.points.iter_point_by_y(1) {
+            Self::sweep_point(point_id, point, edges, context, observer);
+        }
+    }
+
+    /// Run point event for the point, then edge events for edges ending at it.
+    /// Points should be fed in y order.
+    pub(crate) fn sweep_point(
+        point_id: PointId,
+        point: Point,
+        edges: PointEdges,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) {
+        let mut node_id = Self::point_event(point_id, point, context, observer);
+        observer.point_event(point_id, context);
+        #[cfg(feature = "paranoid")]
+        paranoid::assert_invariants(context, format_args!("point event {point_id:?}"));
+
+        let points = context.points;
+        for p in points.edges(edges) {
+            let edge = Edge { p, q: point_id };
+            node_id = Self::edge_event(edge, node_id, context, observer);
+
+            observer.edge_event(edge, context);
+            #[cfg(feature = "paranoid")]
+            paranoid::assert_invariants(context, format_args!("edge event {edge:?}"));
+        }
+
+        debug_assert!(Self::verify_triangles(context));
+    }
+
+    /// Returns the [`NodeId`] created for the point, its index hint is kept
+    /// up to date through the fills, so following edge events don't need to
+    /// search the advancing front again.
+    pub(super) fn point_event(
+        point_id: PointId,
+        point: Point,
+        context: &mut Context,
+        observer: &mut impl Observer,
+    ) -> NodeId {
+        let node = context.advancing_front.locate_node(point).unwrap();
+        let node_id = node.get_node_id();
+        let next_node = node.next().unwrap();
+        let node_point = node.point();
+
+        let triangle = context.triangles.insert(InnerTriangle::new(
+            point_id,
+            node.point_id(),
+            next_node.point_id(),
+        ));
+        let node_triangle = node.triangle.unwrap();
+        context.triangles.mark_neighbor(node_triangle, triangle);
+        let mut new_node_id = context.advancing_front.insert(point_id, point, triangle);
+
+        Self::legalize(triangle, context, observer);
+
+        // in middle case, the node's x should be less than point'x
+        // in left case, they are same.
+        if point.x <= node_point.x + f64::EPSILON {
+            // node is the prev of new node, filling it shifts new node's index
+            if let Some(fill_one) = Self::fill_one(node_id, context, observer) {
+                new_node_id = fill_one.next;
+            }
+        }
+
+        Self::fill_advancing_front(new_node_id, context, observer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SweeperBuilder;
+
+    #[test]
+    fn test_point_event() {
+        let sweeper = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_steiner_points((1..9).map(|i| Point::new(i as f64, (i * 7 % 9) as f64 + 0.5)))
+        .build();
+
+        sweeper.triangulate_with_stages(|context| {
+            let points = context.points;
+            for (point_id, point, _) in points.iter_point_by_y(1) {
+                let node_id = Sweeper::point_event(point_id, point, context, &mut ());
+
+                // the point joins the front, in a triangle of its own
+                let node = context.advancing_front.get_node_with_id(node_id).unwrap();
+                assert_eq!(node.point_id(), point_id);
+                let triangle = node.triangle.unwrap().get(context.triangles);
+                assert!(triangle.points.contains(&point_id));
+                assert!(Sweeper::verify_triangles(context));
+            }
+        });
+    }
+}
@@ -0,0 +1,100 @@
+//! Stages of the sweep, for composing custom sweeps by
+//! [`Sweeper::triangulate_with_stages`], e.g. stop before finalization and
+//! read the raw triangles of the advancing front.
+//!
+//! Observers passed here get every event, [`Observer::mask`] is not read.
+//!
+//! Point and edge events don't allocate once the reused buffers have grown,
+//! given the [`crate::FrontBackend::Vec`] front with enough
+//! [`crate::SweepConfig::front_capacity`], e.g. for sweeps on a real time
+//! budget.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{stages, Point, SweeperBuilder};
+//!
+//!    let sweeper = SweeperBuilder::new(vec![
+//!        Point::new(0., 0.),
+//!        Point::new(10., 0.),
+//!        Point::new(10., 10.),
+//!        Point::new(0., 10.),
+//!    ])
+//!    .build();
+//!
+//!    // sweep, but never mark the interior
+//!    let triangles = sweeper.triangulate_with_stages(|context| {
+//!        stages::sweep_points(context, &mut ());
+//!        assert!(context.triangles.len() > 2);
+//!    });
+//!    assert_eq!(triangles.count(), 0);
+//! ```
+use crate::{Context, Observer, PointId, TriangleId};
+
+use super::Sweeper;
+
+/// Point and edge events for all points in y order, except the lowest one
+/// which is in the initial triangle
+pub fn sweep_points(context: &mut Context, observer: &mut impl Observer) {
+    Sweeper::sweep_points(context, observer);
+}
+
+/// Point event for `point_id`, then edge events of edges ending at it. Points
+/// should be fed in y order, from the second lowest one.
+pub fn sweep_point(point_id: PointId, context: &mut Context, observer: &mut impl Observer) {
+    // panics on an unknown id before the unchecked read
+    let edges = context.points.point_edges(point_id);
+    let point = point_id.get(context.points);
+    Sweeper::sweep_point(point_id, point, edges, context, observer);
+}
+
+/// Mark triangles inside the polygon as interior and push them to
+/// `context.result`, run once after the sweep
+pub fn finalize(context: &mut Context) {
+    Sweeper::finalize_polygon(context);
+}
+
+/// Flip edges of `triangle_id` and ones affected by the flips until they are
+/// legal
+pub fn legalize(triangle_id: TriangleId, context: &mut Context, observer: &mut impl Observer) {
+    Sweeper::legalize(triangle_id, context, observer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweeper::tests::sorted_indices;
+    use crate::{loader, SweeperBuilder};
+
+    #[test]
+    fn test_stages() {
+        let builder = loader::load_file("test_data/bird.dat").unwrap();
+        let expected = sorted_indices(&builder.clone().build().triangulate());
+
+        // point by point, then finalize, same as triangulate
+        let triangles = builder.clone().build().triangulate_with_stages(|context| {
+            let ids = context
+                .points
+                .iter_point_by_y(1)
+                .map(|(point_id, _, _)| point_id)
+                .collect::<Vec<_>>();
+            for point_id in ids {
+                sweep_point(point_id, context, &mut ());
+            }
+            finalize(context);
+        });
+        assert_eq!(sorted_indices(&triangles), expected);
+
+        // skipping finalization leaves the raw sweep, no interior
+        let mut swept = 0;
+        let triangles = builder.build().triangulate_with_stages(|context| {
+            sweep_points(context, &mut ());
+            swept = context.triangles.len();
+        });
+        assert!(swept > expected.len());
+        assert_eq!(triangles.count(), 0);
+        assert!(SweeperBuilder::new(vec![])
+            .build()
+            .triangulate_with_stages(|_| unreachable!())
+            .is_empty());
+    }
+}
@@ -0,0 +1,1166 @@
+use std::sync::OnceLock;
+
+use crate::lerp::{self, Lerp};
+use crate::locate::TriangleGrid;
+use crate::origin::{self, EdgeOrigins, EdgeSplit, InputEdgeId, PolylineId};
+use crate::points::Points;
+use crate::triangles::{MeshError, TriangleId, TriangleStore};
+use crate::utils::orient;
+use crate::{shape::*, Affine, Legality, PointId, Triangle};
+
+use super::Sweeper;
+
+/// Lookups from store to result indices, see [`Triangles::triangles_around_vertex`]
+pub(super) struct Walk {
+    /// result index of each store triangle, `u32::MAX` if not in the result
+    result_index: Vec<u32>,
+    /// a result triangle of each vertex, `u32::MAX` if none
+    vertex_triangle: Vec<u32>,
+}
+
+impl Walk {
+    /// Result triangle next to result triangle `index` around `point`, CCW or CW
+    fn step(
+        &self,
+        triangles: &TriangleStore,
+        result: &[TriangleId],
+        index: u32,
+        point: PointId,
+        ccw: bool,
+    ) -> Option<u32> {
+        let triangle = result[index as usize].get(triangles);
+        // CCW around `point` crosses the edge to its CW point
+        let neighbor = match ccw {
+            true => triangle.neighbor_cw(point),
+            false => triangle.neighbor_ccw(point),
+        };
+        if neighbor.invalid() {
+            return None;
+        }
+        Some(self.result_index[neighbor.as_usize()]).filter(|next| *next != u32::MAX)
+    }
+}
+
+/// Largest coordinate deviation of output vertices from input points, along x
+/// or y, see [`Triangles::precision_report`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PrecisionReport {
+    /// by snapping nearly vertical points, see [`SweepConfig::x_tolerance`]
+    pub snap: f64,
+    /// by rounding through [`SweeperBuilder::with_transform`] and back
+    pub transform: f64,
+    /// by converting output to f32, e.g. [`Triangles::write_positions_into`]
+    pub f32: f64,
+}
+
+impl PrecisionReport {
+    /// Bound of the total deviation of f64 output
+    pub fn max_f64(&self) -> f64 {
+        self.snap + self.transform
+    }
+
+    /// Bound of the total deviation of f32 output
+    pub fn max_f32(&self) -> f64 {
+        self.max_f64() + self.f32
+    }
+}
+
+/// larger of the x and y distance
+pub(super) fn deviation(p: Point, q: Point) -> f64 {
+    (p.x - q.x).abs().max((p.y - q.y).abs())
+}
+
+/// Constrained edge the sweep gave up on, the mesh stays valid but the edge is
+/// missing from it, see [`Triangles::edge_errors`]
+#[derive(thiserror::Error, Debug, Clone, Copy)]
+pub enum EdgeError {
+    /// walking along the edge around point `1` hit the mesh border, e.g. the edge
+    /// grazes the advancing front
+    #[error("edge {0:?} runs off the mesh around point {1:?}")]
+    MissingNeighbor(Edge, PointId),
+}
+
+/// The result of triangulate
+///
+/// Vertex indices are stable. [`Self::update_positions`], [`Self::move_point`]
+/// and the relax passes move points and flip edges, but keep every vertex at
+/// its index and the number of result triangles, so a vertex buffer can be
+/// rewritten in place, only changed positions and the index buffer need upload.
+pub struct Triangles {
+    /// points store, it includes all points, including ones in hole
+    pub(super) points: Points,
+    /// including all triangles, including ones in hole
+    pub(super) triangles: TriangleStore,
+    /// final result `TriangleId`s
+    pub(super) result: Vec<TriangleId>,
+    /// point ids of outer polyline and holes
+    pub(super) rings: Vec<Vec<PointId>>,
+    /// point ids of collapsed holes
+    pub(super) constraints: Vec<Vec<PointId>>,
+    /// tag of each ring, empty if none is tagged
+    pub(super) ring_tags: Vec<Option<u32>>,
+    /// origin of constrained edges, built on first [`Self::edge_origin`]. Lazily
+    /// filled state is in `OnceLock`s, it keeps them safe to share across threads
+    pub(super) origins: OnceLock<EdgeOrigins>,
+    /// lookups for walking neighbor links, built on first walk
+    pub(super) walk: OnceLock<Walk>,
+    /// point location, built on first lookup
+    pub(super) grid: OnceLock<TriangleGrid>,
+    /// constrained edges left out by the sweep
+    pub(super) edge_errors: Vec<EdgeError>,
+    /// material of each result triangle, empty without region seeds
+    pub(super) materials: Vec<Option<u32>>,
+    /// triangles inside each hole, empty unless holes are triangulated
+    pub(super) holes: Vec<Vec<TriangleId>>,
+    /// vertex of each input point, empty if none merged
+    pub(super) merged: Vec<u32>,
+    /// factor points are scaled by, undone on output
+    pub(super) scale: f64,
+    /// inverse of [`SweeperBuilder::with_transform`], applied on output after
+    /// undoing scale
+    pub(super) inverse: Option<Affine>,
+    /// deviation introduced on build, f32 loss is measured on demand
+    pub(super) precision: PrecisionReport,
+
+    /// iterator next cursor
+    pub(super) next: usize,
+    /// iterator back cursor, exclusive
+    pub(super) end: usize,
+}
+
+impl Triangles {
+    /// Number of vertices, same as input points. Vertex index follows input order:
+    /// outer polyline, holes, then steiner points
+    pub fn vertex_count(&self) -> usize {
+        // head and tail are not part of the output
+        self.points.len() - 2
+    }
+
+    /// Number of indices, 3 for each result triangle
+    pub fn index_count(&self) -> usize {
+        self.result.len() * 3
+    }
+
+    /// Number of result triangles, regardless of iteration progress. Triangles
+    /// left to iterate are [`ExactSizeIterator::len`]
+    pub fn triangle_count(&self) -> usize {
+        self.result.len()
+    }
+
+    /// Whether no triangles are left to iterate
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Flip non constrained edges between result triangles until every one is
+    /// locally delaunay, a final pass over the mesh after any flags left from the
+    /// sweep. Work is bounded, it stops after 16 flips per triangle. Result triangle
+    /// indices are kept, their points may change. Returns number of flips.
+    pub fn relax_nonconstrained(&mut self) -> usize {
+        self.relax(None)
+    }
+
+    /// Same as [`Self::relax_nonconstrained`], flipping edges by `legality`
+    /// instead, e.g. a [`crate::FlipObjective`] other than delaunay
+    pub fn relax_with(&mut self, legality: &dyn Legality) -> usize {
+        self.relax(Some(legality))
+    }
+
+    /// Move vertices to `positions`, indexed as [`Self::write_indices_into`], then
+    /// flip edges until delaunay again, e.g. animated points moving a little each
+    /// frame. Far cheaper than a new sweep, topology is reused. Returns number of
+    /// flips, or the first result triangle folded over by the move, the mesh is
+    /// left unchanged then and needs a new sweep.
+    pub fn update_positions(&mut self, positions: &[Point]) -> Result<usize, MeshError> {
+        assert_eq!(
+            positions.len(),
+            self.vertex_count(),
+            "a position is needed for each vertex"
+        );
+        let moved = positions
+            .iter()
+            .map(|p| self.to_internal(*p))
+            .collect::<Vec<_>>();
+        let old = (0..moved.len())
+            .map(|index| PointId::from_usize(index).get(&self.points))
+            .collect::<Vec<_>>();
+
+        for (index, point) in moved.into_iter().enumerate() {
+            self.points.set_point(PointId::from_usize(index), point);
+        }
+        let inverted = self.result.iter().find(|triangle_id| {
+            let [a, b, c] = triangle_id
+                .get(&self.triangles)
+                .points
+                .map(|p| p.get(&self.points));
+            orient(a, b, c) <= 0.
+        });
+        if let Some(triangle_id) = inverted {
+            let triangle_id = *triangle_id;
+            for (index, point) in old.into_iter().enumerate() {
+                self.points.set_point(PointId::from_usize(index), point);
+            }
+            return Err(MeshError::Inverted(triangle_id));
+        }
+        Ok(self.relax(None))
+    }
+
+    fn relax(&mut self, legality: Option<&dyn Legality>) -> usize {
+        // flips move triangles off their vertices
+        self.walk = OnceLock::new();
+        self.grid = OnceLock::new();
+        for triangle_id in self.result.iter() {
+            let triangle = self.triangles.get_mut_unchecked(*triangle_id);
+            for i in 0..3 {
+                triangle.set_delaunay(i, false);
+            }
+        }
+        Sweeper::flip_illegal(
+            &mut self.triangles,
+            &self.points,
+            legality,
+            self.result.clone(),
+            self.result.len() * 16,
+            |triangle| triangle.interior,
+            |_, _| {},
+        )
+    }
+
+    /// Move vertex `vertex` to `to`, then flip edges around it until delaunay
+    /// again, e.g. one particle of a kinetic mesh. Same as
+    /// [`Self::update_positions`] for a single vertex, but only triangles
+    /// around it are checked and flipped, the rest of the mesh is not touched.
+    /// Returns number of flips, or the result triangle folded over by the move,
+    /// the mesh is left unchanged then.
+    pub fn move_point(&mut self, vertex: usize, to: Point) -> Result<usize, MeshError> {
+        assert!(vertex < self.vertex_count(), "unknown vertex {vertex}");
+        let around = self
+            .triangles_around_vertex(vertex)
+            .map(|index| self.result[index])
+            .collect::<Vec<_>>();
+        let point_id = PointId::from_usize(vertex);
+        let old = point_id.get(&self.points);
+        self.points.set_point(point_id, self.to_internal(to));
+
+        let inverted = around.iter().find(|triangle_id| {
+            let [a, b, c] = triangle_id
+                .get(&self.triangles)
+                .points
+                .map(|p| p.get(&self.points));
+            orient(a, b, c) <= 0.
+        });
+        if let Some(triangle_id) = inverted {
+            let triangle_id = *triangle_id;
+            self.points.set_point(point_id, old);
+            return Err(MeshError::Inverted(triangle_id));
+        }
+
+        self.grid = OnceLock::new();
+        for triangle_id in around.iter() {
+            let triangle = self.triangles.get_mut_unchecked(*triangle_id);
+            for i in 0..3 {
+                triangle.set_delaunay(i, false);
+            }
+        }
+        let mut flipped = vec![];
+        let max_flips = around.len() * 16;
+        let flips = Sweeper::flip_illegal(
+            &mut self.triangles,
+            &self.points,
+            None,
+            around,
+            max_flips,
+            |triangle| triangle.interior,
+            |t, ot| flipped.extend([t, ot]),
+        );
+
+        // flips keep result indices, only vertices may move off their triangle
+        if let Some(walk) = self.walk.get_mut() {
+            for triangle_id in flipped {
+                let index = walk.result_index[triangle_id.as_usize()];
+                for p in triangle_id.get(&self.triangles).points {
+                    walk.vertex_triangle[p.as_usize()] = index;
+                }
+            }
+        }
+        Ok(flips)
+    }
+
+    /// Point as input to the position in the mesh, same way in as on build
+    fn to_internal(&self, p: Point) -> Point {
+        let p = match self.inverse.and_then(|inverse| inverse.inverse()) {
+            Some(forward) => forward.apply(p),
+            None => p,
+        };
+        Point::new(p.x * self.scale, p.y * self.scale)
+    }
+
+    /// Check neighbor links are mutual, constrained flags agree on both sides of
+    /// shared edges and no triangle has invalid points, before trusting the mesh
+    pub fn validate(&self) -> Result<(), MeshError> {
+        self.triangles.validate(&self.points)
+    }
+
+    /// Constrained edges the sweep could not insert, empty on success. The mesh
+    /// is still valid, those edges are just not in it.
+    pub fn edge_errors(&self) -> &[EdgeError] {
+        &self.edge_errors
+    }
+
+    /// Position of a point as input, before rescale and transform
+    fn input_point(&self, point_id: PointId) -> Point {
+        let point = point_id.get(&self.points);
+        let point = Point::new(point.x / self.scale, point.y / self.scale);
+        match &self.inverse {
+            Some(inverse) => inverse.apply(point),
+            None => point,
+        }
+    }
+
+    /// Deviation of output vertices from input points, to check a mesh stays
+    /// within tolerance, e.g. of a CNC toolpath. The f32 part is measured over all
+    /// vertices on each call.
+    pub fn precision_report(&self) -> PrecisionReport {
+        let f32 = (0..self.vertex_count())
+            .map(|index| {
+                let p = self.vertex(index);
+                deviation(p, Point::new(p.x as f32 as f64, p.y as f32 as f64))
+            })
+            .fold(0., f64::max);
+        PrecisionReport {
+            f32,
+            ..self.precision
+        }
+    }
+
+    /// Position of vertex `index` as input
+    pub(crate) fn vertex(&self, index: usize) -> Point {
+        self.input_point(PointId::from_usize(index))
+    }
+
+    /// Whether each vertex is on a constrained edge of a result triangle
+    pub(crate) fn constrained_vertices(&self) -> Vec<bool> {
+        let mut constrained = vec![false; self.vertex_count()];
+        for triangle_id in self.result.iter() {
+            let triangle = triangle_id.get(&self.triangles);
+            for i in 0..3 {
+                if triangle.is_constrained(i) {
+                    for p in [triangle.points[(i + 1) % 3], triangle.points[(i + 2) % 3]] {
+                        constrained[p.as_usize()] = true;
+                    }
+                }
+            }
+        }
+        constrained
+    }
+
+    /// Material of result triangle at `index` from region seeds, see
+    /// [`SweeperBuilder::add_region_seed`]. `None` if its region has no seed.
+    pub fn material(&self, index: usize) -> Option<u32> {
+        self.materials.get(index).copied().flatten()
+    }
+
+    /// Result triangle at `index`, regardless of iteration progress
+    pub fn triangle(&self, index: usize) -> Option<Triangle> {
+        Some(self.output_triangle(*self.result.get(index)?))
+    }
+
+    /// Vertex index of each input point, in input order, where
+    /// [`SweeperBuilder::merge_duplicates`] merged points. Empty if no point
+    /// was merged, vertex indices are input indices then.
+    pub fn merged_vertices(&self) -> &[u32] {
+        &self.merged
+    }
+
+    /// Holes with triangle lists, see [`SweeperBuilder::triangulate_holes`]
+    pub fn hole_count(&self) -> usize {
+        self.holes.len()
+    }
+
+    /// Triangles inside hole `hole`, numbered as [`PolylineId::Hole`]. Empty
+    /// unless set by [`SweeperBuilder::triangulate_holes`]. An island in the
+    /// hole is left out, it is in the result.
+    pub fn hole_triangles(&self, hole: usize) -> impl Iterator<Item = Triangle> + '_ {
+        self.holes
+            .get(hole)
+            .into_iter()
+            .flatten()
+            .map(|triangle_id| self.output_triangle(*triangle_id))
+    }
+
+    fn output_triangle(&self, triangle_id: TriangleId) -> Triangle {
+        let triangle = triangle_id.get(&self.triangles);
+        Triangle {
+            points: triangle.points.map(|p| self.input_point(p)),
+        }
+    }
+
+    /// Write vertex indices of all result triangles into `out`, regardless of
+    /// iteration progress. Only whole triangles are written when `out` is short.
+    /// Returns number of indices written.
+    pub fn write_indices_into(&self, out: &mut [u32]) -> usize {
+        let mut written = 0;
+        for (chunk, triangle_id) in out.chunks_exact_mut(3).zip(self.result.iter()) {
+            let triangle = triangle_id.get(&self.triangles);
+            for (slot, point_id) in chunk.iter_mut().zip(triangle.points) {
+                *slot = point_id.as_usize() as u32;
+            }
+            written += 3;
+        }
+        written
+    }
+
+    /// Vertex positions in index order, as input
+    pub fn vertex_positions(&self) -> Vec<[f64; 2]> {
+        (0..self.vertex_count())
+            .map(|idx| {
+                let point = self.vertex(idx);
+                [point.x, point.y]
+            })
+            .collect()
+    }
+
+    /// Write, for each edge of each result triangle, the index of the result triangle
+    /// across it into `out`, `u32::MAX` on the mesh border, e.g. for navmeshes. Edge
+    /// `i` is opposite to point `i`, triangles are in result order, same as
+    /// [`Self::write_indices_into`]. Returns number of indices written.
+    pub fn write_adjacency_into(&self, out: &mut [u32]) -> usize {
+        let mut result_index = vec![u32::MAX; self.triangles.len()];
+        for (index, triangle_id) in self.result.iter().enumerate() {
+            result_index[triangle_id.as_usize()] = index as u32;
+        }
+
+        let mut written = 0;
+        for (chunk, triangle_id) in out.chunks_exact_mut(3).zip(self.result.iter()) {
+            let triangle = triangle_id.get(&self.triangles);
+            for (slot, neighbor) in chunk.iter_mut().zip(triangle.neighbors) {
+                *slot = match neighbor.invalid() {
+                    true => u32::MAX,
+                    false => result_index[neighbor.as_usize()],
+                };
+            }
+            written += 3;
+        }
+        written
+    }
+
+    /// Write vertex positions as `x, y` pairs converted to f32 into `out`, in
+    /// index order. Returns number of floats written.
+    pub fn write_positions_into(&self, out: &mut [f32]) -> usize {
+        let mut written = 0;
+        for (index, chunk) in out
+            .chunks_exact_mut(2)
+            .take(self.vertex_count())
+            .enumerate()
+        {
+            let point = self.vertex(index);
+            chunk[0] = point.x as f32;
+            chunk[1] = point.y as f32;
+            written += 2;
+        }
+        written
+    }
+
+    /// The input edge which edge `edge` of result triangle `triangle` lies on, after
+    /// the sweep split it at points on it. `None` for unconstrained edges and ones
+    /// from collapsed degenerate holes.
+    /// Triangles are indexed in result order, same as iteration and
+    /// [`Self::write_indices_into`], edge `edge` is opposite to point `edge`.
+    pub fn edge_origin(&self, triangle: usize, edge: usize) -> Option<InputEdgeId> {
+        self.origin(triangle, edge)
+            .filter(|origin| origin.ring < self.rings.len())
+    }
+
+    /// The input polyline which edge `edge` of result triangle `triangle` lies on,
+    /// e.g. to style loops or set boundary conditions per loop. Collapsed
+    /// degenerate holes and [`SweeperBuilder::add_constraint`] polylines are
+    /// constraints, numbered in the order added. `None` for
+    /// unconstrained edges. Indexed the same as [`Self::edge_origin`].
+    pub fn edge_polyline(&self, triangle: usize, edge: usize) -> Option<PolylineId> {
+        let ring = self.origin(triangle, edge)?.ring;
+        Some(match ring {
+            0 => PolylineId::Outer,
+            ring if ring < self.rings.len() => PolylineId::Hole(ring - 1),
+            ring => PolylineId::Constraint(ring - self.rings.len()),
+        })
+    }
+
+    /// Tag of ring `ring`, 0 for the outer polyline then holes, same as
+    /// [`InputEdgeId::ring`] and [`Self::boundary_vertices`]. See
+    /// [`SweeperBuilder::add_tagged_hole`]. `None` if it has no tag, and for all
+    /// rings after [`SweeperBuilder::repair_input`].
+    pub fn ring_tag(&self, ring: usize) -> Option<u32> {
+        self.ring_tags.get(ring).copied().flatten()
+    }
+
+    /// Tag of the ring edge `edge` of result triangle `triangle` lies on, e.g. to
+    /// style outlines, indexed the same as [`Self::edge_origin`]
+    pub fn edge_tag(&self, triangle: usize, edge: usize) -> Option<u32> {
+        self.ring_tag(self.edge_origin(triangle, edge)?.ring)
+    }
+
+    /// origin of rings, then of constraints with `ring` counting on past the rings
+    fn origin(&self, triangle: usize, edge: usize) -> Option<InputEdgeId> {
+        let triangle = self.result.get(triangle)?.get(&self.triangles);
+        if edge >= 3 {
+            return None;
+        }
+        let origins = self.origins.get_or_init(|| {
+            origin::edge_origins(
+                &self.points,
+                &self.triangles,
+                &self.rings,
+                &self.constraints,
+            )
+        });
+        let (p, q) = (
+            triangle.points[(edge + 1) % 3],
+            triangle.points[(edge + 2) % 3],
+        );
+        origins.get(&origin::key(p, q)).copied()
+    }
+
+    /// Result triangles around vertex `vertex`, in CCW order, by walking neighbor
+    /// links. Around a vertex on the mesh border, it runs from the border on the
+    /// CW side to the one on the CCW side. Empty for vertices in no result
+    /// triangle, e.g. steiner points in holes.
+    pub fn triangles_around_vertex(&self, vertex: usize) -> impl Iterator<Item = usize> + '_ {
+        let walk = self.walk();
+        let point = PointId::from_usize(vertex);
+        let start = walk
+            .vertex_triangle
+            .get(vertex)
+            .copied()
+            .filter(|t| *t != u32::MAX);
+        // back CW to the border, or around to the start
+        let first = start.map(|start| {
+            let mut first = start;
+            while let Some(prev) = walk.step(&self.triangles, &self.result, first, point, false) {
+                if prev == start {
+                    break;
+                }
+                first = prev;
+            }
+            first
+        });
+        std::iter::successors(first, move |index| {
+            walk.step(&self.triangles, &self.result, *index, point, true)
+                .filter(|next| Some(*next) != first)
+        })
+        .map(|index| index as usize)
+    }
+
+    /// Vertices sharing an edge with vertex `vertex`, in CCW order, same order as
+    /// [`Self::triangles_around_vertex`]. Around a vertex on the mesh border, it
+    /// has one more vertex than triangles.
+    pub fn edge_ring(&self, vertex: usize) -> impl Iterator<Item = usize> + '_ {
+        let point = PointId::from_usize(vertex);
+        let mut ring = vec![];
+        for index in self.triangles_around_vertex(vertex) {
+            let triangle = self.result[index].get(&self.triangles);
+            if ring.is_empty() {
+                ring.push(triangle.point_ccw(point).as_usize());
+            }
+            ring.push(triangle.point_cw(point).as_usize());
+        }
+        // a closed ring ends where it started
+        if ring.len() > 2 && ring.first() == ring.last() {
+            ring.pop();
+        }
+        ring.into_iter()
+    }
+
+    /// Outward unit normal of each vertex on the mesh border, the normalized sum
+    /// of the normals of its border edges, e.g. for extrusion, outlining or
+    /// collision. Indexed by vertex, `None` for vertices off the border. Holes
+    /// border the mesh too, their normals point into the hole.
+    pub fn boundary_normals(&self) -> Vec<Option<[f64; 2]>> {
+        let walk = self.walk();
+        let mut normals = vec![None; self.vertex_count()];
+        for triangle_id in self.result.iter() {
+            let triangle = triangle_id.get(&self.triangles);
+            for (edge, neighbor) in triangle.neighbors.iter().enumerate() {
+                if !neighbor.invalid() && walk.result_index[neighbor.as_usize()] != u32::MAX {
+                    continue;
+                }
+                let [r, p, q] =
+                    [edge, edge + 1, edge + 2].map(|i| triangle.points[i % 3].as_usize());
+                let (p_point, q_point, r_point) = (self.vertex(p), self.vertex(q), self.vertex(r));
+                let (dx, dy) = (q_point.x - p_point.x, q_point.y - p_point.y);
+                let length = dx.hypot(dy);
+                // away from the opposite point, a mirroring transform flips orientation
+                let mut normal = [dy / length, -dx / length];
+                if normal[0] * (r_point.x - p_point.x) + normal[1] * (r_point.y - p_point.y) > 0. {
+                    normal = [-normal[0], -normal[1]];
+                }
+                for vertex in [p, q] {
+                    let sum = normals[vertex].get_or_insert([0., 0.]);
+                    sum[0] += normal[0];
+                    sum[1] += normal[1];
+                }
+            }
+        }
+        for normal in normals.iter_mut().flatten() {
+            let length = normal[0].hypot(normal[1]);
+            // left as zero where border edges fold back onto each other
+            if length > 0. {
+                *normal = [normal[0] / length, normal[1] / length];
+            }
+        }
+        normals
+    }
+
+    /// Attribute at `point`, interpolated barycentrically from `values` of the
+    /// corners of the result triangle containing it, e.g. terrain height. `values`
+    /// is indexed by vertex. `None` if `point` is outside the mesh, or `values`
+    /// misses a corner.
+    pub fn interpolate<T: Lerp>(&self, point: Point, values: &[T]) -> Option<T> {
+        let (index, weights) = self.locate(point)?;
+        let triangle = self.result[index].get(&self.triangles);
+        let [a, b, c] = triangle.points.map(|p| values.get(p.as_usize()));
+        Some(lerp::barycentric([a?, b?, c?], weights))
+    }
+
+    /// Whether each of `points` is in a result triangle, e.g. for particles. Points
+    /// are looked up in grid cell order, nearby points hit the same triangles.
+    pub fn contains_batch(&self, points: &[Point]) -> Vec<bool> {
+        let grid = self.grid();
+        let mut order = points
+            .iter()
+            .enumerate()
+            .filter_map(|(index, p)| Some((grid.cell(*p)?, index)))
+            .collect::<Vec<_>>();
+        order.sort_unstable();
+
+        let mut contains = vec![false; points.len()];
+        for (cell, index) in order {
+            contains[index] = self.locate_in(grid, cell, points[index]).is_some();
+        }
+        contains
+    }
+
+    /// Result triangle containing `point`, and barycentric weights of its corners
+    fn locate(&self, point: Point) -> Option<(usize, [f64; 3])> {
+        let grid = self.grid();
+        self.locate_in(grid, grid.cell(point)?, point)
+    }
+
+    fn locate_in(
+        &self,
+        grid: &TriangleGrid,
+        cell: usize,
+        point: Point,
+    ) -> Option<(usize, [f64; 3])> {
+        // on an edge counts as inside
+        const TOLERANCE: f64 = -1e-12;
+        grid.candidates(cell).iter().find_map(|index| {
+            let index = *index as usize;
+            let triangle = self.result[index].get(&self.triangles);
+            let [a, b, c] = triangle.points.map(|p| self.vertex(p.as_usize()));
+            let area = |p: Point, q: Point, r: Point| {
+                (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x)
+            };
+            // areas share the sign of the triangle, whatever its orientation
+            let total = area(a, b, c);
+            let weights = [
+                area(point, b, c) / total,
+                area(a, point, c) / total,
+                area(a, b, point) / total,
+            ];
+            weights
+                .iter()
+                .all(|w| *w >= TOLERANCE)
+                .then_some((index, weights))
+        })
+    }
+
+    fn grid(&self) -> &TriangleGrid {
+        self.grid.get_or_init(|| {
+            let triangles = (0..self.result.len())
+                .map(|index| self.triangle(index).unwrap().points)
+                .collect::<Vec<_>>();
+            TriangleGrid::new(&triangles)
+        })
+    }
+
+    fn walk(&self) -> &Walk {
+        self.walk.get_or_init(|| {
+            let mut walk = Walk {
+                result_index: vec![u32::MAX; self.triangles.len()],
+                vertex_triangle: vec![u32::MAX; self.vertex_count()],
+            };
+            for (index, triangle_id) in self.result.iter().enumerate() {
+                walk.result_index[triangle_id.as_usize()] = index as u32;
+                for point in triangle_id.get(&self.triangles).points {
+                    walk.vertex_triangle[point.as_usize()] = index as u32;
+                }
+            }
+            walk
+        })
+    }
+
+    /// Vertex indices of the outer polyline, then each hole, in ring order with
+    /// points which split their edges, e.g. steiner points on an edge. Indices are
+    /// the same as [`Self::write_indices_into`], positions are after snapping.
+    pub fn boundary_vertices(&self) -> Vec<Vec<u32>> {
+        origin::boundary_vertices(&self.points, &self.triangles, &self.rings)
+            .into_iter()
+            .map(|ring| ring.iter().map(|p| p.as_usize() as u32).collect())
+            .collect()
+    }
+
+    /// Input edges the sweep split at points on them, e.g. steiner points or
+    /// vertices of another ring. A mesh generated separately next to this one,
+    /// sharing such an edge, needs the same points on it to have no cracks.
+    pub fn edge_splits(&self) -> Vec<EdgeSplit> {
+        origin::edge_splits(&self.points, &self.triangles, &self.rings)
+            .into_iter()
+            .map(|(edge, points)| EdgeSplit {
+                edge,
+                points: points.into_iter().map(|p| self.input_point(p)).collect(),
+            })
+            .collect()
+    }
+}
+
+impl Iterator for Triangles {
+    type Item = Triangle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next < self.end {
+            let index = self.next;
+            self.next += 1;
+
+            // safety: end is at most result len
+            let tri_id = unsafe { self.result.get_unchecked(index) };
+            Some(self.output_triangle(*tri_id))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.next;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Triangles {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.next < self.end {
+            self.end -= 1;
+            self.triangle(self.end)
+        } else {
+            None
+        }
+    }
+}
+
+impl ExactSizeIterator for Triangles {}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::sweeper::tests::{sorted_indices, try_load_from_file};
+    use crate::utils::in_circle;
+    use crate::{datasets, FlipObjective, SweepConfig, SweeperBuilder};
+
+    #[test]
+    fn test_write_into() {
+        let points = try_load_from_file("test_data/bird.dat").unwrap();
+        let triangles = SweeperBuilder::new(points.clone()).build().triangulate();
+
+        let mut positions = vec![0f32; triangles.vertex_count() * 2];
+        assert_eq!(
+            triangles.write_positions_into(&mut positions),
+            points.len() * 2
+        );
+        let mut indices = vec![0u32; triangles.index_count()];
+        assert_eq!(triangles.write_indices_into(&mut indices), 273 * 3);
+        assert_eq!(triangles.validate(), Ok(()));
+
+        for (triangle, chunk) in triangles.zip(indices.chunks(3)) {
+            for (point, index) in triangle.points.iter().zip(chunk) {
+                let index = *index as usize;
+                assert_eq!(positions[index * 2], point.x as f32);
+                assert_eq!(positions[index * 2 + 1], point.y as f32);
+            }
+        }
+
+        // short buffer only gets whole triangles
+        let triangles = SweeperBuilder::new(points).build().triangulate();
+        let mut short = [0u32; 8];
+        assert_eq!(triangles.write_indices_into(&mut short), 6);
+    }
+
+    #[test]
+    fn test_iterator_size() {
+        let mut triangles = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_steiner_points([Point::new(5., 5.)])
+        .build()
+        .triangulate();
+        assert_eq!(triangles.len(), 4);
+        assert!(!triangles.is_empty());
+        let xy = |t: Triangle| t.points.map(|p| (p.x, p.y));
+
+        let first = triangles.next().unwrap();
+        let last = triangles.next_back().unwrap();
+        assert_eq!(triangles.len(), 2);
+        assert_eq!(triangles.triangle_count(), 4);
+        assert_eq!(xy(triangles.triangle(0).unwrap()), xy(first));
+        assert_eq!(xy(triangles.triangle(3).unwrap()), xy(last));
+
+        let middle = triangles.by_ref().rev().collect::<Vec<_>>();
+        assert_eq!(xy(middle[0]), xy(triangles.triangle(2).unwrap()));
+        assert!(triangles.is_empty());
+        assert!(triangles.next_back().is_none());
+        assert!(triangles.next().is_none());
+    }
+
+    #[test]
+    fn test_relax_nonconstrained() {
+        let mut rng = rand::thread_rng();
+        let steiner_points = (0..500)
+            .map(|_| Point::new(rng.gen_range(1.0..799.), rng.gen_range(1.0..799.)))
+            .collect::<Vec<_>>();
+        let mut triangles = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(800., 0.),
+            Point::new(800., 800.),
+            Point::new(0., 800.),
+        ])
+        .add_hole(vec![
+            Point::new(300., 300.),
+            Point::new(300., 500.),
+            Point::new(500., 500.),
+            Point::new(500., 300.),
+        ])
+        .add_steiner_points(
+            steiner_points
+                .into_iter()
+                .filter(|p| !(300.0..=500.).contains(&p.x) || !(300.0..=500.).contains(&p.y)),
+        )
+        .build()
+        .triangulate();
+        let count = triangles.index_count();
+
+        triangles.relax_nonconstrained();
+        assert_eq!(triangles.relax_nonconstrained(), 0);
+        assert_eq!(triangles.index_count(), count);
+
+        let points = &triangles.points;
+        for triangle_id in triangles.result.iter() {
+            let triangle = triangle_id.get(&triangles.triangles);
+            for i in 0..3 {
+                let neighbor = triangle.neighbors[i];
+                if triangle.is_constrained(i) || neighbor.invalid() {
+                    continue;
+                }
+                let neighbor = neighbor.get(&triangles.triangles);
+                assert!(neighbor.interior);
+                let p = triangle.points[i];
+                let op = neighbor.opposite_point(triangle, p);
+                let (ccw, cw) = (triangle.point_ccw(p), triangle.point_cw(p));
+                assert!(!in_circle(
+                    p.get(points),
+                    ccw.get(points),
+                    cw.get(points),
+                    op.get(points),
+                    [p, ccw, cw, op].map(|id| id.as_usize()),
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_triangles_around_vertex() {
+        let triangles = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_hole(vec![
+            Point::new(6., 6.),
+            Point::new(6., 8.),
+            Point::new(8., 8.),
+            Point::new(8., 6.),
+        ])
+        .add_steiner_points([Point::new(3., 3.), Point::new(7., 7.)])
+        .build()
+        .triangulate();
+        let mut indices = vec![0; triangles.index_count()];
+        triangles.write_indices_into(&mut indices);
+        let points = |t: usize| &indices[t * 3..t * 3 + 3];
+
+        for vertex in 0..9 {
+            let around = triangles
+                .triangles_around_vertex(vertex)
+                .collect::<Vec<_>>();
+            let ring = triangles.edge_ring(vertex).collect::<Vec<_>>();
+            // the steiner point is inside, others are on the border
+            let closed = vertex == 8;
+            assert_eq!(ring.len(), around.len() + !closed as usize, "{vertex}");
+            let expected = (0..triangles.triangle_count())
+                .filter(|t| points(*t).contains(&(vertex as u32)))
+                .count();
+            assert_eq!(around.len(), expected);
+            // consecutive triangles share the edge to the ring vertex between them
+            for (i, t) in around.iter().enumerate() {
+                assert!(points(*t).contains(&(ring[i] as u32)));
+                assert!(points(*t).contains(&(ring[(i + 1) % ring.len()] as u32)));
+            }
+        }
+        // CCW around a corner, from the bottom border to the left one
+        let ring = triangles.edge_ring(0).collect::<Vec<_>>();
+        assert_eq!((ring[0], ring[ring.len() - 1]), (1, 3));
+        // in the hole
+        assert_eq!(triangles.triangles_around_vertex(9).count(), 0);
+        assert_eq!(triangles.edge_ring(100).count(), 0);
+    }
+
+    #[test]
+    fn test_boundary_normals() {
+        let square = |min: f64, max: f64| {
+            vec![
+                Point::new(min, min),
+                Point::new(max, min),
+                Point::new(max, max),
+                Point::new(min, max),
+            ]
+        };
+        let builder = SweeperBuilder::new(square(0., 10.))
+            .add_hole(square(4., 6.))
+            .add_steiner_points([Point::new(2., 5.)]);
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        // mirrored, normals still point out of the mesh
+        for builder in [
+            builder.clone(),
+            builder.with_transform(Affine::scale(-1., 1.)),
+        ] {
+            let normals = builder.build().triangulate().boundary_normals();
+            assert_eq!(normals.len(), 9);
+            let close = |vertex: usize, x: f64, y: f64| {
+                let [nx, ny] = normals[vertex].unwrap();
+                (nx - x).abs() < 1e-12 && (ny - y).abs() < 1e-12
+            };
+            // outer corners point out, hole corners into the hole
+            assert!(close(0, -half, -half));
+            assert!(close(2, half, half));
+            assert!(close(4, half, half));
+            assert!(close(6, -half, -half));
+            assert_eq!(normals[8], None);
+        }
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let triangles = datasets::perforated(3)
+            .add_steiner_points([Point::new(11., 2.), Point::new(22., 27.)])
+            .build()
+            .triangulate();
+        let plane = |p: Point| 2. * p.x - 3. * p.y + 1.;
+        let heights = (0..triangles.vertex_count())
+            .map(|index| plane(triangles.vertex(index)))
+            .collect::<Vec<_>>();
+
+        // a plane is linear, interpolation is exact
+        for p in [
+            Point::new(0.5, 0.5),
+            Point::new(13., 29.),
+            Point::new(11., 2.),
+            Point::new(30., 15.),
+            Point::new(27.5, 14.),
+        ] {
+            let height = triangles.interpolate(p, &heights).unwrap();
+            assert!((height - plane(p)).abs() < 1e-9, "{p:?}");
+        }
+        // in a hole, and outside
+        assert_eq!(triangles.interpolate(Point::new(15., 15.), &heights), None);
+        assert_eq!(triangles.interpolate(Point::new(-1., 5.), &heights), None);
+
+        let colors = (0..triangles.vertex_count())
+            .map(|index| [index as f32, 1.])
+            .collect::<Vec<_>>();
+        let color = triangles.interpolate(Point::new(0., 0.), &colors).unwrap();
+        assert_eq!(color, [0., 1.]);
+        assert_eq!(
+            triangles.interpolate(Point::new(0.5, 0.5), &colors[..1]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_contains_batch() {
+        let triangles = datasets::perforated(4)
+            .add_steiner_points([Point::new(1., 1.), Point::new(38., 21.)])
+            .build()
+            .triangulate();
+        let mut rng = rand::thread_rng();
+        let mut points = (0..2000)
+            .map(|_| Point::new(rng.gen_range(-5.0..45.), rng.gen_range(-5.0..45.)))
+            .collect::<Vec<_>>();
+        // corners and a vertex of a hole are on the border
+        points.extend([Point::new(0., 0.), Point::new(40., 40.), Point::new(7., 5.)]);
+
+        let contains = triangles.contains_batch(&points);
+        for (p, contains) in points.iter().zip(contains) {
+            let scanned = (0..triangles.triangle_count()).any(|index| {
+                let [a, b, c] = triangles.triangle(index).unwrap().points;
+                let side = |p: Point, q: Point, r: Point| {
+                    (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x) >= -1e-9
+                };
+                side(a, b, *p) && side(b, c, *p) && side(c, a, *p)
+            });
+            assert_eq!(contains, scanned, "{p:?}");
+        }
+        assert!(triangles.contains_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_precision_report() {
+        let square = vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ];
+        let exact = SweeperBuilder::new(square.clone()).build().triangulate();
+        assert_eq!(exact.precision_report(), PrecisionReport::default());
+
+        let report = SweeperBuilder::new(square)
+            .add_steiner_points([
+                Point::new(5., 3.),
+                Point::new(5.001, 7.),
+                Point::new(0.1, 5.),
+            ])
+            .with_config(SweepConfig::default().x_tolerance(0.01))
+            .with_transform(Affine::scale(2., 1.))
+            .build()
+            .triangulate()
+            .precision_report();
+        // 0.001 moved by 0.002 in sweep space, half of it in input space
+        assert!((report.snap - 0.001).abs() < 1e-12);
+        assert_eq!(report.transform, 0.);
+        // 0.1 isn't exact in f32
+        assert!(report.f32 > 0. && report.f32 < 1e-6);
+        assert_eq!(report.max_f32(), report.snap + report.f32);
+    }
+
+    #[test]
+    fn test_update_positions() {
+        let mut triangles = datasets::uniform(300, 5).build().triangulate();
+        let positions = (0..triangles.vertex_count())
+            .map(|index| triangles.vertex(index))
+            .collect::<Vec<_>>();
+        // the outer square stays, steiner points drift a little
+        let moved = positions
+            .iter()
+            .enumerate()
+            .map(|(index, p)| match index < 4 {
+                true => *p,
+                false => Point::new(
+                    p.x + (index as f64).sin() * 2.,
+                    p.y + (index as f64).cos() * 2.,
+                ),
+            })
+            .collect::<Vec<_>>();
+        assert!(triangles.update_positions(&moved).unwrap() > 0);
+        assert!(triangles.validate().is_ok());
+        let expected = SweeperBuilder::new(moved[..4].to_vec())
+            .add_steiner_points(moved[4..].iter().copied())
+            .build()
+            .triangulate();
+        assert_eq!(sorted_indices(&triangles), sorted_indices(&expected));
+
+        // a jump across the mesh folds triangles over, nothing changes
+        let before = sorted_indices(&triangles);
+        let mut jumped = moved.clone();
+        jumped[10] = Point::new(1000. - jumped[10].x, 1000. - jumped[10].y);
+        assert!(matches!(
+            triangles.update_positions(&jumped),
+            Err(MeshError::Inverted(_))
+        ));
+        assert_eq!(sorted_indices(&triangles), before);
+        assert!(triangles.vertex(10).eq(&moved[10]));
+    }
+
+    #[test]
+    fn test_stable_indices() {
+        let mut triangles = datasets::uniform(200, 7).build().triangulate();
+        let (vertex_count, triangle_count) = (triangles.vertex_count(), triangles.triangle_count());
+        let mut positions = (0..vertex_count)
+            .map(|index| triangles.vertex(index))
+            .collect::<Vec<_>>();
+        let check = |triangles: &Triangles, positions: &[Point]| {
+            assert_eq!(triangles.vertex_count(), vertex_count);
+            assert_eq!(triangles.triangle_count(), triangle_count);
+            for (index, p) in positions.iter().enumerate() {
+                assert!(triangles.vertex(index).eq(p));
+            }
+            // every vertex is still in some triangle
+            let mut used = vec![false; vertex_count];
+            for index in sorted_indices(triangles).into_iter().flatten() {
+                used[index as usize] = true;
+            }
+            assert!(used.into_iter().all(|used| used));
+        };
+
+        triangles.relax_with(&FlipObjective::ShortestEdges);
+        check(&triangles, &positions);
+        positions[20] = Point::new(positions[20].x + 3., positions[20].y - 2.);
+        triangles.move_point(20, positions[20]).unwrap();
+        check(&triangles, &positions);
+        for p in positions[4..].iter_mut() {
+            *p = Point::new(p.x + 0.5, p.y + 0.25);
+        }
+        triangles.update_positions(&positions).unwrap();
+        check(&triangles, &positions);
+    }
+
+    #[test]
+    fn test_move_point() {
+        let mut triangles = datasets::uniform(300, 6).build().triangulate();
+        let mut positions = (0..triangles.vertex_count())
+            .map(|index| triangles.vertex(index))
+            .collect::<Vec<_>>();
+        // walk lookups are built before the moves and kept up to date
+        assert!(triangles.triangles_around_vertex(4).count() > 0);
+        for step in 0..20 {
+            for index in (4..positions.len()).step_by(7) {
+                let p = positions[index];
+                let to = Point::new(
+                    p.x + ((index + step) as f64).sin() * 1.5,
+                    p.y + ((index * step) as f64).cos() * 1.5,
+                );
+                if triangles.move_point(index, to).is_ok() {
+                    positions[index] = to;
+                }
+            }
+        }
+        assert!(triangles.validate().is_ok());
+        let expected = SweeperBuilder::new(positions[..4].to_vec())
+            .add_steiner_points(positions[4..].iter().copied())
+            .build()
+            .triangulate();
+        assert_eq!(sorted_indices(&triangles), sorted_indices(&expected));
+        for vertex in 0..triangles.vertex_count() {
+            assert!(triangles.triangles_around_vertex(vertex).count() > 0);
+        }
+
+        let before = sorted_indices(&triangles);
+        assert!(matches!(
+            triangles.move_point(10, Point::new(-5., -5.)),
+            Err(MeshError::Inverted(_))
+        ));
+        assert_eq!(sorted_indices(&triangles), before);
+        assert!(triangles.vertex(10).eq(&positions[10]));
+    }
+}
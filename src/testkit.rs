@@ -0,0 +1,304 @@
+//! Helpers for downstream regression tests, e.g. a directory of polygon dumps
+//! that once broke triangulation, or golden snapshots of meshes, checked in CI
+//! against each crate upgrade.
+//!
+//! # Example
+//! ```rust,no_run
+//!    use poly2tri_rs::{loader, testkit};
+//!
+//!    let report = testkit::run_corpus("tests/corpus").unwrap();
+//!    assert!(report.failed.is_empty(), "{report:?}");
+//!
+//!    // written on first run, or with P2T_UPDATE_SNAPSHOTS=1
+//!    let triangles = loader::load_file("tests/bird.dat").unwrap().build().triangulate();
+//!    testkit::assert_snapshot(triangles, "tests/snapshots/bird.txt");
+//! ```
+use std::cmp::Ordering;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use crate::loader::{self, LoaderError};
+use crate::{Context, InputReport, Observer, Point, Sweeper, Triangle, TriangleId};
+
+/// Env var that makes [`assert_snapshot`] overwrite snapshots instead of comparing
+pub const UPDATE_SNAPSHOTS_ENV: &str = "P2T_UPDATE_SNAPSHOTS";
+
+/// Why a corpus file failed
+#[derive(thiserror::Error, Debug)]
+pub enum CorpusFailure {
+    #[error("load failed: {0}")]
+    Load(#[from] LoaderError),
+    #[error(transparent)]
+    Input(#[from] InputReport),
+    #[error("{} illegal triangle pairs", .0.len())]
+    Illegal(Vec<(TriangleId, TriangleId)>),
+    #[error("panicked: {0}")]
+    Panic(String),
+}
+
+/// Result of [`run_corpus`], files sorted by path
+#[derive(Debug, Default)]
+pub struct CorpusReport {
+    pub passed: Vec<PathBuf>,
+    pub failed: Vec<(PathBuf, CorpusFailure)>,
+}
+
+/// Run every file in `dir` through [`crate::SweeperBuilder::try_build`] and
+/// triangulation, then check the result is delaunay. Files are loaded with
+/// [`loader::load_file`], sub directories and dot files are skipped. Panics are
+/// caught and reported as failures.
+pub fn run_corpus(dir: impl AsRef<Path>) -> std::io::Result<CorpusReport> {
+    let mut paths = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with('.'));
+        if path.is_file() && !hidden {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut report = CorpusReport::default();
+    for path in paths {
+        match panic::catch_unwind(AssertUnwindSafe(|| run_file(&path))) {
+            Ok(Ok(())) => report.passed.push(path),
+            Ok(Err(failure)) => report.failed.push((path, failure)),
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                report.failed.push((path, CorpusFailure::Panic(message)));
+            }
+        }
+    }
+    Ok(report)
+}
+
+fn run_file(path: &Path) -> Result<(), CorpusFailure> {
+    let builder = loader::load_file(&path.to_string_lossy())?;
+    let mut check = Check::default();
+    builder.try_build()?.triangulate_with_observer(&mut check);
+    if check.illegal.is_empty() {
+        Ok(())
+    } else {
+        Err(CorpusFailure::Illegal(check.illegal))
+    }
+}
+
+#[derive(Default)]
+struct Check {
+    illegal: Vec<(TriangleId, TriangleId)>,
+}
+
+impl Observer for Check {
+    fn finalized(&mut self, context: &Context) {
+        self.illegal = Sweeper::illegal_triangles(context);
+    }
+}
+
+fn cmp_point(a: &Point, b: &Point) -> Ordering {
+    a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+}
+
+/// Snapshot text of a mesh, one triangle per line as `x0 y0 x1 y1 x2 y2`.
+/// Each triangle starts at its smallest point, keeping its winding, and lines
+/// are sorted, so the text only depends on the mesh, not on emit order.
+pub fn snapshot(triangles: impl IntoIterator<Item = Triangle>) -> String {
+    let mut canonical = triangles
+        .into_iter()
+        .map(|t| {
+            let first = (0..3)
+                .min_by(|&i, &j| cmp_point(&t.points[i], &t.points[j]))
+                .unwrap();
+            [0, 1, 2].map(|i| t.points[(first + i) % 3])
+        })
+        .collect::<Vec<_>>();
+    canonical.sort_by(|a, b| {
+        a.iter()
+            .zip(b)
+            .map(|(p, q)| cmp_point(p, q))
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let mut text = String::new();
+    for [a, b, c] in canonical {
+        // f64 display is shortest round trip, so snapshots are exact
+        text += &format!("{} {} {} {} {} {}\n", a.x, a.y, b.x, b.y, c.x, c.y);
+    }
+    text
+}
+
+/// Triangle lines only in the expected or only in the actual snapshot
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    pub missing: Vec<String>,
+    pub extra: Vec<String>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
+}
+
+impl std::fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} missing, {} extra triangles",
+            self.missing.len(),
+            self.extra.len()
+        )?;
+        for line in self.missing.iter() {
+            writeln!(f, "- {line}")?;
+        }
+        for line in self.extra.iter() {
+            writeln!(f, "+ {line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Diff snapshot texts line by line, repeated lines are counted
+pub fn diff_snapshots(expected: &str, actual: &str) -> SnapshotDiff {
+    fn sorted(text: &str) -> Vec<&str> {
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>();
+        lines.sort_unstable();
+        lines
+    }
+    let (expected, actual) = (sorted(expected), sorted(actual));
+
+    let mut diff = SnapshotDiff::default();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        match (expected.get(i), actual.get(j)) {
+            (Some(e), Some(a)) if e == a => {
+                i += 1;
+                j += 1;
+            }
+            (Some(e), a) if a.is_none_or(|a| e < a) => {
+                diff.missing.push(e.to_string());
+                i += 1;
+            }
+            (_, a) => {
+                diff.extra.push(a.unwrap().to_string());
+                j += 1;
+            }
+        }
+    }
+    diff
+}
+
+/// Compare mesh of `triangles` to snapshot at `path`. The snapshot is written
+/// if missing, or if [`UPDATE_SNAPSHOTS_ENV`] is set.
+///
+/// # Panics
+/// if the mesh differs, listing missing and extra triangles
+pub fn assert_snapshot(triangles: impl IntoIterator<Item = Triangle>, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    let actual = snapshot(triangles);
+    let update = std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some();
+    if update || !path.exists() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create snapshot dir");
+        }
+        std::fs::write(path, actual).expect("write snapshot");
+        return;
+    }
+
+    let expected = std::fs::read_to_string(path).expect("read snapshot");
+    let diff = diff_snapshots(&expected, &actual);
+    if !diff.is_empty() {
+        panic!(
+            "mesh differs from snapshot {}, set {UPDATE_SNAPSHOTS_ENV}=1 to update\n{diff}",
+            path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SweeperBuilder;
+
+    #[test]
+    fn test_run_corpus() {
+        // test_data gets temp files from other tests, run a copy
+        let dir = std::env::temp_dir().join(format!("p2t_corpus_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["bird.dat", "nazca_heron.dat"] {
+            std::fs::copy(format!("test_data/{name}"), dir.join(name)).unwrap();
+        }
+        std::fs::write(dir.join("nan.dat"), "0 0\n10 0\nNaN 10\n").unwrap();
+        std::fs::write(dir.join("broken.dat"), "0 0\n10\n").unwrap();
+        std::fs::write(dir.join(".hidden"), "").unwrap();
+        let report = run_corpus(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            report.passed,
+            ["bird.dat", "nazca_heron.dat"].map(|name| dir.join(name))
+        );
+        assert_eq!(report.failed.len(), 2);
+        assert_eq!(report.failed[0].0, dir.join("broken.dat"));
+        assert!(matches!(report.failed[0].1, CorpusFailure::Panic(_)));
+        assert!(matches!(report.failed[1].1, CorpusFailure::Input(_)));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let square = || {
+            SweeperBuilder::new(vec![
+                Point::new(0., 0.),
+                Point::new(10., 0.),
+                Point::new(10., 10.),
+                Point::new(0., 10.),
+            ])
+        };
+        let text = snapshot(square().build().triangulate());
+        assert_eq!(text.lines().count(), 2);
+        // emit order and start point don't matter
+        let mut reversed = square().build().triangulate().collect::<Vec<_>>();
+        reversed.reverse();
+        reversed[0].points.rotate_left(1);
+        assert_eq!(snapshot(reversed), text);
+
+        let refined = snapshot(
+            square()
+                .add_steiner_points([Point::new(5., 5.)])
+                .build()
+                .triangulate(),
+        );
+        let diff = diff_snapshots(&text, &refined);
+        assert_eq!(diff.missing.len(), 2);
+        assert_eq!(diff.extra.len(), 4);
+        assert!(diff.extra.iter().all(|l| l.contains("5 5")));
+        assert!(diff_snapshots(&refined, &refined).is_empty());
+        assert!(diff
+            .to_string()
+            .starts_with("2 missing, 4 extra triangles\n- "));
+
+        let path = std::env::temp_dir().join(format!("p2t_snapshot_{}", std::process::id()));
+        assert_snapshot(square().build().triangulate(), &path);
+        assert_snapshot(square().build().triangulate(), &path);
+        let result = panic::catch_unwind(|| {
+            let triangles = square()
+                .add_steiner_points([Point::new(5., 5.)])
+                .build()
+                .triangulate();
+            assert_snapshot(triangles, &path)
+        });
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,681 @@
+//! Split a large polygon layer into square tiles, triangulate tiles independently
+//! and stitch them back into one mesh.
+//!
+//! Polygons are clipped by each tile's rect, the parts of tile border inside a
+//! polygon become constrained edges of that tile. Both tiles sharing a border clip
+//! against the same line, so they get identical border points and the stitched
+//! mesh has no T-junctions.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{tiling::TilingBuilder, Point};
+//!
+//!    let tiled = TilingBuilder::new(4.)
+//!        .add_polygon(
+//!            vec![
+//!                Point::new(0., 0.),
+//!                Point::new(10., 0.),
+//!                Point::new(10., 10.),
+//!                Point::new(0., 10.),
+//!            ],
+//!            vec![],
+//!        )
+//!        .parallel(true)
+//!        .build()
+//!        .triangulate();
+//!    let mesh = tiled.stitch();
+//!    assert_eq!(tiled.tiles.len(), 9);
+//!    assert!(mesh.triangles.len() >= 18);
+//! ```
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+use crate::utils::{dedup_ring, signed_area};
+use crate::{Point, SweeperBuilder, Triangle};
+
+/// Tile coordinate, tile `(x, y)` covers
+/// `[origin.x + x * size, origin.x + (x + 1) * size] x [origin.y + y * size, ...]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TileKey {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// A polygon with holes
+#[derive(Debug, Clone, Default)]
+pub struct Polygon {
+    pub outer: Vec<Point>,
+    pub holes: Vec<Vec<Point>>,
+}
+
+/// Builder for [`Tiling`]
+#[derive(Clone)]
+pub struct TilingBuilder {
+    tile_size: f64,
+    origin: Point,
+    parallel: bool,
+    polygons: Vec<Polygon>,
+}
+
+impl TilingBuilder {
+    /// Create a builder with square tiles of `tile_size`
+    pub fn new(tile_size: f64) -> Self {
+        assert!(tile_size > 0., "tile size should be positive");
+        Self {
+            tile_size,
+            origin: Point::new(0., 0.),
+            parallel: false,
+            polygons: vec![],
+        }
+    }
+
+    /// Set the origin of tile grid, default is (0, 0)
+    pub fn origin(mut self, origin: Point) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Triangulate tiles on multiple threads. The result is the same as on one
+    /// thread, bit for bit, whatever the thread count or scheduling
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Add a polygon with holes to the layer
+    pub fn add_polygon(mut self, outer: Vec<Point>, holes: Vec<Vec<Point>>) -> Self {
+        self.polygons.push(Polygon { outer, holes });
+        self
+    }
+
+    /// Add multiple polygons to the layer
+    pub fn add_polygons(mut self, polygons: impl IntoIterator<Item = Polygon>) -> Self {
+        self.polygons.extend(polygons);
+        self
+    }
+
+    /// Clip all polygons into tiles
+    pub fn build(mut self) -> Tiling {
+        let mut tiles = BTreeMap::<TileKey, Vec<Polygon>>::new();
+
+        for polygon in std::mem::take(&mut self.polygons) {
+            let rings = oriented_rings(polygon);
+            let Some(outer) = rings.first() else {
+                continue;
+            };
+
+            let (min, max) = bounds(outer);
+            let (x0, y0) = self.tile_index(min);
+            let (x1, y1) = self.tile_index(max);
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    let key = TileKey { x, y };
+                    let clipped = clip(&rings, &self.tile_rect(key));
+                    if !clipped.is_empty() {
+                        tiles.entry(key).or_default().extend(clipped);
+                    }
+                }
+            }
+        }
+
+        Tiling {
+            tile_size: self.tile_size,
+            origin: self.origin,
+            parallel: self.parallel,
+            tiles: tiles.into_iter().collect(),
+        }
+    }
+
+    fn tile_index(&self, point: Point) -> (i64, i64) {
+        (
+            ((point.x - self.origin.x) / self.tile_size).floor() as i64,
+            ((point.y - self.origin.y) / self.tile_size).floor() as i64,
+        )
+    }
+
+    fn tile_rect(&self, key: TileKey) -> Rect {
+        tile_rect(self.origin, self.tile_size, key)
+    }
+}
+
+/// Polygons clipped into tiles, ready to triangulate
+pub struct Tiling {
+    tile_size: f64,
+    origin: Point,
+    parallel: bool,
+    tiles: Vec<(TileKey, Vec<Polygon>)>,
+}
+
+impl Tiling {
+    /// Clipped polygons of each tile
+    pub fn tiles(&self) -> impl Iterator<Item = (TileKey, &[Polygon])> {
+        self.tiles.iter().map(|(key, p)| (*key, p.as_slice()))
+    }
+
+    /// Bounds of tile as (min, max)
+    pub fn tile_bounds(&self, key: TileKey) -> (Point, Point) {
+        let rect = tile_rect(self.origin, self.tile_size, key);
+        (Point::new(rect.x0, rect.y0), Point::new(rect.x1, rect.y1))
+    }
+
+    /// Triangulate all tiles
+    pub fn triangulate(self) -> TiledTriangles {
+        let tiles = if self.parallel && self.tiles.len() > 1 {
+            let threads = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+                .min(self.tiles.len());
+            // threads take the next tile when done, for balance, and results go to
+            // their tile's slot, so the order is the tile order regardless
+            let next = AtomicUsize::new(0);
+            let mut slots = (0..self.tiles.len()).map(|_| None).collect::<Vec<_>>();
+            std::thread::scope(|scope| {
+                let handles = (0..threads)
+                    .map(|_| {
+                        scope.spawn(|| {
+                            let mut done = vec![];
+                            loop {
+                                let index = next.fetch_add(1, Relaxed);
+                                let Some(tile) = self.tiles.get(index) else {
+                                    break done;
+                                };
+                                done.push((index, triangulate_tile(tile)));
+                            }
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                for handle in handles {
+                    for (index, tile) in handle.join().expect("tile triangulation panicked") {
+                        slots[index] = Some(tile);
+                    }
+                }
+            });
+            slots.into_iter().map(Option::unwrap).collect()
+        } else {
+            self.tiles.iter().map(triangulate_tile).collect()
+        };
+
+        TiledTriangles { tiles }
+    }
+}
+
+fn triangulate_tile((key, polygons): &(TileKey, Vec<Polygon>)) -> TileTriangles {
+    let triangles = polygons
+        .iter()
+        .flat_map(|polygon| {
+            SweeperBuilder::new(polygon.outer.clone())
+                .add_holes(polygon.holes.iter().cloned())
+                .build()
+                .triangulate()
+        })
+        .collect();
+    TileTriangles {
+        key: *key,
+        triangles,
+    }
+}
+
+/// Triangles of one tile
+#[derive(Debug, Clone)]
+pub struct TileTriangles {
+    pub key: TileKey,
+    pub triangles: Vec<Triangle>,
+}
+
+/// Result of [`Tiling::triangulate`], sorted by tile key
+#[derive(Debug, Clone)]
+pub struct TiledTriangles {
+    pub tiles: Vec<TileTriangles>,
+}
+
+/// Indexed mesh, tiles' shared border points are merged
+#[derive(Debug, Clone, Default)]
+pub struct StitchedMesh {
+    pub points: Vec<Point>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl TiledTriangles {
+    /// Merge tiles into one indexed mesh. Points are indexed in order of first use,
+    /// tile by tile, so ids are stable across runs
+    pub fn stitch(&self) -> StitchedMesh {
+        let mut mesh = StitchedMesh::default();
+        let mut index = HashMap::<(u64, u64), usize>::new();
+
+        for triangle in self.tiles.iter().flat_map(|t| t.triangles.iter()) {
+            let indices = triangle.points.map(|p| {
+                // +0.0 turns -0.0 into 0.0, so both have same bits
+                let key = ((p.x + 0.).to_bits(), (p.y + 0.).to_bits());
+                *index.entry(key).or_insert_with(|| {
+                    mesh.points.push(p);
+                    mesh.points.len() - 1
+                })
+            });
+            mesh.triangles.push(indices);
+        }
+        mesh
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+}
+
+impl Rect {
+    fn contains(&self, p: Point) -> bool {
+        p.x >= self.x0 && p.x <= self.x1 && p.y >= self.y0 && p.y <= self.y1
+    }
+
+    fn strictly_outside(&self, p: Point) -> bool {
+        !self.contains(p)
+    }
+
+    /// position of a point on border, going ccw from (x0, y0), in [0, 4)
+    fn border_position(&self, p: Point) -> f64 {
+        let (w, h) = (self.x1 - self.x0, self.y1 - self.y0);
+        if p.y == self.y0 && p.x < self.x1 {
+            (p.x - self.x0) / w
+        } else if p.x == self.x1 && p.y < self.y1 {
+            1. + (p.y - self.y0) / h
+        } else if p.y == self.y1 && p.x > self.x0 {
+            2. + (self.x1 - p.x) / w
+        } else {
+            3. + (self.y1 - p.y) / h
+        }
+    }
+
+    fn corner(&self, i: usize) -> Point {
+        match i % 4 {
+            0 => Point::new(self.x0, self.y0),
+            1 => Point::new(self.x1, self.y0),
+            2 => Point::new(self.x1, self.y1),
+            _ => Point::new(self.x0, self.y1),
+        }
+    }
+
+    /// Clip segment a->b, returns the inside part as (t0, p0, t1, p1).
+    /// Points on border are snapped onto the border line.
+    fn clip_segment(&self, a: Point, b: Point) -> Option<(f64, Point, f64, Point)> {
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let mut t0 = 0.;
+        let mut t1 = 1.;
+        // (side value, is x axis) for the side which limits t0/t1
+        let mut snap0 = None;
+        let mut snap1 = None;
+
+        for (p, q, value, x_axis) in [
+            (-dx, a.x - self.x0, self.x0, true),
+            (dx, self.x1 - a.x, self.x1, true),
+            (-dy, a.y - self.y0, self.y0, false),
+            (dy, self.y1 - a.y, self.y1, false),
+        ] {
+            if p == 0. {
+                if q < 0. {
+                    return None;
+                }
+                continue;
+            }
+            let r = q / p;
+            if p < 0. {
+                if r > t0 {
+                    t0 = r;
+                    snap0 = Some((value, x_axis));
+                }
+            } else if r < t1 {
+                t1 = r;
+                snap1 = Some((value, x_axis));
+            }
+        }
+        if t0 > t1 {
+            return None;
+        }
+
+        let at = |t: f64, snap: Option<(f64, bool)>| {
+            if t == 0. {
+                return a;
+            }
+            if t == 1. {
+                return b;
+            }
+            match snap {
+                Some((value, true)) => Point::new(value, a.y + t * dy),
+                Some((value, false)) => Point::new(a.x + t * dx, value),
+                None => Point::new(a.x + t * dx, a.y + t * dy),
+            }
+        };
+        Some((t0, at(t0, snap0), t1, at(t1, snap1)))
+    }
+}
+
+fn tile_rect(origin: Point, size: f64, key: TileKey) -> Rect {
+    Rect {
+        x0: origin.x + key.x as f64 * size,
+        y0: origin.y + key.y as f64 * size,
+        x1: origin.x + (key.x + 1) as f64 * size,
+        y1: origin.y + (key.y + 1) as f64 * size,
+    }
+}
+
+fn bounds(ring: &[Point]) -> (Point, Point) {
+    let mut min = Point::new(f64::MAX, f64::MAX);
+    let mut max = Point::new(f64::MIN, f64::MIN);
+    for p in ring {
+        min = Point::new(min.x.min(p.x), min.y.min(p.y));
+        max = Point::new(max.x.max(p.x), max.y.max(p.y));
+    }
+    (min, max)
+}
+
+/// ccw ring when `ccw` is true, otherwise cw
+fn oriented(mut ring: Vec<Point>, ccw: bool) -> Vec<Point> {
+    if ring.len() > 1 && ring[0].eq(ring.last().unwrap()) {
+        ring.pop();
+    }
+    if (signed_area(&ring) > 0.) != ccw {
+        ring.reverse();
+    }
+    ring
+}
+
+/// ccw outer then cw holes, rings with less than 3 points are dropped
+fn oriented_rings(polygon: Polygon) -> Vec<Vec<Point>> {
+    let mut rings = Vec::with_capacity(polygon.holes.len() + 1);
+    rings.push(oriented(polygon.outer, true));
+    for hole in polygon.holes {
+        rings.push(oriented(hole, false));
+    }
+    rings.into_iter().filter(|r| r.len() >= 3).collect()
+}
+
+/// Clip a polygon by the rect `min`..`max`, see [`SweeperBuilder::clip_to`]
+pub(crate) fn clip_polygon(polygon: Polygon, min: Point, max: Point) -> Vec<Polygon> {
+    let rings = oriented_rings(polygon);
+    if rings.is_empty() {
+        return vec![];
+    }
+    let rect = Rect {
+        x0: min.x,
+        y0: min.y,
+        x1: max.x,
+        y1: max.y,
+    };
+    clip(&rings, &rect)
+}
+
+/// even-odd test against all rings
+fn in_rings(rings: &[Vec<Point>], p: Point) -> bool {
+    let mut inside = false;
+    for ring in rings {
+        for i in 0..ring.len() {
+            let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+            if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// drop repeated points and spikes
+fn clean_ring(ring: Vec<Point>) -> Option<Vec<Point>> {
+    let result = dedup_ring(ring);
+    (result.len() >= 3 && signed_area(&result) != 0.).then_some(result)
+}
+
+/// A ring part inside rect, enters and exits through border
+struct Piece {
+    entry: f64,
+    exit: f64,
+    points: Vec<Point>,
+}
+
+/// Clip rings (first is ccw outer, others are cw holes) by rect
+fn clip(rings: &[Vec<Point>], rect: &Rect) -> Vec<Polygon> {
+    let mut pieces = Vec::<Piece>::new();
+    let mut outers = Vec::<Vec<Point>>::new();
+    let mut holes = Vec::<Vec<Point>>::new();
+
+    for (ring_idx, ring) in rings.iter().enumerate() {
+        let Some(start) = ring.iter().position(|p| rect.strictly_outside(*p)) else {
+            // ring fully inside
+            if ring_idx == 0 {
+                outers.push(ring.clone());
+            } else {
+                holes.push(ring.clone());
+            }
+            continue;
+        };
+
+        let mut current: Option<Vec<Point>> = None;
+        for i in 0..ring.len() {
+            let a = ring[(start + i) % ring.len()];
+            let b = ring[(start + i + 1) % ring.len()];
+            let segment = rect.clip_segment(a, b).filter(|(t0, _, t1, _)| t0 < t1);
+
+            match segment {
+                Some((t0, p0, t1, p1)) => {
+                    if current.is_some() && t0 > 0. {
+                        finish_piece(&mut pieces, current.take().unwrap(), rect);
+                    }
+                    let points = current.get_or_insert_with(|| vec![p0]);
+                    points.push(p1);
+                    if t1 < 1. {
+                        finish_piece(&mut pieces, current.take().unwrap(), rect);
+                    }
+                }
+                None => {
+                    if let Some(points) = current.take() {
+                        finish_piece(&mut pieces, points, rect);
+                    }
+                }
+            }
+        }
+        if let Some(points) = current.take() {
+            finish_piece(&mut pieces, points, rect);
+        }
+    }
+
+    if pieces.is_empty() && in_rings(rings, rect.corner(0)) {
+        // rect is fully covered
+        outers.push((0..4).map(|i| rect.corner(i)).collect());
+    }
+
+    // walk along border ccw from each exit to the next entry
+    let mut used = vec![false; pieces.len()];
+    for first in 0..pieces.len() {
+        if used[first] {
+            continue;
+        }
+        let mut ring = Vec::new();
+        let mut current = first;
+        loop {
+            used[current] = true;
+            ring.extend_from_slice(&pieces[current].points);
+            let exit = pieces[current].exit;
+
+            let next = (0..pieces.len())
+                .filter(|&i| !used[i] || i == first)
+                .min_by(|&i, &j| {
+                    let di = (pieces[i].entry - exit).rem_euclid(4.);
+                    let dj = (pieces[j].entry - exit).rem_euclid(4.);
+                    di.total_cmp(&dj)
+                })
+                .unwrap();
+
+            let distance = (pieces[next].entry - exit).rem_euclid(4.);
+            let first_corner = exit.floor() as usize + 1;
+            let mut corner = first_corner;
+            while (corner as f64) < exit + distance {
+                ring.push(rect.corner(corner));
+                corner += 1;
+            }
+
+            if next == first {
+                break;
+            }
+            current = next;
+        }
+        outers.push(ring);
+    }
+
+    let mut polygons = outers
+        .into_iter()
+        .filter_map(clean_ring)
+        .map(|outer| Polygon {
+            outer,
+            holes: vec![],
+        })
+        .collect::<Vec<_>>();
+    for hole in holes.into_iter().filter_map(clean_ring) {
+        if let Some(polygon) = polygons
+            .iter_mut()
+            .find(|p| in_rings(std::slice::from_ref(&p.outer), hole[0]))
+        {
+            polygon.holes.push(hole);
+        }
+    }
+    polygons
+}
+
+fn finish_piece(pieces: &mut Vec<Piece>, points: Vec<Point>, rect: &Rect) {
+    if points.len() < 2 {
+        return;
+    }
+    pieces.push(Piece {
+        entry: rect.border_position(points[0]),
+        exit: rect.border_position(*points.last().unwrap()),
+        points,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(mesh: &StitchedMesh) -> f64 {
+        mesh.triangles
+            .iter()
+            .map(|t| signed_area(&t.map(|i| mesh.points[i])).abs())
+            .sum()
+    }
+
+    /// every edge is shared by at most 2 triangles, and the border edges
+    /// (used once) only lie on the polygon border
+    fn assert_conforming(mesh: &StitchedMesh, on_border: impl Fn(Point, Point) -> bool) {
+        let mut edges = HashMap::<(usize, usize), usize>::new();
+        for t in mesh.triangles.iter() {
+            for i in 0..3 {
+                let (a, b) = (t[i], t[(i + 1) % 3]);
+                *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        for ((a, b), count) in edges {
+            assert!(count <= 2);
+            if count == 1 {
+                assert!(on_border(mesh.points[a], mesh.points[b]));
+            }
+        }
+    }
+
+    fn square(x0: f64, y0: f64, x1: f64, y1: f64) -> Vec<Point> {
+        vec![
+            Point::new(x0, y0),
+            Point::new(x1, y0),
+            Point::new(x1, y1),
+            Point::new(x0, y1),
+        ]
+    }
+
+    #[test]
+    fn test_tiling_square() {
+        let tiled = TilingBuilder::new(4.)
+            .add_polygon(square(0., 0., 10., 10.), vec![])
+            .build()
+            .triangulate();
+        assert_eq!(tiled.tiles.len(), 9);
+
+        let mesh = tiled.stitch();
+        assert!((area(&mesh) - 100.).abs() < 1e-9);
+        assert_conforming(&mesh, |a, b| {
+            (a.x == b.x && (a.x == 0. || a.x == 10.)) || (a.y == b.y && (a.y == 0. || a.y == 10.))
+        });
+    }
+
+    #[test]
+    fn test_tiling_with_hole() {
+        // concave outer, and a hole crossing tile borders
+        let outer = vec![
+            Point::new(0.5, 0.5),
+            Point::new(9.5, 1.),
+            Point::new(9., 9.5),
+            Point::new(5.5, 4.5),
+            Point::new(1., 9.),
+        ];
+        let hole = square(3., 1.5, 6., 3.5);
+        let expected = signed_area(&outer).abs() - signed_area(&hole).abs();
+
+        let tiling = TilingBuilder::new(2.5)
+            .origin(Point::new(0.1, 0.2))
+            .add_polygon(outer, vec![hole])
+            .build();
+        assert!(tiling.tiles().count() > 4);
+
+        let serial = tiling.triangulate();
+        let mesh = serial.stitch();
+        assert!((area(&mesh) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tiling_parallel() {
+        let polygons = (0..5).map(|i| Polygon {
+            outer: square(i as f64 * 3., 0., i as f64 * 3. + 2.5, 7.),
+            holes: vec![square(i as f64 * 3. + 1., 1., i as f64 * 3. + 1.5, 6.)],
+        });
+        let builder = TilingBuilder::new(1.7).add_polygons(polygons);
+        let serial = builder.clone().build().triangulate().stitch();
+        let xy = |mesh: &StitchedMesh| mesh.points.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+        // scheduling differs between runs, the result doesn't
+        for _ in 0..4 {
+            let parallel = builder
+                .clone()
+                .parallel(true)
+                .build()
+                .triangulate()
+                .stitch();
+            assert_eq!(serial.triangles, parallel.triangles);
+            assert_eq!(xy(&serial), xy(&parallel));
+        }
+        assert!((area(&serial) - 5. * (2.5 * 7. - 0.5 * 5.)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_clip_covered_tile() {
+        let rings = vec![
+            square(0., 0., 10., 10.),
+            oriented(square(4., 4., 6., 6.), false),
+        ];
+        let rect = Rect {
+            x0: 3.,
+            y0: 3.,
+            x1: 7.,
+            y1: 7.,
+        };
+        let clipped = clip(&rings, &rect);
+        assert_eq!(clipped.len(), 1);
+        assert_eq!(clipped[0].outer.len(), 4);
+        assert_eq!(clipped[0].holes.len(), 1);
+
+        // rect inside hole
+        let rect = Rect {
+            x0: 4.5,
+            y0: 4.5,
+            x1: 5.5,
+            y1: 5.5,
+        };
+        assert!(clip(&rings, &rect).is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+//! Sweep timings in the Chrome trace event format, open the output in
+//! `chrome://tracing` or <https://ui.perfetto.dev>.
+//!
+//! Slices are the sweep and finalize phases, each point and edge event, and
+//! each legalization, nested in the event running it.
+//!
+//! # Example
+//! ```rust
+//!    use poly2tri_rs::{trace::PerfettoObserver, Point, SweeperBuilder};
+//!
+//!    let sweeper = SweeperBuilder::new(vec![
+//!        Point::new(0., 0.),
+//!        Point::new(10., 0.),
+//!        Point::new(10., 10.),
+//!        Point::new(0., 10.),
+//!    ])
+//!    .build();
+//!
+//!    let mut trace = PerfettoObserver::new();
+//!    sweeper.triangulate_with_observer(&mut trace);
+//!
+//!    let mut json = vec![];
+//!    trace.write_json(&mut json).unwrap();
+//!    // std::fs::write("sweep.json", json)
+//!    assert!(json.starts_with(br#"{"traceEvents":["#));
+//! ```
+use std::io::{self, Write};
+use std::time::Instant;
+
+use crate::{Context, Edge, Observer, ObserverMask, PointId, TriangleId};
+
+/// One complete ("X") trace event
+#[derive(Debug, Clone, Copy)]
+struct Slice {
+    name: &'static str,
+    /// start, in micro seconds since the observer was created
+    ts: f64,
+    dur: f64,
+    /// point id, or the lower point of an edge, or the triangle legalized
+    arg: Option<(&'static str, usize)>,
+}
+
+/// Records sweep timings, then writes them by [`Self::write_json`]
+///
+/// Time counts from [`Self::new`], create it right before triangulating. Events
+/// only report when they are done, so a point or edge slice starts where the
+/// previous dispatched event ended, with a sampling mask it covers the skipped
+/// events too.
+pub struct PerfettoObserver {
+    origin: Instant,
+    mask: ObserverMask,
+    /// end of the last point or edge event, or the origin
+    last: f64,
+    legalize_start: Option<f64>,
+    sweep_done: f64,
+    slices: Vec<Slice>,
+}
+
+impl Default for PerfettoObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PerfettoObserver {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            mask: ObserverMask::ALL,
+            last: 0.,
+            legalize_start: None,
+            sweep_done: 0.,
+            slices: vec![],
+        }
+    }
+
+    /// Record only events in `mask`, e.g. sample huge sweeps with
+    /// `ObserverMask::ALL.every(100)`
+    pub fn with_mask(mut self, mask: ObserverMask) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// Slices recorded so far
+    pub fn len(&self) -> usize {
+        self.slices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slices.is_empty()
+    }
+
+    /// Write recorded slices as a Chrome trace JSON object
+    pub fn write_json(&self, mut w: impl Write) -> io::Result<()> {
+        write!(w, r#"{{"traceEvents":["#)?;
+        for (i, slice) in self.slices.iter().enumerate() {
+            if i > 0 {
+                write!(w, ",")?;
+            }
+            write!(
+                w,
+                r#"{{"name":"{}","cat":"p2t","ph":"X","pid":1,"tid":1,"ts":{:.3},"dur":{:.3}"#,
+                slice.name, slice.ts, slice.dur
+            )?;
+            if let Some((key, value)) = slice.arg {
+                write!(w, r#","args":{{"{key}":{value}}}"#)?;
+            }
+            write!(w, "}}")?;
+        }
+        write!(w, r#"],"displayTimeUnit":"ms"}}"#)
+    }
+
+    fn now(&self) -> f64 {
+        self.origin.elapsed().as_secs_f64() * 1e6
+    }
+
+    fn push(&mut self, name: &'static str, ts: f64, end: f64, arg: Option<(&'static str, usize)>) {
+        self.slices.push(Slice {
+            name,
+            ts,
+            dur: end - ts,
+            arg,
+        });
+    }
+
+    /// slice from the end of the last event to now
+    fn event(&mut self, name: &'static str, arg: (&'static str, usize)) {
+        let now = self.now();
+        self.push(name, self.last, now, Some(arg));
+        self.last = now;
+    }
+}
+
+impl Observer for PerfettoObserver {
+    fn mask(&self) -> ObserverMask {
+        // phases are needed for the sweep and finalize slices
+        self.mask | ObserverMask::SWEEP_DONE | ObserverMask::FINALIZED
+    }
+
+    fn point_event(&mut self, point_id: PointId, _context: &Context) {
+        self.event("point", ("point", point_id.as_usize()));
+    }
+
+    fn edge_event(&mut self, edge: Edge, _context: &Context) {
+        self.event("edge", ("p", edge.p.as_usize()));
+    }
+
+    fn sweep_done(&mut self, _context: &Context) {
+        self.sweep_done = self.now();
+        self.push("sweep", 0., self.sweep_done, None);
+    }
+
+    fn finalized(&mut self, _context: &Context) {
+        let now = self.now();
+        self.push("finalize", self.sweep_done, now, None);
+    }
+
+    fn will_legalize(&mut self, _triangle_id: TriangleId, _context: &Context) {
+        self.legalize_start = Some(self.now());
+    }
+
+    fn legalized(&mut self, triangle_id: TriangleId, _context: &Context) {
+        if let Some(start) = self.legalize_start.take() {
+            let now = self.now();
+            self.push(
+                "legalize",
+                start,
+                now,
+                Some(("triangle", triangle_id.as_usize())),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader;
+
+    fn count(json: &str, name: &str) -> usize {
+        json.matches(&format!(r#""name":"{name}""#)).count()
+    }
+
+    #[test]
+    fn test_perfetto_observer() {
+        let builder = loader::load_file("test_data/bird.dat").unwrap();
+        let mut trace = PerfettoObserver::new();
+        builder
+            .clone()
+            .build()
+            .triangulate_with_observer(&mut trace);
+
+        let mut json = vec![];
+        trace.write_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.starts_with(r#"{"traceEvents":[{"#));
+        assert!(json.ends_with(r#"}],"displayTimeUnit":"ms"}"#));
+        assert_eq!((count(&json, "sweep"), count(&json, "finalize")), (1, 1));
+        // bird is a single ring, the lowest point is in the initial triangle
+        let points = count(&json, "point");
+        assert_eq!(points, 274);
+        assert_eq!(count(&json, "edge"), points + 1);
+        assert!(count(&json, "legalize") > 0);
+        assert_eq!(json.matches(r#""ph":"X""#).count(), trace.len());
+
+        // slices other than finalize lie inside the sweep
+        let sweep = trace.slices.iter().find(|s| s.name == "sweep").unwrap();
+        for slice in trace.slices.iter() {
+            assert!(slice.dur >= 0.);
+            if slice.name != "finalize" {
+                assert!(slice.ts + slice.dur <= sweep.dur);
+            }
+        }
+
+        let mut phases = PerfettoObserver::new().with_mask(ObserverMask::NONE);
+        builder.build().triangulate_with_observer(&mut phases);
+        assert_eq!(phases.len(), 2);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::Point;
+
+/// 2D affine transform, `x' = a * x + b * y + tx`, `y' = c * x + d * y + ty`.
+/// See [`crate::SweeperBuilder::with_transform`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub tx: f64,
+    pub ty: f64,
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Affine {
+    pub const IDENTITY: Self = Self::new(1., 0., 0., 1., 0., 0.);
+
+    pub const fn new(a: f64, b: f64, c: f64, d: f64, tx: f64, ty: f64) -> Self {
+        Self { a, b, c, d, tx, ty }
+    }
+
+    pub const fn translate(tx: f64, ty: f64) -> Self {
+        Self::new(1., 0., 0., 1., tx, ty)
+    }
+
+    pub const fn scale(sx: f64, sy: f64) -> Self {
+        Self::new(sx, 0., 0., sy, 0., 0.)
+    }
+
+    /// Rotate ccw by `angle` radians around the origin
+    pub fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(cos, -sin, sin, cos, 0., 0.)
+    }
+
+    /// `self` first, then `next`
+    pub fn then(&self, next: &Self) -> Self {
+        Self::new(
+            next.a * self.a + next.b * self.c,
+            next.a * self.b + next.b * self.d,
+            next.c * self.a + next.d * self.c,
+            next.c * self.b + next.d * self.d,
+            next.a * self.tx + next.b * self.ty + next.tx,
+            next.c * self.tx + next.d * self.ty + next.ty,
+        )
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// `None` if the transform collapses the plane, or isn't finite
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0. || !det.is_finite() {
+            return None;
+        }
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(Self::new(
+            a,
+            b,
+            c,
+            d,
+            -(a * self.tx + b * self.ty),
+            -(c * self.tx + d * self.ty),
+        ))
+    }
+
+    pub fn apply(&self, p: Point) -> Point {
+        Point::new(
+            self.a * p.x + self.b * p.y + self.tx,
+            self.c * p.x + self.d * p.y + self.ty,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_affine() {
+        let transform = Affine::rotate(0.5)
+            .then(&Affine::scale(2., 3.))
+            .then(&Affine::translate(10., -4.));
+        let inverse = transform.inverse().unwrap();
+        for p in [Point::new(0., 0.), Point::new(1., 2.), Point::new(-7., 3.5)] {
+            let q = inverse.apply(transform.apply(p));
+            assert!((q.x - p.x).abs() < 1e-12 && (q.y - p.y).abs() < 1e-12);
+        }
+        let p = Affine::translate(1., 0.)
+            .then(&Affine::scale(2., 1.))
+            .apply(Point::new(1., 1.));
+        assert!(p.eq(&Point::new(4., 1.)));
+        assert!(Affine::scale(1., 0.).inverse().is_none());
+        assert_eq!(Affine::default().inverse(), Some(Affine::IDENTITY));
+    }
+}
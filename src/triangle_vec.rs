@@ -0,0 +1,172 @@
+//! Owned result with random access, for when a single pass over [`Triangles`] is
+//! not enough, e.g. picking or looking up neighbors by index.
+use std::slice;
+
+use crate::{Point, Triangle, Triangles};
+
+/// Owned result mesh, triangles share vertices by index
+#[derive(Debug, Clone, Default)]
+pub struct TriangleVec {
+    vertices: Vec<Point>,
+    indices: Vec<[u32; 3]>,
+}
+
+impl TriangleVec {
+    /// Collect result of `triangles`, regardless of iteration progress
+    pub fn new(triangles: &Triangles) -> Self {
+        let mut flat = vec![0; triangles.index_count()];
+        triangles.write_indices_into(&mut flat);
+        Self {
+            vertices: (0..triangles.vertex_count())
+                .map(|index| triangles.vertex(index))
+                .collect(),
+            indices: flat.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect(),
+        }
+    }
+
+    /// Number of triangles
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Triangle at `index`, in result order
+    pub fn triangle(&self, index: usize) -> Option<Triangle> {
+        self.indices
+            .get(index)
+            .map(|indices| to_triangle(&self.vertices, indices))
+    }
+
+    /// Vertex positions as input, in input order: outer polyline, holes, then
+    /// steiner points
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// Vertex indices of each triangle, counter clockwise
+    pub fn indices(&self) -> &[[u32; 3]] {
+        &self.indices
+    }
+
+    /// Vertex buffer and index buffer, e.g. for GPU upload without a copy.
+    /// Vertices no triangle uses, e.g. steiner points in holes, are kept so
+    /// indices stay the input order.
+    pub fn into_parts(self) -> (Vec<Point>, Vec<[u32; 3]>) {
+        (self.vertices, self.indices)
+    }
+
+    pub fn iter(&self) -> TriangleVecIter<'_> {
+        TriangleVecIter {
+            vertices: &self.vertices,
+            indices: self.indices.iter(),
+        }
+    }
+}
+
+fn to_triangle(vertices: &[Point], indices: &[u32; 3]) -> Triangle {
+    Triangle {
+        points: indices.map(|i| vertices[i as usize]),
+    }
+}
+
+/// Iterator over triangles of a [`TriangleVec`]
+#[derive(Debug, Clone)]
+pub struct TriangleVecIter<'a> {
+    vertices: &'a [Point],
+    indices: slice::Iter<'a, [u32; 3]>,
+}
+
+impl Iterator for TriangleVecIter<'_> {
+    type Item = Triangle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = self.indices.next()?;
+        Some(to_triangle(self.vertices, indices))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for TriangleVecIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let indices = self.indices.next_back()?;
+        Some(to_triangle(self.vertices, indices))
+    }
+}
+
+impl ExactSizeIterator for TriangleVecIter<'_> {}
+
+impl<'a> IntoIterator for &'a TriangleVec {
+    type Item = Triangle;
+    type IntoIter = TriangleVecIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for TriangleVec {
+    type Item = Triangle;
+    type IntoIter = std::vec::IntoIter<Triangle>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter().collect::<Vec<_>>().into_iter()
+    }
+}
+
+impl From<&Triangles> for TriangleVec {
+    fn from(triangles: &Triangles) -> Self {
+        Self::new(triangles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SweeperBuilder;
+
+    #[test]
+    fn test_triangle_vec() {
+        let builder = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ])
+        .add_hole(vec![
+            Point::new(4., 4.),
+            Point::new(4., 6.),
+            Point::new(6., 6.),
+            Point::new(6., 4.),
+        ]);
+        let mut triangles = builder.clone().build().triangulate();
+        // progress of the iterator doesn't matter
+        triangles.next();
+        let mesh = TriangleVec::new(&triangles);
+        assert_eq!(mesh.len(), 8);
+        assert_eq!(mesh.vertices().len(), 8);
+        assert_eq!(mesh.vertices()[1].x, 10.);
+
+        let xy = |t: Triangle| t.points.map(|p| (p.x, p.y));
+        let collected = builder.clone().build().collect_mesh();
+        for (index, triangle) in collected.iter().enumerate() {
+            assert_eq!(xy(triangle), xy(mesh.triangle(index).unwrap()));
+            assert_eq!(xy(triangle), xy(triangles.triangle(index).unwrap()));
+        }
+        assert!(mesh.triangle(8).is_none());
+        assert_eq!(
+            xy(mesh.iter().next_back().unwrap()),
+            xy(mesh.triangle(7).unwrap())
+        );
+        assert_eq!((&mesh).into_iter().len(), 8);
+        let (vertices, indices) = mesh.clone().into_parts();
+        assert_eq!((vertices.len(), indices.as_slice()), (8, mesh.indices()));
+        assert_eq!(builder.build().triangulate_indexed().1, indices);
+        assert_eq!(mesh.into_iter().count(), 8);
+    }
+}
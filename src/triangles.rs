@@ -1,3 +1,4 @@
+use crate::points::Points;
 use crate::shape::InnerTriangle;
 
 #[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -11,11 +12,11 @@ impl TriangleId {
         self.0 == Self::INVALID.0
     }
 
-    pub fn get<'a, 'b>(&'a self, triangles: &'b TriangleStore) -> &'b InnerTriangle {
+    pub fn get<'b>(&self, triangles: &'b TriangleStore) -> &'b InnerTriangle {
         triangles.get_unchecked(*self)
     }
 
-    pub fn try_get<'a, 'b>(&'a self, triangles: &'b TriangleStore) -> Option<&'b InnerTriangle> {
+    pub fn try_get<'b>(&self, triangles: &'b TriangleStore) -> Option<&'b InnerTriangle> {
         triangles.get(*self)
     }
 
@@ -36,6 +37,22 @@ impl TriangleId {
     }
 }
 
+/// Broken link or point found by [`TriangleStore::validate`]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum MeshError {
+    #[error("triangle {0:?} has invalid or repeated points")]
+    Points(TriangleId),
+    #[error("triangle {0:?} has neighbor {1:?} not sharing an edge")]
+    NotAdjacent(TriangleId, TriangleId),
+    #[error("triangle {0:?} has neighbor {1:?} not linking back")]
+    NotMutual(TriangleId, TriangleId),
+    #[error("triangle {0:?} and {1:?} disagree on whether their edge is constrained")]
+    Constrained(TriangleId, TriangleId),
+    /// a vertex moved by [`crate::Triangles::update_positions`] folded it over
+    #[error("triangle {0:?} is inverted")]
+    Inverted(TriangleId),
+}
+
 /// Triangle store, store triangles and their neighborhood relations
 // Note: For n vetexes, there will around n - 2 triangles, so space complexity is
 //       O(n).
@@ -103,6 +120,7 @@ impl TriangleStore {
         (ref_0, ref_1)
     }
 
+    #[allow(clippy::type_complexity)]
     pub(crate) unsafe fn get_mut_six(
         &mut self,
         id_0: TriangleId,
@@ -168,6 +186,87 @@ impl TriangleStore {
             .map(|(idx, t)| (TriangleId::from_index(idx), t))
     }
 
+    /// Drop triangles which are not kept, remaining ones are renumbered in order and
+    /// neighbors pointing to dropped ones become invalid.
+    /// Returns new id for each old id, [`TriangleId::INVALID`] for dropped ones.
+    pub(crate) fn retain(&mut self, keep: &[bool]) -> Vec<TriangleId> {
+        debug_assert_eq!(keep.len(), self.triangles.len());
+        let mut next = 0;
+        let remap = keep
+            .iter()
+            .map(|&keep| {
+                if keep {
+                    next += 1;
+                    TriangleId::from_index(next - 1)
+                } else {
+                    TriangleId::INVALID
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut index = 0;
+        self.triangles.retain(|_| {
+            index += 1;
+            keep[index - 1]
+        });
+        for triangle in self.triangles.iter_mut() {
+            for neighbor in triangle.neighbors.iter_mut() {
+                if !neighbor.invalid() {
+                    *neighbor = remap[neighbor.as_usize()];
+                }
+            }
+        }
+        remap
+    }
+
+    /// clear delaunay flags of all edges
+    pub(crate) fn clear_delaunay_flags(&mut self) {
+        for triangle in self.triangles.iter_mut() {
+            for edge_index in 0..3 {
+                triangle.set_delaunay(edge_index, false);
+            }
+        }
+    }
+
+    /// iter all triangles mutably
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut InnerTriangle> {
+        self.triangles.iter_mut()
+    }
+
+    /// Check every triangle has distinct valid points, and every neighbor link is
+    /// mutual with the same constrained flag on both sides. Returns the first error
+    pub fn validate(&self, points: &Points) -> Result<(), MeshError> {
+        for (triangle_id, triangle) in self.iter() {
+            let [a, b, c] = triangle.points;
+            if a == b
+                || b == c
+                || a == c
+                || triangle
+                    .points
+                    .iter()
+                    .any(|p| points.get_point(*p).is_none())
+            {
+                return Err(MeshError::Points(triangle_id));
+            }
+
+            for (ei, neighbor_id) in triangle.neighbors.iter().enumerate() {
+                let Some(neighbor) = self.get(*neighbor_id) else {
+                    continue;
+                };
+                let Some((l_ei, r_ei)) = triangle.common_edge_index(neighbor) else {
+                    return Err(MeshError::NotAdjacent(triangle_id, *neighbor_id));
+                };
+                if l_ei != ei || neighbor.neighbors[r_ei] != triangle_id {
+                    return Err(MeshError::NotMutual(triangle_id, *neighbor_id));
+                }
+                if triangle.is_constrained(l_ei) != neighbor.is_constrained(r_ei) {
+                    return Err(MeshError::Constrained(triangle_id, *neighbor_id));
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// mark two triangle as neighbor
     pub fn mark_neighbor(&mut self, left: TriangleId, right: TriangleId) {
         let (left_triangle, right_triangle) = unsafe { self.get_mut_two(left, right) };
@@ -182,7 +281,7 @@ impl TriangleStore {
         left_triangle: &mut InnerTriangle,
         right_triangle: &mut InnerTriangle,
     ) {
-        let Some((l_ei, r_ei)) = left_triangle.common_edge_index(&right_triangle) else {
+        let Some((l_ei, r_ei)) = left_triangle.common_edge_index(right_triangle) else {
             debug_assert!(false, "they are not neighbors");
             return;
         };
@@ -202,7 +301,7 @@ impl TriangleStore {
 
 #[cfg(test)]
 mod tests {
-    use crate::{points::PointsBuilder, shape::Point};
+    use crate::{points::PointsBuilder, shape::Point, PointId};
 
     use super::*;
 
@@ -228,6 +327,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate() {
+        let mut triangles = TriangleStore::new();
+        let mut points = PointsBuilder::default();
+
+        let p0 = points.add_steiner_point(Point::new(0., 0.));
+        let p1 = points.add_steiner_point(Point::new(2., 0.));
+        let p2 = points.add_steiner_point(Point::new(1., 2.));
+        let p3 = points.add_steiner_point(Point::new(4., 2.));
+        let points = points.build();
+
+        let t1 = triangles.insert(InnerTriangle::new(p0, p1, p2));
+        let t2 = triangles.insert(InnerTriangle::new(p2, p1, p3));
+        triangles.mark_neighbor(t1, t2);
+        assert_eq!(triangles.validate(&points), Ok(()));
+
+        triangles.get_mut(t1).unwrap().set_constrained(0, true);
+        assert_eq!(
+            triangles.validate(&points),
+            Err(MeshError::Constrained(t1, t2))
+        );
+        triangles.get_mut(t2).unwrap().neighbors[2] = TriangleId::INVALID;
+        assert_eq!(
+            triangles.validate(&points),
+            Err(MeshError::NotMutual(t1, t2))
+        );
+        triangles.get_mut(t1).unwrap().neighbors[0] = TriangleId::INVALID;
+        triangles.get_mut(t2).unwrap().points[0] = PointId::from_usize(100);
+        assert_eq!(triangles.validate(&points), Err(MeshError::Points(t2)));
+    }
+
     #[test]
     fn test_triangles_get_mut_two() {
         let mut triangles = TriangleStore::new();
@@ -0,0 +1,185 @@
+use crate::{
+    points::Points, Context, FrontBackend, Observer, Point, PointId, SweepConfig, SweeperBuilder,
+};
+
+/// trials smaller than this say nothing about the full run
+const MIN_SAMPLE: usize = 64;
+
+/// estimated front length where [`FrontBackend::Chunked`] takes over. Vec wins on
+/// all bundled datasets, its memmove only dominates on very wide fronts.
+const CHUNKED_FRONT_LEN: usize = 1 << 15;
+
+/// estimated front length where [`FrontBackend::BTree`] takes over
+const BTREE_FRONT_LEN: usize = 1 << 18;
+
+/// Collects front statistics during a trial sweep
+#[derive(Default)]
+struct FrontProbe {
+    /// max front length seen
+    max_front: usize,
+    /// total front length change, counts both inserts and deletes
+    churn: usize,
+    last_front: usize,
+    triangles: usize,
+}
+
+impl Observer for FrontProbe {
+    fn point_event(&mut self, _point_id: PointId, context: &Context) {
+        let len = context.advancing_front.len();
+        self.max_front = self.max_front.max(len);
+        self.churn += len.abs_diff(self.last_front);
+        self.last_front = len;
+    }
+
+    fn finalized(&mut self, context: &Context) {
+        self.triangles = context.triangles.len();
+    }
+}
+
+/// Result of one trial sweep
+#[derive(Debug, Clone, Copy)]
+struct Trial {
+    points: usize,
+    max_front: usize,
+    churn: usize,
+    triangles: usize,
+}
+
+/// Run a sweep over every `n`th point by y, edges are dropped, the points are
+/// wrapped by their bounding box.
+fn run_trial(points: &Points, sample: usize) -> Trial {
+    let total = points.len() - 2;
+    let step = total.div_ceil(sample).max(1);
+    let sampled = points
+        .iter_point_by_y(0)
+        .step_by(step)
+        .map(|(_, point, _)| point)
+        .collect::<Vec<_>>();
+
+    let (mut xmin, mut ymin, mut xmax, mut ymax) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for p in sampled.iter() {
+        xmin = xmin.min(p.x);
+        ymin = ymin.min(p.y);
+        xmax = xmax.max(p.x);
+        ymax = ymax.max(p.y);
+    }
+    let margin = ((xmax - xmin) + (ymax - ymin)) * 0.01 + 1.;
+    let (xmin, ymin, xmax, ymax) = (xmin - margin, ymin - margin, xmax + margin, ymax + margin);
+
+    let mut probe = FrontProbe::default();
+    let point_count = sampled.len();
+    // bottom edge is tilted, a level one is cocircular with the artificial head and tail
+    let _ = SweeperBuilder::new(vec![
+        Point::new(xmin, ymin - margin),
+        Point::new(xmax, ymin),
+        Point::new(xmax, ymax),
+        Point::new(xmin, ymax),
+    ])
+    .add_steiner_points(sampled)
+    .with_config(SweepConfig::default().front_backend(FrontBackend::Vec))
+    .build()
+    .triangulate_with_observer(&mut probe);
+
+    Trial {
+        points: point_count,
+        max_front: probe.max_front,
+        churn: probe.churn,
+        triangles: probe.triangles,
+    }
+}
+
+/// Pick backend and capacities for sweeping `points`, based on trials over `sample` points.
+/// Returns `None` when points or sample are too few to tell.
+pub(crate) fn tune(points: &Points, sample: usize) -> Option<SweepConfig> {
+    let total = points.len() - 2;
+    if total < MIN_SAMPLE || sample < MIN_SAMPLE {
+        return None;
+    }
+    let sample = sample.min(total);
+
+    // two trials at different sizes tell how the front grows with point count
+    let small = run_trial(points, sample / 2);
+    let large = run_trial(points, sample);
+
+    let front = extrapolate(
+        small.max_front,
+        small.points,
+        large.max_front,
+        large.points,
+        total,
+    );
+    // churn per point tells how hard the front is rewritten, a front which keeps
+    // shifting pays the insert/delete cost more often
+    let churn_per_point = large.churn as f64 / large.points as f64;
+    let weighted_front = (front as f64 * churn_per_point.max(1.)) as usize;
+
+    let front_backend = if weighted_front >= BTREE_FRONT_LEN {
+        FrontBackend::BTree
+    } else if weighted_front >= CHUNKED_FRONT_LEN {
+        FrontBackend::Chunked
+    } else {
+        FrontBackend::Vec
+    };
+
+    let triangles_per_point = large.triangles as f64 / large.points as f64;
+    Some(
+        SweepConfig::default()
+            .front_backend(front_backend)
+            .front_capacity(front + front / 4)
+            .triangle_capacity((triangles_per_point * total as f64 * 1.1) as usize),
+    )
+}
+
+/// Extrapolate the value measured at two sizes to `target` size, assuming
+/// `value ~ size ^ e` with e in [0, 1]
+fn extrapolate(
+    small: usize,
+    small_size: usize,
+    large: usize,
+    large_size: usize,
+    target: usize,
+) -> usize {
+    let exp = if large > small && large_size > small_size {
+        ((large as f64 / small as f64).ln() / (large_size as f64 / small_size as f64).ln())
+            .clamp(0., 1.)
+    } else {
+        0.
+    };
+    (large as f64 * (target as f64 / large_size as f64).powf(exp)).ceil() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::points::PointsBuilder;
+
+    fn random_points(count: usize, width: f64, height: f64) -> Points {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut builder = PointsBuilder::with_capacity(count);
+        builder.add_steiner_points(
+            (0..count).map(|_| Point::new(rng.gen_range(0.0..width), rng.gen_range(0.0..height))),
+        );
+        builder.build()
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // linear growth
+        assert_eq!(extrapolate(50, 100, 100, 200, 2000), 1000);
+        // no growth
+        assert_eq!(extrapolate(100, 100, 100, 200, 2000), 100);
+    }
+
+    #[test]
+    fn test_tune() {
+        assert!(tune(&random_points(10, 100., 100.), 1000).is_none());
+
+        let config = tune(&random_points(4000, 1000., 1000.), 400).unwrap();
+        let front_capacity = config.front_capacity.unwrap();
+        assert!(front_capacity > 3 && front_capacity <= 4000);
+        assert!(config.triangle_capacity.unwrap() >= 4000);
+        assert_eq!(config.front_backend, FrontBackend::Vec);
+    }
+}
@@ -1,6 +1,8 @@
 use crate::shape::Point;
+use crate::Predicates;
 
 #[derive(Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum Orientation {
     /// Clock Wise
     ///     
@@ -29,60 +31,172 @@ impl Orientation {
     pub fn is_collinear(&self) -> bool {
         matches!(self, Self::Collinear)
     }
+
+    /// by sign of an orientation determinant, positive is ccw
+    fn of(val: f64) -> Self {
+        if val > 0. {
+            Self::CCW
+        } else if val < 0. {
+            Self::CW
+        } else {
+            Self::Collinear
+        }
+    }
 }
 
 pub fn orient_2d(a: Point, b: Point, c: Point) -> Orientation {
     debug_assert!(!a.eq(&b) && !a.eq(&c) && !b.eq(&c), "orient_2d point same");
 
-    let detleft = (a.x - c.x) * (b.y - c.y);
-    let detright = (a.y - c.y) * (b.x - c.x);
-    let val = detleft - detright;
+    #[cfg(feature = "exact_orient")]
+    let val = orient(a, b, c);
+    #[cfg(not(feature = "exact_orient"))]
+    let val = (a.x - c.x) * (b.y - c.y) - (a.y - c.y) * (b.x - c.x);
 
-    if val > 0. {
-        Orientation::CCW
-    } else if val < 0. {
-        Orientation::CW
-    } else {
-        Orientation::Collinear
+    Orientation::of(val)
+}
+
+impl Predicates {
+    /// [`orient_2d`] in this arithmetic
+    pub(crate) fn orient_2d(self, a: Point, b: Point, c: Point) -> Orientation {
+        match self {
+            Self::Fast => orient_2d(a, b, c),
+            Self::Adaptive => Orientation::of(orient(a, b, c)),
+        }
     }
 }
 
-/// check whether pd is in circle defined by pa, pb, pc
-/// requirements: pa is known to be opposite side with pd.
-pub fn in_circle(pa: Point, pb: Point, pc: Point, pd: Point) -> bool {
-    let adx = pa.x - pd.x;
-    let ady = pa.y - pd.y;
-    let bdx = pb.x - pd.x;
-    let bdy = pb.y - pd.y;
+/// In circle determinant with pd as origin, and bound of its rounding error
+struct InCircle {
+    det: f64,
+    permanent: f64,
+}
 
-    let adxbdy = adx * bdy;
-    let bdxady = bdx * ady;
-    let oabd = adxbdy - bdxady;
+impl InCircle {
+    fn new(pa: Point, pb: Point, pc: Point, pd: Point) -> Self {
+        let adx = pa.x - pd.x;
+        let ady = pa.y - pd.y;
+        let bdx = pb.x - pd.x;
+        let bdy = pb.y - pd.y;
+        let cdx = pc.x - pd.x;
+        let cdy = pc.y - pd.y;
+
+        let bdxcdy = bdx * cdy;
+        let cdxbdy = cdx * bdy;
+        let cdxady = cdx * ady;
+        let adxcdy = adx * cdy;
+        let adxbdy = adx * bdy;
+        let bdxady = bdx * ady;
+
+        let alift = adx * adx + ady * ady;
+        let blift = bdx * bdx + bdy * bdy;
+        let clift = cdx * cdx + cdy * cdy;
+
+        let det = alift * (bdxcdy - cdxbdy) + blift * (cdxady - adxcdy) + clift * (adxbdy - bdxady);
+        let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift
+            + (cdxady.abs() + adxcdy.abs()) * blift
+            + (adxbdy.abs() + bdxady.abs()) * clift;
+
+        Self { det, permanent }
+    }
 
-    if oabd <= 0. {
-        return false;
+    /// error bound from Shewchuk's incircle filter, with some headroom
+    fn is_tie(&self) -> bool {
+        self.det.abs() <= self.permanent * 16. * f64::EPSILON
     }
+}
+
+/// whether pa, pb, pd and pc, pa, pd are both ccw, otherwise the edge between pb
+/// and pc is not flippable
+pub(crate) fn flippable(pa: Point, pb: Point, pc: Point, pd: Point) -> bool {
+    let adx = pa.x - pd.x;
+    let ady = pa.y - pd.y;
+    let oabd = adx * (pb.y - pd.y) - (pb.x - pd.x) * ady;
+    let ocad = (pc.x - pd.x) * ady - adx * (pc.y - pd.y);
+    oabd > 0. && ocad > 0.
+}
 
-    let cdx = pc.x - pd.x;
-    let cdy = pc.y - pd.y;
+pub(crate) fn coord(p: Point) -> robust::Coord<f64> {
+    robust::Coord { x: p.x, y: p.y }
+}
 
-    let cdxady = cdx * ady;
-    let adxcdy = adx * cdy;
-    let ocad = cdxady - adxcdy;
+/// Orientation determinant, or a value of the same sign, positive when ccw. With
+/// the `exact_orient` feature, exact even where the adaptive predicate under or
+/// overflows
+pub(crate) fn orient(a: Point, b: Point, c: Point) -> f64 {
+    #[cfg(feature = "exact_orient")]
+    return crate::exact::orient2d(a, b, c);
+    #[cfg(not(feature = "exact_orient"))]
+    robust::orient2d(coord(a), coord(b), coord(c))
+}
 
-    if ocad <= 0. {
+/// check whether pd is in circle defined by pa, pb, pc
+/// requirements: pa is known to be opposite side with pd.
+///
+/// Near the circle the sign is computed exactly. Exact ties are broken by
+/// Simulation of Simplicity: each point's lift `x² + y²` is raised by an
+/// infinitesimal, larger for smaller `ids`, and the first point, by id, whose lift
+/// changes the determinant decides. So cocircular points, e.g. grids and lattices,
+/// always take the same branch, and a flipped edge is never illegal again.
+pub fn in_circle(pa: Point, pb: Point, pc: Point, pd: Point, ids: [usize; 4]) -> bool {
+    if !flippable(pa, pb, pc, pd) {
         return false;
     }
+    let in_circle = InCircle::new(pa, pb, pc, pd);
+    if !in_circle.is_tie() {
+        return in_circle.det > 0.;
+    }
 
-    let bdxcdy = bdx * cdy;
-    let cdxbdy = cdx * bdy;
+    let det = robust::incircle(coord(pa), coord(pb), coord(pc), coord(pd));
+    if det != 0. {
+        return det > 0.;
+    }
 
-    let alift = adx * adx + ady * ady;
-    let blift = bdx * bdx + bdy * bdy;
-    let clift = cdx * cdx + cdy * cdy;
+    // derivative of the determinant by each point's lift, raising pd's lift lowers
+    // the other three relative to it
+    let lift_terms = [
+        orient(pb, pc, pd),
+        orient(pc, pa, pd),
+        orient(pa, pb, pd),
+        -orient(pa, pb, pc),
+    ];
+    let mut order = [0, 1, 2, 3];
+    order.sort_unstable_by_key(|idx| ids[*idx]);
+    order
+        .into_iter()
+        .map(|idx| lift_terms[idx])
+        .find(|term| *term != 0.)
+        .is_some_and(|term| term > 0.)
+}
 
-    let det = alift * (bdxcdy - cdxbdy) + blift * ocad + clift * oabd;
+/// same as [`in_circle`], but pd within rounding error of the circle is treated as
+/// on the circle, i.e, not inside. Used to verify result, where cocircular points
+/// may go either way.
+pub fn in_circle_tolerant(pa: Point, pb: Point, pc: Point, pd: Point) -> bool {
+    if !flippable(pa, pb, pc, pd) {
+        return false;
+    }
+    let in_circle = InCircle::new(pa, pb, pc, pd);
+    in_circle.det > 0. && !in_circle.is_tie()
+}
 
+/// same as [`in_circle`], but each point's lift `x² + y²` is lowered by its weight,
+/// i.e. pd has negative power distance to the circle orthogonal to the weighted
+/// pa, pb, pc. All weights 0 is the same as [`in_circle`], exact ties are not flipped.
+pub fn in_power_circle(pa: Point, pb: Point, pc: Point, pd: Point, weights: [f64; 4]) -> bool {
+    if !flippable(pa, pb, pc, pd) {
+        return false;
+    }
+    let lift = |p: Point, weight: f64| {
+        let (dx, dy) = (p.x - pd.x, p.y - pd.y);
+        (dx, dy, dx * dx + dy * dy - weight + weights[3])
+    };
+    let (adx, ady, alift) = lift(pa, weights[0]);
+    let (bdx, bdy, blift) = lift(pb, weights[1]);
+    let (cdx, cdy, clift) = lift(pc, weights[2]);
+
+    let det = alift * (bdx * cdy - cdx * bdy)
+        + blift * (cdx * ady - adx * cdy)
+        + clift * (adx * bdy - bdx * ady);
     det > 0.
 }
 
@@ -100,6 +214,77 @@ pub fn in_scan_area(a: Point, b: Point, c: Point, d: Point) -> bool {
     true
 }
 
+/// Whether all points lie on one line, exactly. Less than 3 distinct points are
+/// collinear too
+pub(crate) fn is_collinear(points: &[Point]) -> bool {
+    let Some(first) = points.first() else {
+        return true;
+    };
+    let Some(second) = points.iter().find(|p| !p.eq(first)) else {
+        return true;
+    };
+    points.iter().all(|p| orient(*first, *second, *p) == 0.)
+}
+
+/// Hash key of a point's exact position
+pub(crate) fn point_key(p: Point) -> (u64, u64) {
+    // -0. and 0. are the same point
+    ((p.x + 0.).to_bits(), (p.y + 0.).to_bits())
+}
+
+/// Signed area of a ring, positive when ccw
+pub(crate) fn signed_area(ring: &[Point]) -> f64 {
+    (0..ring.len())
+        .map(|i| {
+            let (p, q) = (ring[i], ring[(i + 1) % ring.len()]);
+            p.x * q.y - q.x * p.y
+        })
+        .sum::<f64>()
+        / 2.
+}
+
+/// Whether `p` is inside `ring` by even odd rule, `None` when exactly on it
+pub(crate) fn point_in_ring(ring: &[Point], p: Point) -> Option<bool> {
+    let mut inside = false;
+    for i in 0..ring.len() {
+        let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+        if a.eq(&p) || b.eq(&p) {
+            return None;
+        }
+        let within_x = a.x.min(b.x) <= p.x && p.x <= a.x.max(b.x);
+        let within_y = a.y.min(b.y) <= p.y && p.y <= a.y.max(b.y);
+        let side = orient(a, b, p);
+        if side == 0. && within_x && within_y {
+            return None;
+        }
+        // edge crosses the ray going right from p
+        if (a.y > p.y) != (b.y > p.y) && (side > 0.) == (b.y > a.y) {
+            inside = !inside;
+        }
+    }
+    Some(inside)
+}
+
+/// drop repeated points, spikes and the closing point
+pub(crate) fn dedup_ring(ring: Vec<Point>) -> Vec<Point> {
+    let mut result = Vec::<Point>::with_capacity(ring.len());
+    for p in ring {
+        if result.last().map(|l| l.eq(&p)).unwrap_or(false) {
+            continue;
+        }
+        // a -> b -> a spike
+        if result.len() >= 2 && result[result.len() - 2].eq(&p) {
+            result.pop();
+            continue;
+        }
+        result.push(p);
+    }
+    while result.len() > 1 && result[0].eq(result.last().unwrap()) {
+        result.pop();
+    }
+    result
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Angle {
     dy: f64,
@@ -127,9 +312,7 @@ impl Angle {
         // * `y >= 0`: `arctan(y/x) + pi` -> `(pi/2, pi]`
         // * `y < 0`: `arctan(y/x) - pi` -> `(-pi, -pi/2)`
 
-        if self.dx == 0. && self.dy == 0. {
-            false
-        } else if self.dx >= 0. {
+        if (self.dx == 0. && self.dy == 0.) || self.dx >= 0. {
             false
         } else {
             // self.dx < 0.
@@ -141,8 +324,6 @@ impl Angle {
     pub fn is_negative(&self) -> bool {
         if self.dx == 0. && self.dy == 0. {
             false
-        } else if self.dx >= 0. {
-            self.dy < 0.
         } else {
             self.dy < 0.
         }
@@ -158,7 +339,34 @@ mod tests {
         let pa = Point::new(0., 0.);
         let pb = Point::new(2., 0.);
         let pc = Point::new(1., 1.);
-        assert!(in_circle(pa, pb, pc, Point::new(1.5, 0.6)));
+        assert!(in_circle(pa, pb, pc, Point::new(1.5, 0.6), [0, 1, 2, 3]));
+        assert!(in_circle_tolerant(pa, pb, pc, Point::new(1.5, 0.6)));
+
+        // cocircular trapezoid, the raw determinant says inside due to rounding
+        let (pa, pb, pc, pd) = (
+            Point::new(4., 1.),
+            Point::new(3.4, 0.),
+            Point::new(4.5, 1.),
+            Point::new(5.1, 0.),
+        );
+        assert!(!in_circle_tolerant(pa, pb, pc, pd));
+        // quad is pa, pb, pd, pc in ccw, exactly one diagonal is legal whatever the ids
+        for ids in [[0, 1, 2, 3], [3, 2, 1, 0], [1, 3, 0, 2], [2, 0, 3, 1]] {
+            let [a, b, c, d] = ids;
+            assert_ne!(
+                in_circle(pa, pb, pc, pd, ids),
+                in_circle(pb, pd, pa, pc, [b, d, a, c])
+            );
+        }
+
+        // square, the tie goes the same way for both diagonals checked from either side
+        let (pa, pb, pc, pd) = (
+            Point::new(0., 1.),
+            Point::new(0., 0.),
+            Point::new(1., 1.),
+            Point::new(1., 0.),
+        );
+        assert!(in_circle(pa, pb, pc, pd, [0, 1, 2, 3]) == in_circle(pd, pc, pb, pa, [3, 2, 1, 0]));
     }
 
     #[test]
@@ -184,6 +392,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_predicates() {
+        // points a few ulps off the line y = x, exactly ccw above it and cw below
+        let (q, r) = (Point::new(12., 12.), Point::new(24., 24.));
+        let ulp = 0.5f64.next_up() - 0.5;
+        let mut misjudged = 0;
+        for i in 0..64 {
+            for j in 0..64 {
+                let p = Point::new(0.5 + i as f64 * ulp, 0.5 + j as f64 * ulp);
+                let expected = Orientation::of(j as f64 - i as f64);
+                assert_eq!(Predicates::Adaptive.orient_2d(p, q, r), expected);
+                misjudged += (Predicates::Fast.orient_2d(p, q, r) != expected) as usize;
+            }
+        }
+        if cfg!(feature = "exact_orient") {
+            assert_eq!(misjudged, 0);
+        } else {
+            assert!(misjudged > 0);
+        }
+    }
+
     #[test]
     fn test_angle() {
         let angle = Angle::new(Point::new(0., 0.), Point::new(1., 0.), Point::new(1., 1.));
@@ -0,0 +1,193 @@
+//! Input checks for [`crate::SweeperBuilder::try_build`], all problems are collected
+//! into one [`InputReport`].
+use std::collections::HashMap;
+
+use crate::points::PointsBuilder;
+use crate::repair::intersecting_segments;
+use crate::utils::{point_in_ring, point_key};
+use crate::{InputEdgeId, Point, PointId};
+
+/// Coordinates above this may overflow the in circle determinant, 4th degree in
+/// coordinates, see [`crate::SweepConfig::auto_rescale`]
+pub(crate) const MAX_SAFE_MAGNITUDE: f64 = 1e60;
+
+/// A problem found in input. Point ids are the same as indices of
+/// [`crate::Triangles::write_indices_into`]
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum InputError {
+    /// the outer polyline has less than 3 points, e.g. a builder left empty
+    #[error("outer polyline has {0} points, at least 3 are needed")]
+    Empty(usize),
+    /// point ids
+    #[error("points {0:?} have NaN or infinite coordinates")]
+    NonFinite(Vec<usize>),
+    /// coordinates are large enough to overflow predicates, and auto rescale is
+    /// disabled
+    #[error("coordinate magnitude {magnitude:e} is above {limit:e}, predicates may overflow")]
+    Overflow { magnitude: f64, limit: f64 },
+    /// point id pairs at the same position, earlier one first, e.g. a closing point
+    #[error("points {0:?} are duplicates")]
+    Duplicates(Vec<(usize, usize)>),
+    /// edge pairs which cross or overlap, or where one ends inside the other
+    #[error("edges {0:?} intersect")]
+    Intersections(Vec<(InputEdgeId, InputEdgeId)>),
+    /// ring indices of holes with a vertex outside the outer polyline
+    #[error("holes {0:?} are not inside the outer polyline")]
+    HolesOutside(Vec<usize>),
+}
+
+/// All problems found in input, see [`crate::SweeperBuilder::try_build`]
+#[derive(thiserror::Error, Debug, Clone, Default, PartialEq)]
+#[error("invalid input: {}", .problems.iter().map(|p| p.to_string()).collect::<Vec<_>>().join("; "))]
+pub struct InputReport {
+    pub problems: Vec<InputError>,
+}
+
+/// Check points and rings, the first ring is the outer. Edges and holes with non
+/// finite points are not checked further
+pub(crate) fn validate(
+    points_builder: &PointsBuilder,
+    rings: &[Vec<PointId>],
+    auto_rescale: bool,
+) -> InputReport {
+    let mut problems = vec![];
+    let outer_len = rings.first().map_or(0, Vec::len);
+    if outer_len < 3 {
+        problems.push(InputError::Empty(outer_len));
+    }
+    let points = (0..points_builder.len())
+        .map(|idx| points_builder.get_point(PointId::from_usize(idx)))
+        .collect::<Vec<_>>();
+    let is_finite = |p: &Point| p.x.is_finite() && p.y.is_finite();
+
+    let non_finite = (0..points.len())
+        .filter(|idx| !is_finite(&points[*idx]))
+        .collect::<Vec<_>>();
+    if !non_finite.is_empty() {
+        problems.push(InputError::NonFinite(non_finite));
+    }
+
+    let magnitude = points_builder.max_magnitude();
+    if !auto_rescale && magnitude > MAX_SAFE_MAGNITUDE {
+        problems.push(InputError::Overflow {
+            magnitude,
+            limit: MAX_SAFE_MAGNITUDE,
+        });
+    }
+
+    let mut first_ids = HashMap::<(u64, u64), usize>::new();
+    let duplicates = points
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| is_finite(p))
+        .filter_map(|(idx, p)| {
+            let first = *first_ids.entry(point_key(*p)).or_insert(idx);
+            (first != idx).then_some((first, idx))
+        })
+        .collect::<Vec<_>>();
+    if !duplicates.is_empty() {
+        problems.push(InputError::Duplicates(duplicates));
+    }
+
+    // rings with non finite points are left empty, ring indices are kept
+    let ring_points = rings
+        .iter()
+        .map(|ring| {
+            let ring = ring
+                .iter()
+                .map(|id| points[id.as_usize()])
+                .collect::<Vec<_>>();
+            match ring.iter().all(is_finite) {
+                true => ring,
+                false => vec![],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let intersections = intersecting_segments(&ring_points)
+        .into_iter()
+        .map(|((r0, i0), (r1, i1))| {
+            (
+                InputEdgeId {
+                    ring: r0,
+                    index: i0,
+                },
+                InputEdgeId {
+                    ring: r1,
+                    index: i1,
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    if !intersections.is_empty() {
+        problems.push(InputError::Intersections(intersections));
+    }
+
+    if let Some(outer) = ring_points.first().filter(|outer| outer.len() >= 3) {
+        let holes_outside = (1..ring_points.len())
+            .filter(|r| ring_points[*r].iter().any(|p| is_outside(outer, *p)))
+            .collect::<Vec<_>>();
+        if !holes_outside.is_empty() {
+            problems.push(InputError::HolesOutside(holes_outside));
+        }
+    }
+
+    InputReport { problems }
+}
+
+/// Whether `p` is strictly outside `ring`, points on it are not
+fn is_outside(ring: &[Point], p: Point) -> bool {
+    point_in_ring(ring, p) == Some(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SweeperBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_input_report() {
+        let report = SweeperBuilder::new(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+            // edge 3 crosses edge 0, then a closing point
+            Point::new(5., -2.),
+            Point::new(0., 0.),
+        ])
+        .add_steiner_points([
+            Point::new(5., 5.),
+            Point::new(5., 5.),
+            Point::new(f64::NAN, 1.),
+        ])
+        // outside the outer polyline
+        .add_hole(vec![
+            Point::new(20., 20.),
+            Point::new(22., 20.),
+            Point::new(22., 22.),
+        ])
+        // touches the outer polyline at a vertex, it is fine
+        .add_hole(vec![
+            Point::new(10., 10.),
+            Point::new(8., 9.),
+            Point::new(9., 8.),
+        ])
+        .try_build()
+        .err()
+        .unwrap();
+
+        let edge = |ring, index| InputEdgeId { ring, index };
+        assert_eq!(
+            report.problems,
+            vec![
+                InputError::NonFinite(vec![8]),
+                InputError::Duplicates(vec![(0, 5), (6, 7)]),
+                InputError::Intersections(vec![(edge(0, 0), edge(0, 3))]),
+                InputError::HolesOutside(vec![1]),
+            ]
+        );
+        assert!(report.to_string().starts_with("invalid input: points [8]"));
+    }
+}
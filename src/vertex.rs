@@ -0,0 +1,228 @@
+use crate::sweeper::Triangles;
+
+/// Position attribute format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PositionFormat {
+    /// `x, y` as f32
+    #[default]
+    F32x2,
+    /// `x, y, 0.` as f32
+    F32x3,
+}
+
+impl PositionFormat {
+    fn size(&self) -> usize {
+        match self {
+            PositionFormat::F32x2 => 8,
+            PositionFormat::F32x3 => 12,
+        }
+    }
+}
+
+/// Byte offsets of each attribute inside one vertex
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VertexLayout {
+    /// bytes per vertex
+    pub stride: usize,
+    pub position: usize,
+    pub position_format: PositionFormat,
+    /// `u, v` as f32
+    pub uv: Option<usize>,
+    /// `r, g, b, a` as unorm u8
+    pub color: Option<usize>,
+}
+
+/// Interleaved vertex bytes, little endian, with the matching index buffer
+#[derive(Debug, Clone)]
+pub struct VertexBuffers {
+    pub layout: VertexLayout,
+    pub vertices: Vec<u8>,
+    pub indices: Vec<u32>,
+}
+
+impl VertexBuffers {
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len() / self.layout.stride
+    }
+}
+
+/// Writes triangulation result into interleaved vertex buffer, attributes are laid
+/// out in order: position, uv, color, padding.
+///
+/// # Example
+/// ```rust
+///    use poly2tri_rs::{Point, PositionFormat, SweeperBuilder, VertexWriter};
+///
+///    let triangles = SweeperBuilder::new(vec![
+///        Point::new(0., 0.),
+///        Point::new(2., 0.),
+///        Point::new(2., 2.),
+///        Point::new(0., 2.),
+///    ])
+///    .build()
+///    .triangulate();
+///    let buffers = VertexWriter::new()
+///        .position(PositionFormat::F32x3)
+///        .uv(true)
+///        .color([255, 0, 0, 255])
+///        .write(&triangles);
+///    assert_eq!(buffers.layout.stride, 24);
+///    assert_eq!(buffers.vertex_count(), 4);
+///    assert_eq!(buffers.indices.len(), 6);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VertexWriter {
+    position: PositionFormat,
+    uv: bool,
+    color: Option<[u8; 4]>,
+    padding: usize,
+}
+
+impl VertexWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn position(mut self, format: PositionFormat) -> Self {
+        self.position = format;
+        self
+    }
+
+    /// Emit uv, positions mapped to [0, 1] over the bounding box
+    pub fn uv(mut self, uv: bool) -> Self {
+        self.uv = uv;
+        self
+    }
+
+    /// Emit the color for every vertex
+    pub fn color(mut self, rgba: [u8; 4]) -> Self {
+        self.color = Some(rgba);
+        self
+    }
+
+    /// Zero bytes appended to each vertex, e.g. to reach a required stride
+    pub fn padding(mut self, bytes: usize) -> Self {
+        self.padding = bytes;
+        self
+    }
+
+    pub fn layout(&self) -> VertexLayout {
+        let position = 0;
+        let mut stride = self.position.size();
+        let uv = self.uv.then(|| {
+            stride += 8;
+            stride - 8
+        });
+        let color = self.color.map(|_| {
+            stride += 4;
+            stride - 4
+        });
+        VertexLayout {
+            stride: stride + self.padding,
+            position,
+            position_format: self.position,
+            uv,
+            color,
+        }
+    }
+
+    pub fn write(&self, triangles: &Triangles) -> VertexBuffers {
+        let layout = self.layout();
+
+        let mut positions = vec![0f32; triangles.vertex_count() * 2];
+        triangles.write_positions_into(&mut positions);
+        let mut indices = vec![0u32; triangles.index_count()];
+        triangles.write_indices_into(&mut indices);
+
+        let (mut min, mut max) = ([f32::MAX; 2], [f32::MIN; 2]);
+        for xy in positions.chunks_exact(2) {
+            for i in 0..2 {
+                min[i] = min[i].min(xy[i]);
+                max[i] = max[i].max(xy[i]);
+            }
+        }
+        let uv_scale = [0, 1].map(|i| {
+            let extent = max[i] - min[i];
+            if extent > 0. {
+                1. / extent
+            } else {
+                0.
+            }
+        });
+
+        let mut vertices = Vec::with_capacity(layout.stride * positions.len() / 2);
+        for xy in positions.chunks_exact(2) {
+            vertices.extend_from_slice(&xy[0].to_le_bytes());
+            vertices.extend_from_slice(&xy[1].to_le_bytes());
+            if self.position == PositionFormat::F32x3 {
+                vertices.extend_from_slice(&0f32.to_le_bytes());
+            }
+            if self.uv {
+                for i in 0..2 {
+                    let uv = (xy[i] - min[i]) * uv_scale[i];
+                    vertices.extend_from_slice(&uv.to_le_bytes());
+                }
+            }
+            if let Some(color) = self.color {
+                vertices.extend_from_slice(&color);
+            }
+            vertices.resize(vertices.len() + self.padding, 0);
+        }
+
+        VertexBuffers {
+            layout,
+            vertices,
+            indices,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Point, SweeperBuilder};
+
+    fn f32_at(bytes: &[u8], offset: usize) -> f32 {
+        f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_vertex_writer() {
+        let triangles = SweeperBuilder::new(vec![
+            Point::new(1., 1.),
+            Point::new(3., 1.),
+            Point::new(3., 5.),
+            Point::new(1., 5.),
+        ])
+        .build()
+        .triangulate();
+
+        let plain = VertexWriter::new().write(&triangles);
+        assert_eq!(plain.layout.stride, 8);
+        assert_eq!(plain.vertices.len(), 32);
+        assert_eq!(f32_at(&plain.vertices, 8), 3.);
+
+        let writer = VertexWriter::new()
+            .position(PositionFormat::F32x3)
+            .uv(true)
+            .color([1, 2, 3, 4])
+            .padding(4);
+        let layout = writer.layout();
+        assert_eq!(layout.uv, Some(12));
+        assert_eq!(layout.color, Some(20));
+        assert_eq!(layout.stride, 28);
+
+        let buffers = writer.write(&triangles);
+        assert_eq!(buffers.vertex_count(), 4);
+        assert_eq!(buffers.indices.len(), 6);
+        // third vertex (3, 5) is the max corner
+        let vertex = &buffers.vertices[layout.stride * 2..layout.stride * 3];
+        assert_eq!(f32_at(vertex, 0), 3.);
+        assert_eq!(f32_at(vertex, 4), 5.);
+        assert_eq!(f32_at(vertex, 8), 0.);
+        assert_eq!(f32_at(vertex, 12), 1.);
+        assert_eq!(f32_at(vertex, 16), 1.);
+        assert_eq!(&vertex[20..24], &[1, 2, 3, 4]);
+        assert_eq!(&vertex[24..28], &[0, 0, 0, 0]);
+    }
+}
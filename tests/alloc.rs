@@ -0,0 +1,89 @@
+//! Point and edge events don't allocate once warm, checked by a counting
+//! global allocator, in its own test binary so other tests don't run under it.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::f64::consts::TAU;
+
+use poly2tri_rs::{stages, FrontBackend, Point, SweepConfig, SweeperBuilder};
+use rand::{Rng, SeedableRng};
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// counts allocations of the current thread, tests run on their own threads
+struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static COUNTING: Counting = Counting;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|count| count.get())
+}
+
+/// random steiner points in a square, point events and legalization
+fn uniform(count: usize) -> SweeperBuilder {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+    let points = (0..count)
+        .map(|_| Point::new(rng.gen_range(0. ..1000.), rng.gen_range(0. ..1000.)))
+        .collect::<Vec<_>>();
+    SweeperBuilder::new(vec![
+        Point::new(-10., -10.),
+        Point::new(1010., -10.),
+        Point::new(1010., 1010.),
+        Point::new(-10., 1010.),
+    ])
+    .add_steiner_points(points)
+}
+
+/// star shaped outline with a noisy radius, edge events and front fills
+fn contour(count: usize) -> SweeperBuilder {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+    let points = (0..count)
+        .map(|i| {
+            let angle = i as f64 * TAU / count as f64;
+            let radius = 100. * (1. + 0.3 * (angle * 7.).sin() + 0.2 * rng.gen::<f64>());
+            Point::new(radius * angle.cos(), radius * angle.sin())
+        })
+        .collect();
+    SweeperBuilder::new(points)
+}
+
+#[test]
+fn test_alloc_counter() {
+    for builder in [uniform(2000), contour(2000)] {
+        let config = SweepConfig::default()
+            .front_backend(FrontBackend::Vec)
+            .front_capacity(4096);
+        let sweeper = builder.with_config(config).build();
+        sweeper.triangulate_with_stages(|context| {
+            let len = context.points.len() - 2;
+            let mut warm = 0;
+            for order in 1..len {
+                // first half grows the reused buffers
+                if order == len / 2 {
+                    warm = allocations();
+                }
+                let point_id = context.points.get_id_by_y(order).unwrap();
+                stages::sweep_point(point_id, context, &mut ());
+            }
+            assert_eq!(allocations(), warm);
+        });
+    }
+}